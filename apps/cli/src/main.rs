@@ -13,17 +13,59 @@ struct Args {
     asm_out: Option<PathBuf>,
     #[arg(short, long)]
     verbose: bool,
+    /// Additional .c or .o files to link against
+    #[arg(long)]
+    link: Vec<PathBuf>,
+    /// Optimization level, 0 to 2
+    #[arg(short = 'O', default_value_t = 1)]
+    opt_level: u8,
+    /// Optional passes to run instead of the ones chosen by the optimization level
+    #[arg(long, value_delimiter = ',')]
+    passes: Option<Vec<String>>,
+    /// Print the program after the given passes
+    #[arg(long, value_delimiter = ',')]
+    print_after: Vec<String>,
+    /// Rename variables through SSA construction and destruction
+    #[arg(long)]
+    ssa: bool,
+    /// Eliminate common subexpressions within blocks
+    #[arg(long)]
+    cse: bool,
+    /// Allocate registers by linear scan instead of graph coloring
+    #[arg(long)]
+    linear_scan: bool,
+    /// Number of registers available to variables, to force spilling
+    #[arg(long)]
+    regs: Option<usize>,
 }
 
 fn run_cli() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
-    let compiler = Driver::new(
+    let mut compiler = Driver::new(
         args.verbose,
         args.in_file,
         args.asm_out,
         args.object_out,
         args.out,
     )?;
+    compiler.paths.link_inputs = args.link;
+    compiler.passes.set_level(args.opt_level);
+    compiler.passes.linear_scan = args.linear_scan;
+    if let Some(count) = args.regs {
+        compiler.passes.set_registers(count)?;
+    }
+    if let Some(passes) = args.passes {
+        compiler.passes.set_passes(&passes)?;
+    }
+    if args.ssa {
+        compiler.passes.enable("ssa")?;
+    }
+    if args.cse {
+        compiler.passes.enable("cse")?;
+    }
+    for pass in args.print_after {
+        compiler.passes.print_after(&pass)?;
+    }
     compiler.run()?;
     Ok(())
 }
@@ -0,0 +1,33 @@
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+static uint64_t restored[5];
+
+/* Runs the compiled main with known values in the callee saved registers
+   and fails unless main restores all of them before returning */
+__attribute__((constructor)) static void check_callee_saved(void) {
+    __asm__ volatile("movq $1, %%rbx\n\t"
+                     "movq $2, %%r12\n\t"
+                     "movq $3, %%r13\n\t"
+                     "movq $4, %%r14\n\t"
+                     "movq $5, %%r15\n\t"
+                     "call main\n\t"
+                     "movq %%rbx, restored(%%rip)\n\t"
+                     "movq %%r12, restored+8(%%rip)\n\t"
+                     "movq %%r13, restored+16(%%rip)\n\t"
+                     "movq %%r14, restored+24(%%rip)\n\t"
+                     "movq %%r15, restored+32(%%rip)\n\t"
+                     :
+                     :
+                     : "rax", "rbx", "rcx", "rdx", "rsi", "rdi", "r8", "r9", "r10", "r11",
+                       "r12", "r13", "r14", "r15", "memory", "cc");
+    for (int i = 0; i < 5; i++) {
+        if (restored[i] != (uint64_t)(i + 1)) {
+            printf("callee saved register %d not restored\n", i);
+            exit(1);
+        }
+    }
+    printf("callee saved registers restored\n");
+    exit(0);
+}
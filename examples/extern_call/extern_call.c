@@ -0,0 +1,14 @@
+#include <stdint.h>
+
+int64_t add3(int64_t a, int64_t b, int64_t c) {
+    return a + b + c;
+}
+
+int64_t weighted_sum(int64_t a, int64_t b, int64_t c, int64_t d, int64_t e, int64_t f,
+                     int64_t g, int64_t h, int64_t i) {
+    return a + 2 * b + 3 * c + 4 * d + 5 * e + 6 * f + 7 * g + 8 * h + 9 * i;
+}
+
+int64_t is_even(int64_t n) {
+    return n % 2 == 0;
+}
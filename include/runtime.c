@@ -2,11 +2,35 @@
 #include <inttypes.h>
 #include <stdlib.h>
 #include <stdio.h>
+#include <string.h>
 #include "runtime.h"
 
 int64_t* heap_end;
 int64_t* free_ptr;
 
+static char output_buffer[OUTPUT_BUFFER_SIZE];
+static size_t output_len = 0;
+
+// Write the buffered output to stdout
+void flush_output() {
+    fwrite(output_buffer, 1, output_len, stdout);
+    fflush(stdout);
+    output_len = 0;
+}
+
+// Append bytes to the output buffer, flushing it when it is full
+static void write_output(const char* bytes, size_t len) {
+    if (output_len + len > OUTPUT_BUFFER_SIZE) {
+        flush_output();
+    }
+    if (len > OUTPUT_BUFFER_SIZE) {
+        fwrite(bytes, 1, len, stdout);
+        return;
+    }
+    memcpy(output_buffer + output_len, bytes, len);
+    output_len += len;
+}
+
 // Initialize the memory of the runtime with the initial heap size.
 void initialize() {
     int64_t* heap_begin;
@@ -20,10 +44,14 @@ void initialize() {
 
 // Obtain a piece of memory of the given size.
 int64_t* allocate(uint64_t size_in_bytes) {
+    if (!free_ptr) {
+        initialize();
+    }
     uint64_t words_needed = (size_in_bytes + sizeof(int64_t) - 1) / sizeof(int64_t);
     if (free_ptr + words_needed > heap_end) {
         fprintf(stderr, "Error: Heap exhausted! Requested %lu bytes\n", size_in_bytes);
         fprintf(stderr, "Current free_ptr: %p, heap_end: %p\n", free_ptr, heap_end);
+        flush_output();
         exit(1);
     }
     int64_t* allocated_ptr = free_ptr;
@@ -31,15 +59,114 @@ int64_t* allocate(uint64_t size_in_bytes) {
     return allocated_ptr;
 }
 
+// An integer read ahead by try_read_int, which the next read_int returns
+// instead of reading from stdin. read_bool fails while one is pending, as
+// the next input is an integer.
+static int has_pending_int = 0;
+static int64_t pending_int;
+
+// Report malformed input or EOF and exit
+static void input_error(const char* message) {
+    flush_output();
+    fprintf(stderr, "Error: %s\n", message);
+    exit(EXIT_FAILURE);
+}
+
 // Read an integer from stdin
 int64_t read_int() {
+    if (has_pending_int) {
+        has_pending_int = 0;
+        return pending_int;
+    }
     int64_t i;
-    scanf("%" SCNd64, &i);
+    flush_output();
+    int res = scanf("%" SCNd64, &i);
+    if (res == EOF) {
+        input_error("read_int reached end of input");
+    }
+    if (res != 1) {
+        input_error("read_int expected an integer");
+    }
     return i;
 }
 
+// Check whether an integer can be read from stdin, reading it ahead
+// Input that is not an integer is left unread
+int64_t try_read_int() {
+    if (has_pending_int) {
+        return 1;
+    }
+    flush_output();
+    if (scanf("%" SCNd64, &pending_int) != 1) {
+        return 0;
+    }
+    has_pending_int = 1;
+    return 1;
+}
+
+// Read a boolean (true or false) from stdin
+int64_t read_bool() {
+    if (has_pending_int) {
+        input_error("read_bool expected true or false");
+    }
+    char word[6];
+    flush_output();
+    int res = scanf("%5s", word);
+    if (res == EOF) {
+        input_error("read_bool reached end of input");
+    }
+    if (res == 1 && strcmp(word, "true") == 0) {
+        return 1;
+    }
+    if (res == 1 && strcmp(word, "false") == 0) {
+        return 0;
+    }
+    input_error("read_bool expected true or false");
+    return 0;
+}
+
 // print an integer to stdout
 void print_int(int64_t x) {
-    printf("%" PRId64, x);
+    char digits[32];
+    int len = snprintf(digits, sizeof(digits), "%" PRId64, x);
+    write_output(digits, len);
+}
+
+// Print a boolean to stdout
+void print_bool(int64_t b) {
+    if (b) {
+        write_output("true", 4);
+    } else {
+        write_output("false", 5);
+    }
 }
 
+// Print a newline to stdout
+void print_newline() {
+    write_output("\n", 1);
+}
+
+// Print a string to stdout
+void print_string(int64_t* s) {
+    write_output((char*)(s + 1), s[0]);
+}
+
+// Print a string followed by a newline to stdout
+void println(int64_t* s) {
+    print_string(s);
+    print_newline();
+}
+
+// Get the length of a string in bytes
+int64_t string_length(int64_t* s) {
+    return s[0];
+}
+
+// Allocate a new string containing s1 followed by s2
+int64_t* string_concat(int64_t* s1, int64_t* s2) {
+    int64_t* result = allocate(sizeof(int64_t) + s1[0] + s2[0]);
+    result[0] = s1[0] + s2[0];
+    memcpy(result + 1, s1 + 1, s1[0]);
+    memcpy((char*)(result + 1) + s1[0], s2 + 1, s2[0]);
+    return result;
+}
@@ -1,6 +1,7 @@
 #include <stdint.h>
 
 #define HEAP_SIZE (64 * 1024 * 1024)
+#define OUTPUT_BUFFER_SIZE (64 * 1024)
 
 extern int64_t* heap_end asm("heap_end");
 extern int64_t* free_ptr asm("free_ptr");
@@ -11,8 +12,39 @@ void initialize() asm("initialize");
 // Obtain a piece of memory of the given size.
 int64_t* allocate(uint64_t size_in_bytes) asm("allocate");
 
-// Read an integer from stdin.
+// Read an integer from stdin, exiting on malformed input or EOF.
 int64_t read_int() __asm__("read_int");
 
+// Try to read an integer from stdin, returning 1 on success and 0 otherwise.
+// A successfully read integer is returned by the next call to read_int,
+// and read_bool exits as long as it has not been read.
+int64_t try_read_int() __asm__("try_read_int");
+
+// Read a boolean (true or false) from stdin, exiting on malformed input or EOF.
+int64_t read_bool() __asm__("read_bool");
+
+// Output is buffered until it is flushed or the buffer is full.
+// Flush buffered output to stdout.
+void flush_output() __asm__("flush_output");
+
 // Print an integer to stdout.
 void print_int(int64_t x) __asm__("print_int");
+
+// Print a boolean (0 or 1) to stdout.
+void print_bool(int64_t b) __asm__("print_bool");
+
+// Print a newline to stdout.
+void print_newline() __asm__("print_newline");
+
+// Strings are a length word followed by that many bytes.
+// Print a string to stdout.
+void print_string(int64_t* s) __asm__("print_string");
+
+// Print a string followed by a newline to stdout.
+void println(int64_t* s) __asm__("println");
+
+// Get the length of a string in bytes.
+int64_t string_length(int64_t* s) __asm__("string_length");
+
+// Allocate a new string containing s1 followed by s2.
+int64_t* string_concat(int64_t* s1, int64_t* s2) __asm__("string_concat");
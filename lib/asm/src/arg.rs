@@ -7,6 +7,8 @@ pub enum Arg {
     Register(Reg),
     ByteReg(ByteReg),
     Deref(Reg, i64),
+    /// A global variable, addressed relative to `%rip`
+    Global(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -15,6 +17,12 @@ pub enum VarArg {
     Var(String),
 }
 
+impl Arg {
+    pub fn is_memory(&self) -> bool {
+        matches!(self, Arg::Deref(_, _) | Arg::Global(_))
+    }
+}
+
 impl fmt::Display for Arg {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -22,6 +30,7 @@ impl fmt::Display for Arg {
             Arg::Register(reg) => write!(f, "%{reg}"),
             Arg::ByteReg(reg) => write!(f, "%{reg}"),
             Arg::Deref(reg, offset) => write!(f, "{offset}(%{reg})"),
+            Arg::Global(label) => write!(f, "{label}(%rip)"),
         }
     }
 }
@@ -1,4 +1,4 @@
-use super::{Arg, VarArg};
+use super::{Arg, Reg, VarArg};
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -13,6 +13,18 @@ pub enum ByteReg {
     Dl,
 }
 
+impl ByteReg {
+    /// The 64-bit register containing this byte
+    pub fn full_reg(&self) -> Reg {
+        match self {
+            ByteReg::Ah | ByteReg::Al => Reg::Rax,
+            ByteReg::Bh | ByteReg::Bl => Reg::Rbx,
+            ByteReg::Ch | ByteReg::Cl => Reg::Rcx,
+            ByteReg::Dh | ByteReg::Dl => Reg::Rdx,
+        }
+    }
+}
+
 impl fmt::Display for ByteReg {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -0,0 +1,15 @@
+use definitions::Global;
+use std::fmt;
+
+pub fn fmt_data(globals: &[Global], f: &mut fmt::Formatter) -> fmt::Result {
+    if globals.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, ".data")?;
+    for global in globals.iter() {
+        writeln!(f, "\t.p2align 3")?;
+        writeln!(f, "{}:", global.label())?;
+        writeln!(f, "\t.quad {}", global.value)?;
+    }
+    Ok(())
+}
@@ -1,8 +1,5 @@
-use super::{
-    arg::{Arg, VarArg},
-    reg::Reg,
-};
-use crate::patch_instructions::{PatchInstructions, remove_double_deref};
+use super::arg::{Arg, VarArg};
+use crate::patch_instructions::{PatchInstructions, SCRATCH_REGISTER, remove_double_deref};
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -10,10 +7,11 @@ pub enum Instruction<Arg> {
     AddQ { src: Arg, dest: Arg },
     SubQ { src: Arg, dest: Arg },
     NegQ { arg: Arg },
+    IncQ { arg: Arg },
     MovQ { src: Arg, dest: Arg },
     PushQ { arg: Arg },
     PopQ { arg: Arg },
-    CallQ { label: String },
+    CallQ { label: String, arity: usize },
     RetQ,
     Jump { label: String },
     XorQ { src: Arg, dest: Arg },
@@ -23,6 +21,7 @@ pub enum Instruction<Arg> {
     JumpCC { cc: Cc, label: String },
     AndQ { src: Arg, dest: Arg },
     OrQ { src: Arg, dest: Arg },
+    LeaQ { label: String, dest: Arg },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -35,10 +34,75 @@ pub enum Cc {
     Ge,
 }
 
+impl Cc {
+    /// The condition code that holds exactly when `self` does not
+    pub fn negate(&self) -> Cc {
+        match self {
+            Cc::E => Cc::Ne,
+            Cc::Ne => Cc::E,
+            Cc::L => Cc::Ge,
+            Cc::Le => Cc::G,
+            Cc::G => Cc::Le,
+            Cc::Ge => Cc::L,
+        }
+    }
+}
+
 pub type VarInstr = Instruction<VarArg>;
 pub type Instr = Instruction<Arg>;
 
 impl<Arg> Instruction<Arg> {
+    /// Applies `f` to every argument of the instruction
+    pub fn map_args<B>(self, mut f: impl FnMut(Arg) -> B) -> Instruction<B> {
+        match self {
+            Instruction::AddQ { src, dest } => Instruction::AddQ {
+                src: f(src),
+                dest: f(dest),
+            },
+            Instruction::SubQ { src, dest } => Instruction::SubQ {
+                src: f(src),
+                dest: f(dest),
+            },
+            Instruction::NegQ { arg } => Instruction::NegQ { arg: f(arg) },
+            Instruction::IncQ { arg } => Instruction::IncQ { arg: f(arg) },
+            Instruction::MovQ { src, dest } => Instruction::MovQ {
+                src: f(src),
+                dest: f(dest),
+            },
+            Instruction::PushQ { arg } => Instruction::PushQ { arg: f(arg) },
+            Instruction::PopQ { arg } => Instruction::PopQ { arg: f(arg) },
+            Instruction::CallQ { label, arity } => Instruction::CallQ { label, arity },
+            Instruction::RetQ => Instruction::RetQ,
+            Instruction::Jump { label } => Instruction::Jump { label },
+            Instruction::XorQ { src, dest } => Instruction::XorQ {
+                src: f(src),
+                dest: f(dest),
+            },
+            Instruction::CmpQ { left, right } => Instruction::CmpQ {
+                left: f(left),
+                right: f(right),
+            },
+            Instruction::SetCC { cc, dest } => Instruction::SetCC { cc, dest: f(dest) },
+            Instruction::MovZBQ { src, dest } => Instruction::MovZBQ {
+                src: f(src),
+                dest: f(dest),
+            },
+            Instruction::JumpCC { cc, label } => Instruction::JumpCC { cc, label },
+            Instruction::AndQ { src, dest } => Instruction::AndQ {
+                src: f(src),
+                dest: f(dest),
+            },
+            Instruction::OrQ { src, dest } => Instruction::OrQ {
+                src: f(src),
+                dest: f(dest),
+            },
+            Instruction::LeaQ { label, dest } => Instruction::LeaQ {
+                label,
+                dest: f(dest),
+            },
+        }
+    }
+
     pub fn add<A1, A2>(src: A1, dest: A2) -> Instruction<Arg>
     where
         A1: Into<Arg>,
@@ -93,9 +157,10 @@ impl<Arg> Instruction<Arg> {
         Instruction::PopQ { arg: arg.into() }
     }
 
-    pub fn call(lb: &str) -> Instruction<Arg> {
+    pub fn call(lb: &str, arity: usize) -> Instruction<Arg> {
         Instruction::CallQ {
             label: lb.to_owned(),
+            arity,
         }
     }
 
@@ -111,13 +176,31 @@ impl PatchInstructions for Instruction<Arg> {
     fn patch_instructions(self) -> Vec<Instruction<Arg>> {
         match self {
             Instruction::NegQ { arg } => vec![Instruction::NegQ { arg }],
+            Instruction::IncQ { arg } => vec![Instruction::IncQ { arg }],
             Instruction::PushQ { arg } => vec![Instruction::PushQ { arg }],
             Instruction::PopQ { arg } => vec![Instruction::PopQ { arg }],
-            Instruction::CallQ { label } => vec![Instruction::CallQ { label }],
+            Instruction::CallQ { label, arity } => vec![Instruction::CallQ { label, arity }],
             Instruction::RetQ => vec![Instruction::RetQ],
             Instruction::Jump { label } => vec![Instruction::Jump { label }],
             Instruction::JumpCC { cc, label } => vec![Instruction::JumpCC { cc, label }],
             Instruction::SetCC { cc, dest } => vec![Instruction::SetCC { cc, dest }],
+            Instruction::LeaQ {
+                label,
+                dest: Arg::Register(reg),
+            } => vec![Instruction::LeaQ {
+                label,
+                dest: Arg::Register(reg),
+            }],
+            Instruction::LeaQ { label, dest } => vec![
+                Instruction::LeaQ {
+                    label,
+                    dest: SCRATCH_REGISTER.into(),
+                },
+                Instruction::MovQ {
+                    src: SCRATCH_REGISTER.into(),
+                    dest,
+                },
+            ],
 
             Instruction::CmpQ {
                 left: Arg::Immediate(i),
@@ -125,10 +208,10 @@ impl PatchInstructions for Instruction<Arg> {
             } => vec![
                 Instruction::MovQ {
                     src: Arg::Immediate(i),
-                    dest: Reg::Rax.into(),
+                    dest: SCRATCH_REGISTER.into(),
                 },
                 Instruction::CmpQ {
-                    left: Reg::Rax.into(),
+                    left: SCRATCH_REGISTER.into(),
                     right,
                 },
             ],
@@ -139,6 +222,16 @@ impl PatchInstructions for Instruction<Arg> {
                 src,
                 dest: Arg::Register(reg),
             }],
+            Instruction::MovZBQ { src, dest } => vec![
+                Instruction::MovZBQ {
+                    src,
+                    dest: SCRATCH_REGISTER.into(),
+                },
+                Instruction::MovQ {
+                    src: SCRATCH_REGISTER.into(),
+                    dest,
+                },
+            ],
 
             Instruction::AddQ { src, dest } => {
                 remove_double_deref(src, dest, |src, dest| Instruction::AddQ { src, dest })
@@ -155,9 +248,6 @@ impl PatchInstructions for Instruction<Arg> {
             Instruction::CmpQ { left, right } => {
                 remove_double_deref(left, right, |left, right| Instruction::CmpQ { left, right })
             }
-            Instruction::MovZBQ { src, dest } => {
-                remove_double_deref(src, dest, |src, dest| Instruction::MovZBQ { src, dest })
-            }
             Instruction::AndQ { src, dest } => {
                 remove_double_deref(src, dest, |src, dest| Instruction::AndQ { src, dest })
             }
@@ -177,10 +267,11 @@ where
             Instruction::AddQ { src, dest } => write!(f, "addq {src}, {dest}"),
             Instruction::SubQ { src, dest } => write!(f, "subq {src}, {dest}"),
             Instruction::NegQ { arg } => write!(f, "negq {arg}"),
+            Instruction::IncQ { arg } => write!(f, "incq {arg}"),
             Instruction::MovQ { src, dest } => write!(f, "movq {src}, {dest}"),
             Instruction::PushQ { arg } => write!(f, "pushq {arg}"),
             Instruction::PopQ { arg } => write!(f, "popq {arg}"),
-            Instruction::CallQ { label } => write!(f, "callq {label}"),
+            Instruction::CallQ { label, .. } => write!(f, "callq {label}"),
             Instruction::RetQ => write!(f, "retq"),
             Instruction::Jump { label } => write!(f, "jmp {label}"),
             Instruction::XorQ { src, dest } => write!(f, "xorq {src}, {dest}"),
@@ -190,6 +281,7 @@ where
             Instruction::JumpCC { cc, label } => write!(f, "j{cc} {label}"),
             Instruction::AndQ { src, dest } => write!(f, "andq {src}, {dest}"),
             Instruction::OrQ { src, dest } => write!(f, "orq {src}, {dest}"),
+            Instruction::LeaQ { label, dest } => write!(f, "leaq {label}(%rip), {dest}"),
         }
     }
 }
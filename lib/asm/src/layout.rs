@@ -0,0 +1,148 @@
+use crate::{Block, Instruction, Program};
+
+/// Orders blocks so that jump targets follow their jumps where possible,
+/// then removes jumps to the block immediately after
+pub fn layout_blocks(mut prog: Program) -> Program {
+    let mut remaining = prog.blocks;
+    let mut ordered: Vec<Block<_>> = vec![];
+    while !remaining.is_empty() {
+        let mut next = remaining.remove(0);
+        loop {
+            let target = match next.instrs.last() {
+                Some(Instruction::Jump { label }) => Some(label.clone()),
+                _ => None,
+            };
+            ordered.push(next);
+            let Some(ind) =
+                target.and_then(|target| remaining.iter().position(|bl| bl.label == target))
+            else {
+                break;
+            };
+            next = remaining.remove(ind);
+        }
+    }
+
+    for ind in 0..ordered.len() {
+        let Some(following) = ordered.get(ind + 1).map(|block| block.label.clone()) else {
+            continue;
+        };
+        let instrs = &mut ordered[ind].instrs;
+        if matches!(instrs.last(), Some(Instruction::Jump { label }) if *label == following) {
+            instrs.pop();
+        }
+        let len = instrs.len();
+        if len >= 2
+            && let [
+                Instruction::JumpCC {
+                    cc,
+                    label: then_label,
+                },
+                Instruction::Jump { label: else_label },
+            ] = &mut instrs[len - 2..]
+            && *then_label == following
+        {
+            *cc = cc.negate();
+            *then_label = else_label.clone();
+            instrs.pop();
+        }
+    }
+    prog.blocks = ordered;
+    prog
+}
+
+#[cfg(test)]
+mod layout_tests {
+    use super::layout_blocks;
+    use crate::{Block, Cc, Instruction, Program, Reg};
+    use std::collections::HashSet;
+
+    #[test]
+    fn layout_fall_through() {
+        let mut prog = Program::new(0, HashSet::new());
+        prog.blocks
+            .push(Block::new("main", vec![Instruction::jmp("start")]));
+        prog.blocks
+            .push(Block::new("conclusion", vec![Instruction::RetQ]));
+        prog.blocks.push(Block::new(
+            "block_0",
+            vec![
+                Instruction::mov(1, Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        ));
+        prog.blocks.push(Block::new(
+            "start",
+            vec![
+                Instruction::CmpQ {
+                    left: Reg::Rax.into(),
+                    right: 1.into(),
+                },
+                Instruction::JumpCC {
+                    cc: Cc::E,
+                    label: "conclusion".to_owned(),
+                },
+                Instruction::jmp("block_0"),
+            ],
+        ));
+        let result = layout_blocks(prog);
+        let labels: Vec<&str> = result
+            .blocks
+            .iter()
+            .map(|block| block.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["main", "start", "block_0", "conclusion"]);
+        assert_eq!(result.blocks[0].instrs, vec![]);
+        assert_eq!(
+            result.blocks[1].instrs[1..],
+            vec![Instruction::JumpCC {
+                cc: Cc::E,
+                label: "conclusion".to_owned(),
+            }]
+        );
+        assert_eq!(result.blocks[2].instrs, vec![Instruction::mov(1, Reg::Rax)]);
+    }
+
+    #[test]
+    fn layout_keep_back_edge() {
+        // `start` is already placed when `block_0` jumps back to it
+        let mut prog = Program::new(0, HashSet::new());
+        prog.blocks
+            .push(Block::new("main", vec![Instruction::jmp("start")]));
+        prog.blocks.push(Block::new(
+            "start",
+            vec![
+                Instruction::CmpQ {
+                    left: Reg::Rax.into(),
+                    right: 1.into(),
+                },
+                Instruction::JumpCC {
+                    cc: Cc::E,
+                    label: "block_1".to_owned(),
+                },
+                Instruction::jmp("block_0"),
+            ],
+        ));
+        let loop_body = vec![Instruction::add(1, Reg::Rax), Instruction::jmp("start")];
+        prog.blocks.push(Block::new("block_0", loop_body.clone()));
+        prog.blocks.push(Block::new(
+            "block_1",
+            vec![
+                Instruction::mov(1, Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        ));
+        prog.blocks
+            .push(Block::new("conclusion", vec![Instruction::RetQ]));
+        let result = layout_blocks(prog);
+        let labels: Vec<&str> = result
+            .blocks
+            .iter()
+            .map(|block| block.label.as_str())
+            .collect();
+        assert_eq!(
+            labels,
+            vec!["main", "start", "block_0", "block_1", "conclusion"]
+        );
+        assert_eq!(result.blocks[2].instrs, loop_body);
+    }
+}
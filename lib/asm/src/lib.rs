@@ -1,19 +1,26 @@
 pub mod arg;
 pub mod block;
 pub mod byte_reg;
+pub mod data;
 pub mod instr;
+pub mod layout;
 pub mod patch_instructions;
+pub mod peephole;
 pub mod prelude_conclusion;
 pub mod prog;
 pub mod reg;
+pub mod rodata;
 pub mod var_prog;
 
 pub use arg::{Arg, VarArg};
 pub use block::Block;
 pub use byte_reg::ByteReg;
 pub use instr::{Cc, Instruction};
-pub use patch_instructions::PatchInstructions;
-pub use prelude_conclusion::generate_prelude_conclusion;
+pub use layout::layout_blocks;
+pub use patch_instructions::{PatchInstructions, SCRATCH_REGISTER};
+pub use peephole::{PEEPHOLE_RULES, PeepholeRule, peephole};
+pub use prelude_conclusion::{CONCLUSION_LABEL, generate_prelude_conclusion};
 pub use prog::Program;
 pub use reg::Reg;
+pub use rodata::StringLiteral;
 pub use var_prog::VarProgram;
@@ -1,5 +1,8 @@
 use crate::{Arg, Instruction, Reg};
 
+/// Register patched instructions go through, never given to variables
+pub const SCRATCH_REGISTER: Reg = Reg::R15;
+
 pub trait PatchInstructions {
     type Target;
     fn patch_instructions(self) -> Self::Target;
@@ -11,12 +14,12 @@ pub fn remove_double_deref(
     instr: impl FnOnce(Arg, Arg) -> Instruction<Arg>,
 ) -> Vec<Instruction<Arg>> {
     match (&src, &dest) {
-        (Arg::Deref(_, _), Arg::Deref(_, _)) => vec![
+        (src_arg, dest_arg) if src_arg.is_memory() && dest_arg.is_memory() => vec![
             Instruction::MovQ {
                 src,
-                dest: Reg::Rax.into(),
+                dest: SCRATCH_REGISTER.into(),
             },
-            instr(Reg::Rax.into(), dest),
+            instr(SCRATCH_REGISTER.into(), dest),
         ],
         _ => vec![instr(src, dest)],
     }
@@ -49,7 +52,7 @@ mod patch_instructions_tests {
             ],
         ));
         let result = prog.patch_instructions();
-        let mut expected = Program::new(16, HashSet::new());
+        let mut expected = Program::new(16, HashSet::from([Reg::R15]));
         expected.blocks.push(Block::new(
             "start",
             vec![
@@ -59,10 +62,10 @@ mod patch_instructions_tests {
                 },
                 Instruction::MovQ {
                     src: Arg::Deref(Reg::Rbp, -8),
-                    dest: Reg::Rax.into(),
+                    dest: Reg::R15.into(),
                 },
                 Instruction::MovQ {
-                    src: Reg::Rax.into(),
+                    src: Reg::R15.into(),
                     dest: Arg::Deref(Reg::Rbp, -16),
                 },
                 Instruction::MovQ {
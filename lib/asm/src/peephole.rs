@@ -0,0 +1,465 @@
+use crate::{Arg, Cc, Instruction, Program, Reg};
+use std::collections::{HashMap, HashSet};
+
+type Instr = Instruction<Arg>;
+
+/// The number of instructions to replace and their replacement
+type Rewrite = Option<(usize, Vec<Instr>)>;
+
+/// A local rewrite of the instructions at the start of a window
+pub struct PeepholeRule {
+    pub name: &'static str,
+    apply: fn(&Window) -> Rewrite,
+}
+
+/// The remaining instructions of a block, with registers live into every block
+pub struct Window<'a> {
+    instrs: &'a [Instr],
+    live_in: &'a HashMap<String, HashSet<Reg>>,
+}
+
+pub const PEEPHOLE_RULES: [PeepholeRule; 5] = [
+    PeepholeRule {
+        name: "remove self move",
+        apply: remove_self_move,
+    },
+    PeepholeRule {
+        name: "zero with xor",
+        apply: zero_with_xor,
+    },
+    PeepholeRule {
+        name: "add one with inc",
+        apply: add_one_with_inc,
+    },
+    PeepholeRule {
+        name: "fold move add move",
+        apply: fold_move_add_move,
+    },
+    PeepholeRule {
+        name: "fuse compare and branch",
+        apply: fuse_compare_branch,
+    },
+];
+
+/// Applies `PEEPHOLE_RULES` until none of them match
+/// Returns the optimized program and how often each rule fired
+pub fn peephole(mut prog: Program) -> (Program, Vec<(&'static str, usize)>) {
+    let mut fired = [0; PEEPHOLE_RULES.len()];
+    let mut changing = true;
+    while changing {
+        changing = false;
+        let live_in = live_registers(&prog);
+        for block in prog.blocks.iter_mut() {
+            let mut ind = 0;
+            while ind < block.instrs.len() {
+                let window = Window {
+                    instrs: &block.instrs[ind..],
+                    live_in: &live_in,
+                };
+                let applied = PEEPHOLE_RULES
+                    .iter()
+                    .enumerate()
+                    .find_map(|(rule_ind, rule)| Some((rule_ind, (rule.apply)(&window)?)));
+                match applied {
+                    Some((rule_ind, (len, replacement))) => {
+                        block.instrs.splice(ind..ind + len, replacement);
+                        fired[rule_ind] += 1;
+                        changing = true;
+                    }
+                    None => ind += 1,
+                }
+            }
+        }
+    }
+    let report = PEEPHOLE_RULES
+        .iter()
+        .zip(fired)
+        .filter(|(_, count)| *count > 0)
+        .map(|(rule, count)| (rule.name, count))
+        .collect();
+    (prog, report)
+}
+
+fn remove_self_move(window: &Window) -> Rewrite {
+    match window.instrs {
+        [Instruction::MovQ { src, dest }, ..] if src == dest => Some((1, vec![])),
+        _ => None,
+    }
+}
+
+fn zero_with_xor(window: &Window) -> Rewrite {
+    match window.instrs {
+        [
+            Instruction::MovQ {
+                src: Arg::Immediate(0),
+                dest: dest @ Arg::Register(_),
+            },
+            ..,
+        ] if window.flags_dead_after(1) => Some((
+            1,
+            vec![Instruction::XorQ {
+                src: dest.clone(),
+                dest: dest.clone(),
+            }],
+        )),
+        _ => None,
+    }
+}
+
+fn add_one_with_inc(window: &Window) -> Rewrite {
+    match window.instrs {
+        [
+            Instruction::AddQ {
+                src: Arg::Immediate(1),
+                dest,
+            },
+            ..,
+        ] if window.flags_dead_after(1) => Some((1, vec![Instruction::IncQ { arg: dest.clone() }])),
+        _ => None,
+    }
+}
+
+/// `movq a, r; addq b, r; movq r, a` becomes `addq b, a` if `r` is dead afterwards
+fn fold_move_add_move(window: &Window) -> Rewrite {
+    let [
+        Instruction::MovQ {
+            src: a,
+            dest: Arg::Register(r),
+        },
+        Instruction::AddQ {
+            src: b,
+            dest: Arg::Register(r_add),
+        },
+        Instruction::MovQ {
+            src: Arg::Register(r_back),
+            dest: a_back,
+        },
+        ..,
+    ] = window.instrs
+    else {
+        return None;
+    };
+    let foldable = r == r_add
+        && r == r_back
+        && a == a_back
+        && !arg_regs(a).contains(r)
+        && !arg_regs(b).contains(r)
+        && !(a.is_memory() && b.is_memory())
+        && window.dead_after(3, r);
+    foldable.then(|| {
+        (
+            3,
+            vec![Instruction::AddQ {
+                src: b.clone(),
+                dest: a.clone(),
+            }],
+        )
+    })
+}
+
+/// `cmpq; setCC; movzbq; cmpq $1; je` becomes `cmpq; jCC`
+/// if neither the byte register nor the boolean are used afterwards
+fn fuse_compare_branch(window: &Window) -> Rewrite {
+    let [
+        cmp @ Instruction::CmpQ { .. },
+        Instruction::SetCC {
+            cc,
+            dest: Arg::ByteReg(byte_reg),
+        },
+        Instruction::MovZBQ {
+            src: Arg::ByteReg(byte_src),
+            dest: Arg::Register(r),
+        },
+        Instruction::CmpQ {
+            left: Arg::Register(r_cmp),
+            right: Arg::Immediate(1),
+        },
+        Instruction::JumpCC { cc: jump_cc, label },
+        ..,
+    ] = window.instrs
+    else {
+        return None;
+    };
+    let fused_cc = match jump_cc {
+        Cc::E => cc.clone(),
+        Cc::Ne => cc.negate(),
+        _ => return None,
+    };
+    let fusable = byte_reg == byte_src
+        && r == r_cmp
+        && window.dead_after(4, r)
+        && window.dead_after(4, &byte_reg.full_reg());
+    fusable.then(|| {
+        (
+            5,
+            vec![
+                cmp.clone(),
+                Instruction::JumpCC {
+                    cc: fused_cc,
+                    label: label.clone(),
+                },
+            ],
+        )
+    })
+}
+
+impl Window<'_> {
+    /// Whether `reg` is overwritten before being read, starting at instruction `start`
+    fn dead_after(&self, start: usize, reg: &Reg) -> bool {
+        for instr in self.instrs.iter().skip(start) {
+            match instr {
+                Instruction::Jump { label } => return !self.live_into(label, reg),
+                Instruction::JumpCC { label, .. } if self.live_into(label, reg) => return false,
+                _ => (),
+            }
+            if read_regs(instr).contains(reg) {
+                return false;
+            }
+            if written_regs(instr).contains(reg) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn live_into(&self, label: &str, reg: &Reg) -> bool {
+        self.live_in
+            .get(label)
+            .is_none_or(|live| live.contains(reg))
+    }
+
+    /// Whether the flags are set again before any instruction reads them
+    /// Every conditional jump or set is preceded by a comparison in its own block,
+    /// so flags are never live across jumps
+    fn flags_dead_after(&self, start: usize) -> bool {
+        for instr in self.instrs.iter().skip(start) {
+            match instr {
+                Instruction::SetCC { .. } | Instruction::JumpCC { .. } => return false,
+                Instruction::AddQ { .. }
+                | Instruction::SubQ { .. }
+                | Instruction::NegQ { .. }
+                | Instruction::IncQ { .. }
+                | Instruction::XorQ { .. }
+                | Instruction::CmpQ { .. }
+                | Instruction::AndQ { .. }
+                | Instruction::OrQ { .. }
+                | Instruction::CallQ { .. }
+                | Instruction::Jump { .. }
+                | Instruction::RetQ => return true,
+                _ => (),
+            }
+        }
+        true
+    }
+}
+
+/// Registers live at the start of each block
+fn live_registers(prog: &Program) -> HashMap<String, HashSet<Reg>> {
+    let mut live_in: HashMap<String, HashSet<Reg>> = prog
+        .blocks
+        .iter()
+        .map(|block| (block.label.clone(), HashSet::new()))
+        .collect();
+    let mut changing = true;
+    while changing {
+        changing = false;
+        for block in prog.blocks.iter().rev() {
+            let mut live: HashSet<Reg> = match block.instrs.last() {
+                Some(Instruction::Jump { .. } | Instruction::RetQ) => HashSet::new(),
+                _ => all_regs(),
+            };
+            for instr in block.instrs.iter().rev() {
+                match instr {
+                    Instruction::Jump { label } => {
+                        live = live_in.get(label).cloned().unwrap_or_else(all_regs)
+                    }
+                    Instruction::JumpCC { label, .. } => {
+                        live.extend(live_in.get(label).cloned().unwrap_or_else(all_regs))
+                    }
+                    _ => {
+                        live = &(&live - &written_regs(instr)) | &read_regs(instr);
+                    }
+                }
+            }
+            if live_in.get(&block.label) != Some(&live) {
+                live_in.insert(block.label.clone(), live);
+                changing = true;
+            }
+        }
+    }
+    live_in
+}
+
+fn all_regs() -> HashSet<Reg> {
+    Reg::caller_saved()
+        .into_iter()
+        .chain(Reg::callee_saved())
+        .collect()
+}
+
+fn arg_regs(arg: &Arg) -> HashSet<Reg> {
+    match arg {
+        Arg::Register(reg) | Arg::Deref(reg, _) => HashSet::from([reg.clone()]),
+        Arg::ByteReg(byte_reg) => HashSet::from([byte_reg.full_reg()]),
+        Arg::Immediate(_) | Arg::Global(_) => HashSet::new(),
+    }
+}
+
+/// Registers used to address memory when `arg` is written
+fn address_regs(arg: &Arg) -> HashSet<Reg> {
+    match arg {
+        Arg::Deref(reg, _) => HashSet::from([reg.clone()]),
+        _ => HashSet::new(),
+    }
+}
+
+fn read_regs(instr: &Instr) -> HashSet<Reg> {
+    match instr {
+        Instruction::AddQ { src, dest }
+        | Instruction::SubQ { src, dest }
+        | Instruction::XorQ { src, dest }
+        | Instruction::AndQ { src, dest }
+        | Instruction::OrQ { src, dest } => &arg_regs(src) | &arg_regs(dest),
+        Instruction::NegQ { arg } | Instruction::IncQ { arg } => arg_regs(arg),
+        Instruction::MovQ { src, dest } | Instruction::MovZBQ { src, dest } => {
+            &arg_regs(src) | &address_regs(dest)
+        }
+        Instruction::CmpQ { left, right } => &arg_regs(left) | &arg_regs(right),
+        Instruction::SetCC { dest, .. } => address_regs(dest),
+        Instruction::LeaQ { dest, .. } => address_regs(dest),
+        Instruction::PushQ { arg } => &arg_regs(arg) | &HashSet::from([Reg::Rsp]),
+        Instruction::PopQ { arg } => &address_regs(arg) | &HashSet::from([Reg::Rsp]),
+        Instruction::CallQ { arity, .. } => Reg::arg_registers()
+            .into_iter()
+            .take(*arity)
+            .chain([Reg::Rsp])
+            .collect(),
+        // the return value and everything restored for the caller
+        Instruction::RetQ => Reg::callee_saved().into_iter().chain([Reg::Rax]).collect(),
+        Instruction::Jump { .. } | Instruction::JumpCC { .. } => HashSet::new(),
+    }
+}
+
+fn written_regs(instr: &Instr) -> HashSet<Reg> {
+    match instr {
+        Instruction::AddQ { dest, .. }
+        | Instruction::SubQ { dest, .. }
+        | Instruction::XorQ { dest, .. }
+        | Instruction::AndQ { dest, .. }
+        | Instruction::OrQ { dest, .. }
+        | Instruction::MovQ { dest, .. }
+        | Instruction::MovZBQ { dest, .. }
+        | Instruction::LeaQ { dest, .. }
+        | Instruction::NegQ { arg: dest }
+        | Instruction::IncQ { arg: dest }
+        | Instruction::PopQ { arg: dest } => match dest {
+            Arg::Register(reg) => HashSet::from([reg.clone()]),
+            _ => HashSet::new(),
+        },
+        // the remaining bytes are never read after setting a byte register
+        Instruction::SetCC { dest, .. } => match dest {
+            Arg::ByteReg(byte_reg) => HashSet::from([byte_reg.full_reg()]),
+            _ => HashSet::new(),
+        },
+        Instruction::CallQ { .. } => Reg::caller_saved().into_iter().collect(),
+        Instruction::CmpQ { .. }
+        | Instruction::PushQ { .. }
+        | Instruction::RetQ
+        | Instruction::Jump { .. }
+        | Instruction::JumpCC { .. } => HashSet::new(),
+    }
+}
+
+#[cfg(test)]
+mod peephole_tests {
+    use super::peephole;
+    use crate::{Arg, Block, ByteReg, Cc, Instruction, Program, Reg};
+    use std::collections::HashSet;
+
+    #[test]
+    fn peephole_moves() {
+        let mut prog = Program::new(0, HashSet::new());
+        prog.blocks.push(Block::new(
+            "start",
+            vec![
+                Instruction::mov(Reg::Rcx, Reg::Rcx),
+                Instruction::mov(Arg::Deref(Reg::Rbp, -8), Reg::Rax),
+                Instruction::add(1, Reg::Rax),
+                Instruction::mov(Reg::Rax, Arg::Deref(Reg::Rbp, -8)),
+                Instruction::mov(0, Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        ));
+        prog.blocks
+            .push(Block::new("conclusion", vec![Instruction::RetQ]));
+        let (result, fired) = peephole(prog);
+
+        assert_eq!(
+            result.blocks[0].instrs,
+            vec![
+                Instruction::IncQ {
+                    arg: Arg::Deref(Reg::Rbp, -8)
+                },
+                Instruction::XorQ {
+                    src: Reg::Rax.into(),
+                    dest: Reg::Rax.into()
+                },
+                Instruction::jmp("conclusion"),
+            ]
+        );
+        assert_eq!(
+            fired,
+            vec![
+                ("remove self move", 1),
+                ("zero with xor", 1),
+                ("add one with inc", 1),
+                ("fold move add move", 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn peephole_fuse_branch() {
+        let branch = |used: bool| {
+            let mut prog = Program::new(0, HashSet::new());
+            prog.blocks.push(Block::new(
+                "start",
+                vec![
+                    Instruction::CmpQ {
+                        left: Reg::Rcx.into(),
+                        right: 5.into(),
+                    },
+                    Instruction::SetCC {
+                        cc: Cc::L,
+                        dest: ByteReg::Al.into(),
+                    },
+                    Instruction::MovZBQ {
+                        src: ByteReg::Al.into(),
+                        dest: Reg::Rdx.into(),
+                    },
+                    Instruction::CmpQ {
+                        left: Reg::Rdx.into(),
+                        right: 1.into(),
+                    },
+                    Instruction::JumpCC {
+                        cc: Cc::E,
+                        label: "block_0".to_owned(),
+                    },
+                    Instruction::jmp("block_0"),
+                ],
+            ));
+            let used_reg = if used { Reg::Rdx } else { Reg::Rcx };
+            prog.blocks.push(Block::new(
+                "block_0",
+                vec![
+                    Instruction::mov(used_reg, Reg::Rdi),
+                    Instruction::call("print_int", 1),
+                    Instruction::RetQ,
+                ],
+            ));
+            peephole(prog).0.blocks[0].instrs.len()
+        };
+        assert_eq!(branch(false), 3);
+        assert_eq!(branch(true), 6);
+    }
+}
@@ -1,11 +1,19 @@
 use crate::{Arg, Block, Instruction, Program, Reg};
+use definitions::FLUSH_OUTPUT_CALL;
+
+/// Block programs jump to in order to return, generated with the prelude
+pub const CONCLUSION_LABEL: &str = "conclusion";
 
 pub fn generate_prelude_conclusion(prog: Program) -> Program {
     let prelude = generate_prelude(&prog);
     let conclusion = generate_conclusion(&prog);
     let mut finalized = Program::new(prog.stack_space, prog.used_callee);
+    finalized.strings = prog.strings;
+    finalized.globals = prog.globals;
     finalized.blocks.push(Block::new("main", prelude));
-    finalized.blocks.push(Block::new("conclusion", conclusion));
+    finalized
+        .blocks
+        .push(Block::new(CONCLUSION_LABEL, conclusion));
     for block in prog.blocks {
         finalized
             .blocks
@@ -51,10 +59,16 @@ fn generate_prelude(prog: &Program) -> Vec<Instruction<Arg>> {
 
 fn generate_conclusion(prog: &Program) -> Vec<Instruction<Arg>> {
     let used_space = used_space(prog);
-    let mut conc = vec![Instruction::AddQ {
-        src: Arg::Immediate(used_space),
-        dest: Reg::Rsp.into(),
-    }];
+    let mut conc = vec![
+        Instruction::CallQ {
+            label: FLUSH_OUTPUT_CALL.to_owned(),
+            arity: 0,
+        },
+        Instruction::AddQ {
+            src: Arg::Immediate(used_space),
+            dest: Reg::Rsp.into(),
+        },
+    ];
 
     let mut callee_vec: Vec<&Reg> = prog.used_callee.iter().collect();
     callee_vec.sort();
@@ -154,6 +168,10 @@ mod generate_prelude_conclusion_tests {
         expected.blocks.push(Block::new(
             "conclusion",
             vec![
+                Instruction::CallQ {
+                    label: "flush_output".to_owned(),
+                    arity: 0,
+                },
                 Instruction::AddQ {
                     src: Arg::Immediate(8),
                     dest: Reg::Rsp.into(),
@@ -182,6 +200,7 @@ mod generate_prelude_conclusion_tests {
             vec![
                 Instruction::CallQ {
                     label: "read_int".to_owned(),
+                    arity: 0,
                 },
                 Instruction::MovQ {
                     src: Reg::Rax.into(),
@@ -260,6 +279,10 @@ mod generate_prelude_conclusion_tests {
         expected.blocks.push(Block::new(
             "conclusion",
             vec![
+                Instruction::CallQ {
+                    label: "flush_output".to_owned(),
+                    arity: 0,
+                },
                 Instruction::AddQ {
                     src: 0.into(),
                     dest: Reg::Rsp.into(),
@@ -1,12 +1,20 @@
-use super::{Arg, Block, Reg};
-use crate::patch_instructions::PatchInstructions;
+use super::{Arg, Block, Reg, StringLiteral};
+use crate::{
+    data::fmt_data,
+    patch_instructions::{PatchInstructions, SCRATCH_REGISTER},
+    rodata::fmt_rodata,
+};
+use definitions::Global;
 use std::{collections::HashSet, fmt};
 
 #[derive(Debug, Clone)]
 pub struct Program {
     pub blocks: Vec<Block<Arg>>,
+    /// Bytes of stack slots below the saved callee saved registers
     pub stack_space: u64,
     pub used_callee: HashSet<Reg>,
+    pub strings: Vec<StringLiteral>,
+    pub globals: Vec<Global>,
 }
 
 impl Program {
@@ -15,6 +23,8 @@ impl Program {
             stack_space,
             blocks: vec![],
             used_callee,
+            strings: vec![],
+            globals: vec![],
         }
     }
 }
@@ -23,16 +33,36 @@ impl PatchInstructions for Program {
     type Target = Self;
     fn patch_instructions(self) -> Self::Target {
         let mut patched = Program::new(self.stack_space, self.used_callee);
+        patched.strings = self.strings;
+        patched.globals = self.globals;
         for block in self.blocks {
             patched.blocks.push(block.patch_instructions());
         }
+        let uses_scratch = patched
+            .blocks
+            .iter()
+            .flat_map(|block| block.instrs.iter())
+            .any(|instr| {
+                let mut uses = false;
+                instr
+                    .clone()
+                    .map_args(|arg| uses |= arg == SCRATCH_REGISTER.into());
+                uses
+            });
+        if uses_scratch {
+            patched.used_callee.insert(SCRATCH_REGISTER);
+        }
         patched
     }
 }
 
 impl PartialEq for Program {
     fn eq(&self, other: &Self) -> bool {
-        if self.stack_space != other.stack_space || (self.used_callee != other.used_callee) {
+        if self.stack_space != other.stack_space
+            || self.used_callee != other.used_callee
+            || self.strings != other.strings
+            || self.globals != other.globals
+        {
             return false;
         }
         for block in self.blocks.iter() {
@@ -58,6 +88,7 @@ impl fmt::Display for Program {
             block.fmt(f)?;
             writeln!(f)?;
         }
-        Ok(())
+        fmt_rodata(&self.strings, f)?;
+        fmt_data(&self.globals, f)
     }
 }
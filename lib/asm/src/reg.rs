@@ -36,6 +36,10 @@ impl Reg {
         ]
     }
 
+    pub const fn arg_registers() -> [Reg; 6] {
+        [Reg::Rdi, Reg::Rsi, Reg::Rdx, Reg::Rcx, Reg::R8, Reg::R9]
+    }
+
     pub const fn callee_saved() -> [Reg; 7] {
         [
             Reg::Rsp,
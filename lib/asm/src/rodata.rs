@@ -0,0 +1,57 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringLiteral {
+    pub label: String,
+    pub value: String,
+}
+
+impl StringLiteral {
+    pub fn new(label: &str, value: &str) -> StringLiteral {
+        StringLiteral {
+            label: label.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for StringLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "\t.p2align 3")?;
+        writeln!(f, "{}:", self.label)?;
+        writeln!(f, "\t.quad {}", self.value.len())?;
+        write!(f, "\t.ascii \"")?;
+        for byte in self.value.bytes() {
+            match byte {
+                b'"' => f.write_str("\\\"")?,
+                b'\\' => f.write_str("\\\\")?,
+                b' '..=b'~' => write!(f, "{}", byte as char)?,
+                _ => write!(f, "\\{byte:03o}")?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+pub fn fmt_rodata(strings: &[StringLiteral], f: &mut fmt::Formatter) -> fmt::Result {
+    if strings.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, ".section .rodata")?;
+    for string in strings.iter() {
+        writeln!(f, "{string}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod rodata_tests {
+    use super::StringLiteral;
+
+    #[test]
+    fn display_escaped() {
+        let result = StringLiteral::new("str_0", "say \"hi\"\n").to_string();
+        let expected = "\t.p2align 3\nstr_0:\n\t.quad 9\n\t.ascii \"say \\\"hi\\\"\\012\"";
+        assert_eq!(result, expected)
+    }
+}
@@ -1,14 +1,22 @@
-use super::{Block, Instruction, VarArg};
+use super::{Block, Instruction, StringLiteral, VarArg};
+use crate::{data::fmt_data, rodata::fmt_rodata};
+use definitions::Global;
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VarProgram {
     pub blocks: Vec<Block<VarArg>>,
+    pub strings: Vec<StringLiteral>,
+    pub globals: Vec<Global>,
 }
 
 impl VarProgram {
     pub fn new() -> VarProgram {
-        VarProgram { blocks: vec![] }
+        VarProgram {
+            blocks: vec![],
+            strings: vec![],
+            globals: vec![],
+        }
     }
 
     pub fn add_block(&mut self, lb: &str, instrs: Vec<Instruction<VarArg>>) {
@@ -37,7 +45,8 @@ impl fmt::Display for VarProgram {
             block.fmt(f)?;
             writeln!(f)?;
         }
-        Ok(())
+        fmt_rodata(&self.strings, f)?;
+        fmt_data(&self.globals, f)
     }
 }
 impl Default for VarProgram {
@@ -1,7 +1,7 @@
 use super::{Continuation, Expression, Tail};
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Atom {
     Integer(i64),
     Variable(String),
@@ -9,6 +9,24 @@ pub enum Atom {
     Unit,
 }
 
+impl Atom {
+    /// The value of a constant atom, with booleans as `0` or `1`
+    pub fn constant(&self) -> Option<i64> {
+        match self {
+            Atom::Integer(i) => Some(*i),
+            Atom::Bool(b) => Some(*b as i64),
+            Atom::Variable(_) | Atom::Unit => None,
+        }
+    }
+
+    pub fn as_var(&self) -> Option<&String> {
+        match self {
+            Atom::Variable(var) => Some(var),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for Atom {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
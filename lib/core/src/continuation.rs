@@ -1,4 +1,5 @@
 use super::Atom;
+use definitions::Comparator;
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -6,22 +7,94 @@ pub enum Continuation {
     Return(Atom),
     Goto(String),
     If {
-        cond: Atom,
+        left: Atom,
+        cmp: Comparator,
+        right: Atom,
         then_label: String,
         else_label: String,
     },
 }
 
+impl Continuation {
+    pub fn branch(left: Atom, cmp: Comparator, right: Atom, then_lb: &str, else_lb: &str) -> Self {
+        Continuation::If {
+            left,
+            cmp,
+            right,
+            then_label: then_lb.to_owned(),
+            else_label: else_lb.to_owned(),
+        }
+    }
+
+    /// Branch on a boolean atom
+    pub fn cond(cond: Atom, then_lb: &str, else_lb: &str) -> Continuation {
+        Continuation::branch(cond, Comparator::Eq, Atom::Bool(true), then_lb, else_lb)
+    }
+
+    /// Jumps to `to` wherever this continuation would jump to `from`
+    pub fn redirect(&mut self, from: &str, to: &str) {
+        let targets = match self {
+            Continuation::Return(_) => vec![],
+            Continuation::Goto(label) => vec![label],
+            Continuation::If {
+                then_label,
+                else_label,
+                ..
+            } => vec![then_label, else_label],
+        };
+        for target in targets.into_iter().filter(|label| *label == from) {
+            *target = to.to_owned();
+        }
+    }
+
+    /// The atoms read by this continuation
+    pub fn atoms_mut(&mut self) -> Vec<&mut Atom> {
+        match self {
+            Continuation::Return(atm) => vec![atm],
+            Continuation::Goto(_) => vec![],
+            Continuation::If { left, right, .. } => vec![left, right],
+        }
+    }
+
+    /// Variables read by this continuation
+    pub fn used_vars(&self) -> Vec<&String> {
+        match self {
+            Continuation::Return(atm) => atm.as_var().into_iter().collect(),
+            Continuation::Goto(_) => vec![],
+            Continuation::If { left, right, .. } => {
+                left.as_var().into_iter().chain(right.as_var()).collect()
+            }
+        }
+    }
+
+    pub fn successors(&self) -> Vec<&String> {
+        match self {
+            Continuation::Return(_) => vec![],
+            Continuation::Goto(label) => vec![label],
+            Continuation::If {
+                then_label,
+                else_label,
+                ..
+            } => vec![then_label, else_label],
+        }
+    }
+}
+
 impl fmt::Display for Continuation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Continuation::Return(exp) => write!(f, "return {exp};"),
             Continuation::Goto(label) => write!(f, "goto {label};"),
             Continuation::If {
-                cond,
+                left,
+                cmp,
+                right,
                 then_label,
                 else_label,
-            } => write!(f, "if {cond} goto {then_label} else goto {else_label};"),
+            } => write!(
+                f,
+                "if {left}{cmp}{right} goto {then_label} else goto {else_label};"
+            ),
         }
     }
 }
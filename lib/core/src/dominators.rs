@@ -0,0 +1,236 @@
+use crate::Program;
+use std::collections::{HashMap, HashSet};
+
+/// Dominator tree of the blocks reachable from `start`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dominators {
+    /// Reachable blocks in reverse postorder
+    pub order: Vec<String>,
+    /// Immediate dominator of every reachable block except `start`
+    pub idom: HashMap<String, String>,
+}
+
+impl Dominators {
+    /// Iterative algorithm of Cooper, Harvey and Kennedy
+    pub fn new(prog: &Program) -> Dominators {
+        let order = prog.reverse_postorder();
+        let index: HashMap<&String, usize> = order.iter().zip(0..).collect();
+        let preds = prog.predecessors();
+
+        let mut idom: Vec<Option<usize>> = vec![None; order.len()];
+        if !order.is_empty() {
+            idom[0] = Some(0);
+        }
+        let mut changing = true;
+        while changing {
+            changing = false;
+            for (ind, label) in order.iter().enumerate().skip(1) {
+                let mut new_idom = None;
+                for pred in preds[label].iter().filter_map(|pred| index.get(pred)) {
+                    if idom[*pred].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        None => *pred,
+                        Some(other) => intersect(&idom, *pred, other),
+                    });
+                }
+                if new_idom.is_some() && idom[ind] != new_idom {
+                    idom[ind] = new_idom;
+                    changing = true;
+                }
+            }
+        }
+
+        let idom = idom
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(ind, dom)| Some((order[ind].clone(), order[(*dom)?].clone())))
+            .collect();
+        Dominators { order, idom }
+    }
+
+    /// Whether every path from `start` to `block` goes through `dom`
+    pub fn dominates(&self, dom: &str, block: &str) -> bool {
+        let mut current = block;
+        loop {
+            if current == dom {
+                return true;
+            }
+            match self.idom.get(current) {
+                Some(parent) => current = parent,
+                None => return false,
+            }
+        }
+    }
+
+    /// Children in the dominator tree, in reverse postorder
+    pub fn children(&self) -> HashMap<String, Vec<String>> {
+        let mut children: HashMap<String, Vec<String>> = self
+            .order
+            .iter()
+            .map(|label| (label.clone(), vec![]))
+            .collect();
+        for label in self.order.iter() {
+            if let Some(parent) = self.idom.get(label) {
+                children
+                    .entry(parent.clone())
+                    .or_default()
+                    .push(label.clone());
+            }
+        }
+        children
+    }
+
+    /// Blocks where the dominance of each block ends
+    pub fn frontiers(&self, prog: &Program) -> HashMap<String, HashSet<String>> {
+        let mut frontiers: HashMap<String, HashSet<String>> = self
+            .order
+            .iter()
+            .map(|label| (label.clone(), HashSet::new()))
+            .collect();
+        for (label, preds) in prog.predecessors() {
+            let Some(idom) = self.idom.get(&label) else {
+                continue;
+            };
+            if preds.len() < 2 {
+                continue;
+            }
+            let reachable = preds.iter().filter(|pred| self.order.contains(pred));
+            for pred in reachable {
+                let mut runner = pred;
+                while runner != idom {
+                    frontiers
+                        .entry(runner.clone())
+                        .or_default()
+                        .insert(label.clone());
+                    match self.idom.get(runner) {
+                        Some(parent) => runner = parent,
+                        None => break,
+                    }
+                }
+            }
+        }
+        frontiers
+    }
+}
+
+fn intersect(idom: &[Option<usize>], mut fst: usize, mut snd: usize) -> usize {
+    while fst != snd {
+        while fst > snd {
+            fst = idom[fst].unwrap_or(0);
+        }
+        while snd > fst {
+            snd = idom[snd].unwrap_or(0);
+        }
+    }
+    fst
+}
+
+#[cfg(test)]
+mod dominators_tests {
+    use super::Dominators;
+    use crate::{Atom, Continuation, Program, Tail};
+    use std::collections::{HashMap, HashSet};
+
+    fn goto(label: &str) -> Tail {
+        Tail {
+            stmts: vec![],
+            cont: Continuation::Goto(label.to_owned()),
+        }
+    }
+
+    fn branch(then_lb: &str, else_lb: &str) -> Tail {
+        Tail {
+            stmts: vec![],
+            cont: Continuation::cond("c".into(), then_lb, else_lb),
+        }
+    }
+
+    #[test]
+    fn dominators_loop() {
+        let mut prog = Program::new();
+        prog.add_block("start", goto("block_0"));
+        prog.add_block("block_0", branch("block_1", "block_2"));
+        prog.add_block("block_1", branch("block_3", "block_4"));
+        prog.add_block("block_3", goto("block_0"));
+        prog.add_block("block_4", goto("block_0"));
+        prog.add_block("block_2", Atom::Unit.into());
+        let result = Dominators::new(&prog);
+
+        let expected_idom = HashMap::from([
+            ("block_0".to_owned(), "start".to_owned()),
+            ("block_1".to_owned(), "block_0".to_owned()),
+            ("block_2".to_owned(), "block_0".to_owned()),
+            ("block_3".to_owned(), "block_1".to_owned()),
+            ("block_4".to_owned(), "block_1".to_owned()),
+        ]);
+        assert_eq!(result.idom, expected_idom);
+        assert!(result.dominates("block_0", "block_4"));
+        assert!(!result.dominates("block_3", "block_0"));
+
+        let frontiers = result.frontiers(&prog);
+        let loop_header = HashSet::from(["block_0".to_owned()]);
+        assert_eq!(frontiers["block_3"], loop_header);
+        assert_eq!(frontiers["block_1"], loop_header);
+        assert_eq!(frontiers["block_0"], loop_header);
+        assert!(frontiers["block_2"].is_empty());
+    }
+
+    #[test]
+    fn dominators_irreducible() {
+        // the loop between block_0 and block_1 can be entered at either block
+        let mut prog = Program::new();
+        prog.add_block("start", branch("block_0", "block_1"));
+        prog.add_block("block_0", branch("block_1", "block_2"));
+        prog.add_block("block_1", goto("block_0"));
+        prog.add_block("block_2", Atom::Unit.into());
+        let result = Dominators::new(&prog);
+
+        let expected_idom = HashMap::from([
+            ("block_0".to_owned(), "start".to_owned()),
+            ("block_1".to_owned(), "start".to_owned()),
+            ("block_2".to_owned(), "block_0".to_owned()),
+        ]);
+        assert_eq!(result.idom, expected_idom);
+        assert!(!result.dominates("block_0", "block_1"));
+        assert!(!result.dominates("block_1", "block_0"));
+
+        let frontiers = result.frontiers(&prog);
+        assert_eq!(frontiers["block_0"], HashSet::from(["block_1".to_owned()]));
+        assert_eq!(frontiers["block_1"], HashSet::from(["block_0".to_owned()]));
+        assert!(frontiers["start"].is_empty());
+        assert!(frontiers["block_2"].is_empty());
+    }
+
+    #[test]
+    fn dominators_multiple_exits() {
+        // the loop is left from its header and from the middle of its body
+        let mut prog = Program::new();
+        prog.add_block("start", goto("block_0"));
+        prog.add_block("block_0", branch("block_1", "block_3"));
+        prog.add_block("block_1", branch("block_2", "block_3"));
+        prog.add_block("block_2", goto("block_0"));
+        prog.add_block("block_3", Atom::Unit.into());
+        let result = Dominators::new(&prog);
+
+        let expected_idom = HashMap::from([
+            ("block_0".to_owned(), "start".to_owned()),
+            ("block_1".to_owned(), "block_0".to_owned()),
+            ("block_2".to_owned(), "block_1".to_owned()),
+            ("block_3".to_owned(), "block_0".to_owned()),
+        ]);
+        assert_eq!(result.idom, expected_idom);
+        assert!(!result.dominates("block_1", "block_3"));
+
+        let frontiers = result.frontiers(&prog);
+        assert_eq!(
+            frontiers["block_1"],
+            HashSet::from(["block_0".to_owned(), "block_3".to_owned()])
+        );
+        assert_eq!(frontiers["block_2"], HashSet::from(["block_0".to_owned()]));
+        assert_eq!(frontiers["block_0"], HashSet::from(["block_0".to_owned()]));
+        assert!(frontiers["block_3"].is_empty());
+    }
+}
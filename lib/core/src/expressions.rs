@@ -1,11 +1,19 @@
 use super::Atom;
-use definitions::{BinaryOperation, Comparator, READ_INT_CALL, UnaryOperation};
+use definitions::{
+    BinaryOperation, Comparator, READ_BOOL_CALL, READ_INT_CALL, TRY_READ_INT_CALL, UnaryOperation,
+};
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Expression {
     Atm(Atom),
+    Str {
+        label: String,
+        value: String,
+    },
     ReadInt,
+    ReadBool,
+    TryReadInt,
     UnaryOp {
         arg: Atom,
         op: UnaryOperation,
@@ -20,6 +28,10 @@ pub enum Expression {
         cmp: Comparator,
         right: Atom,
     },
+    Call {
+        name: String,
+        args: Vec<Atom>,
+    },
 }
 
 impl Expression {
@@ -34,16 +46,74 @@ impl Expression {
     pub fn cmp(left: Atom, cmp: Comparator, right: Atom) -> Expression {
         Expression::Cmp { left, cmp, right }
     }
+
+    pub fn call(name: &str, args: Vec<Atom>) -> Expression {
+        Expression::Call {
+            name: name.to_owned(),
+            args,
+        }
+    }
+
+    /// Whether evaluating the expression has no effect besides its value
+    pub fn is_pure(&self) -> bool {
+        !matches!(
+            self,
+            Expression::Call { .. }
+                | Expression::ReadInt
+                | Expression::ReadBool
+                | Expression::TryReadInt
+        )
+    }
+
+    /// The atoms read by this expression
+    pub fn atoms_mut(&mut self) -> Vec<&mut Atom> {
+        match self {
+            Expression::Atm(atm) | Expression::UnaryOp { arg: atm, .. } => vec![atm],
+            Expression::BinOp { fst, snd, .. } => vec![fst, snd],
+            Expression::Cmp { left, right, .. } => vec![left, right],
+            Expression::Call { args, .. } => args.iter_mut().collect(),
+            Expression::Str { .. }
+            | Expression::ReadInt
+            | Expression::ReadBool
+            | Expression::TryReadInt => vec![],
+        }
+    }
+
+    /// Variables read by this expression
+    pub fn used_vars(&self) -> Vec<&String> {
+        let atoms: Vec<&Atom> = match self {
+            Expression::Atm(atm) | Expression::UnaryOp { arg: atm, .. } => vec![atm],
+            Expression::BinOp { fst, snd, .. } => vec![fst, snd],
+            Expression::Cmp { left, right, .. } => vec![left, right],
+            Expression::Call { args, .. } => args.iter().collect(),
+            Expression::Str { .. }
+            | Expression::ReadInt
+            | Expression::ReadBool
+            | Expression::TryReadInt => vec![],
+        };
+        atoms.into_iter().filter_map(Atom::as_var).collect()
+    }
 }
 
 impl fmt::Display for Expression {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Expression::Atm(atm) => atm.fmt(f),
+            Expression::Str { value, .. } => write!(f, "{value:?}"),
             Expression::ReadInt => f.write_str(READ_INT_CALL),
+            Expression::ReadBool => f.write_str(READ_BOOL_CALL),
+            Expression::TryReadInt => f.write_str(TRY_READ_INT_CALL),
             Expression::UnaryOp { arg, op } => write!(f, "{op}({arg})"),
             Expression::BinOp { fst, op, snd } => write!(f, "{fst} {op} {snd}"),
             Expression::Cmp { left, cmp, right } => write!(f, "{left}{cmp}{right}"),
+            Expression::Call { name, args } => write!(
+                f,
+                "{name}({})",
+                args.iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
         }
     }
 }
@@ -1,14 +1,19 @@
 mod atoms;
 mod block;
 mod continuation;
+mod dominators;
 mod expressions;
+mod liveness;
+pub mod optimize;
 mod program;
+pub mod ssa;
 mod statements;
 mod tail;
 
 pub use atoms::Atom;
 pub use block::Block;
 pub use continuation::Continuation;
+pub use dominators::Dominators;
 pub use expressions::Expression;
 pub use program::Program;
 pub use statements::Statement;
@@ -0,0 +1,32 @@
+use crate::Program;
+use definitions::dataflow::{Analysis, Direction, solve};
+use std::collections::{HashMap, HashSet};
+
+struct Liveness<'a> {
+    prog: &'a Program,
+}
+
+impl Analysis for Liveness<'_> {
+    type Fact = HashSet<String>;
+    const DIRECTION: Direction = Direction::Backward;
+
+    fn transfer(&self, label: &str, live_out: &HashSet<String>) -> HashSet<String> {
+        let Some(block) = self.prog.get_block(label) else {
+            return live_out.clone();
+        };
+        let mut live = live_out.clone();
+        live.extend(block.tail.cont.used_vars().into_iter().cloned());
+        for stmt in block.tail.stmts.iter().rev() {
+            if let Some(var) = stmt.defined_var() {
+                live.remove(var);
+            }
+            live.extend(stmt.used_vars().into_iter().cloned());
+        }
+        live
+    }
+}
+
+/// Variables live at the start of each block
+pub(crate) fn live_in(prog: &Program) -> HashMap<String, HashSet<String>> {
+    solve(&Liveness { prog }, prog, "start").entry
+}
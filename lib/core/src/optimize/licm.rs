@@ -0,0 +1,345 @@
+use crate::{Continuation, Dominators, Expression, Program, Statement, Tail, liveness::live_in};
+use std::collections::{HashMap, HashSet};
+
+/// Blocks on a cycle through `header`, which dominates all of them
+struct NaturalLoop {
+    header: String,
+    /// In reverse postorder
+    blocks: Vec<String>,
+}
+
+/// Moves pure assignments computing the same value in every iteration of a loop
+/// into a new preheader block in front of the loop
+pub fn hoist_loop_invariants(mut prog: Program) -> Program {
+    loop {
+        let doms = Dominators::new(&prog);
+        let hoisted = natural_loops(&prog, &doms)
+            .into_iter()
+            .find_map(|natural_loop| {
+                let stmts = take_invariants(&mut prog, &doms, &natural_loop);
+                (!stmts.is_empty()).then_some((natural_loop, stmts))
+            });
+        let Some((natural_loop, stmts)) = hoisted else {
+            return prog;
+        };
+
+        let preheader = prog.fresh_label();
+        for block in prog.blocks.iter_mut() {
+            if !natural_loop.blocks.contains(&block.label) {
+                block.tail.cont.redirect(&natural_loop.header, &preheader);
+            }
+        }
+        prog.add_block(
+            &preheader,
+            Tail {
+                stmts,
+                cont: Continuation::Goto(natural_loop.header),
+            },
+        );
+    }
+}
+
+/// Loops sharing a header are merged, inner loops come first
+fn natural_loops(prog: &Program, doms: &Dominators) -> Vec<NaturalLoop> {
+    let preds = prog.predecessors();
+    let mut loops: HashMap<&String, HashSet<&String>> = HashMap::new();
+    for latch in doms.order.iter() {
+        let Some(block) = prog.get_block(latch) else {
+            continue;
+        };
+        for header in block.tail.cont.successors() {
+            if *header == "start" || !doms.dominates(header, latch) {
+                continue;
+            }
+            let body = loops
+                .entry(header)
+                .or_insert_with(|| HashSet::from([header]));
+            let mut todo = vec![latch];
+            while let Some(label) = todo.pop() {
+                if doms.order.contains(label) && body.insert(label) {
+                    todo.extend(preds[label].iter());
+                }
+            }
+        }
+    }
+
+    let mut loops: Vec<NaturalLoop> = loops
+        .into_iter()
+        .map(|(header, body)| NaturalLoop {
+            header: header.clone(),
+            blocks: doms
+                .order
+                .iter()
+                .filter(|label| body.contains(label))
+                .cloned()
+                .collect(),
+        })
+        .collect();
+    loops.sort_by_key(|natural_loop| (natural_loop.blocks.len(), natural_loop.header.clone()));
+    loops
+}
+
+/// Removes the invariant statements of a loop and returns them in an order
+/// where every statement comes after the ones it depends on
+fn take_invariants(
+    prog: &mut Program,
+    doms: &Dominators,
+    natural_loop: &NaturalLoop,
+) -> Vec<Statement> {
+    let live_in = live_in(prog);
+    let mut loop_defs: HashMap<String, usize> = HashMap::new();
+    let mut has_calls = false;
+    for label in natural_loop.blocks.iter() {
+        let Some(block) = prog.get_block(label) else {
+            continue;
+        };
+        for stmt in block.tail.stmts.iter() {
+            if let Some(var) = stmt.defined_var() {
+                *loop_defs.entry(var.clone()).or_default() += 1;
+            }
+            if let Statement::Assign { bound, .. } | Statement::Set { bound, .. } = stmt {
+                has_calls |= matches!(bound, Expression::Call { .. });
+            }
+        }
+    }
+    let exits: Vec<(String, String)> = natural_loop
+        .blocks
+        .iter()
+        .filter_map(|label| prog.get_block(label))
+        .flat_map(|block| {
+            block
+                .tail
+                .cont
+                .successors()
+                .into_iter()
+                .filter(|succ| !natural_loop.blocks.contains(succ))
+                .map(|succ| (block.label.clone(), succ.clone()))
+        })
+        .collect();
+    let globals: HashSet<String> = prog.globals.iter().map(|gl| gl.name.clone()).collect();
+
+    let is_invariant = |label: &String, stmt: &Statement, hoisted: &HashSet<String>| {
+        let Statement::Assign { var, bound } = stmt else {
+            return false;
+        };
+        // The only write in the loop, never read before it,
+        // and seen after the loop only when it happened in the last iteration
+        let single_def = !globals.contains(var)
+            && loop_defs.get(var) == Some(&1)
+            && !live_in[&natural_loop.header].contains(var)
+            && exits
+                .iter()
+                .all(|(from, to)| !live_in[to].contains(var) || doms.dominates(label, from));
+        // Set variables and globals written by calls change between iterations
+        let operands_fixed = bound.used_vars().into_iter().all(|used| {
+            let changing = loop_defs.contains_key(used) || (has_calls && globals.contains(used));
+            hoisted.contains(used) || !changing
+        });
+        single_def && bound.is_pure() && operands_fixed
+    };
+
+    let mut hoisted = vec![];
+    let mut hoisted_vars = HashSet::new();
+    let mut changing = true;
+    while changing {
+        changing = false;
+        for label in natural_loop.blocks.iter() {
+            let Some(block) = prog.get_block_mut(label) else {
+                continue;
+            };
+            let mut ind = 0;
+            while ind < block.tail.stmts.len() {
+                if !is_invariant(label, &block.tail.stmts[ind], &hoisted_vars) {
+                    ind += 1;
+                    continue;
+                }
+                let stmt = block.tail.stmts.remove(ind);
+                hoisted_vars.extend(stmt.defined_var().cloned());
+                hoisted.push(stmt);
+                changing = true;
+            }
+        }
+    }
+    hoisted
+}
+
+#[cfg(test)]
+mod licm_tests {
+    use super::hoist_loop_invariants;
+    use crate::{Atom, Continuation, Expression, Program, Statement, Tail};
+    use definitions::{BinaryOperation, Comparator, PrintKind};
+
+    fn loop_cond() -> Continuation {
+        Continuation::branch(
+            "i".into(),
+            Comparator::Lt,
+            "x0".into(),
+            "block_0",
+            "block_1",
+        )
+    }
+
+    fn bound() -> Statement {
+        Statement::assign(
+            "x0",
+            Expression::bin("n".into(), BinaryOperation::Add, 1.into()),
+        )
+    }
+
+    #[test]
+    fn hoist_while_invariants() {
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::assign("n", Expression::ReadInt),
+                    Statement::set("i", Atom::Integer(0).into()),
+                    bound(),
+                ],
+                cont: loop_cond(),
+            },
+        );
+        let body = vec![
+            Statement::assign(
+                "x1",
+                Expression::bin("n".into(), BinaryOperation::Sub, "i".into()),
+            ),
+            Statement::assign("x2", Expression::ReadInt),
+            Statement::assign(
+                "x3",
+                Expression::bin("n".into(), BinaryOperation::Add, "n".into()),
+            ),
+            Statement::print(PrintKind::Int, "x1".into()),
+            Statement::print(PrintKind::Int, "x2".into()),
+            Statement::print(PrintKind::Int, "x3".into()),
+            Statement::set(
+                "i",
+                Expression::bin("i".into(), BinaryOperation::Add, 1.into()),
+            ),
+            bound(),
+        ];
+        prog.add_block(
+            "block_0",
+            Tail {
+                stmts: body.clone(),
+                cont: loop_cond(),
+            },
+        );
+        prog.add_block("block_1", Atom::Unit.into());
+        let result = hoist_loop_invariants(prog.clone());
+
+        let mut expected = prog;
+        expected.get_block_mut("start").unwrap().tail.cont = Continuation::branch(
+            "i".into(),
+            Comparator::Lt,
+            "x0".into(),
+            "block_2",
+            "block_1",
+        );
+        expected.get_block_mut("block_0").unwrap().tail.stmts = body
+            .into_iter()
+            .filter(|stmt| !matches!(stmt.defined_var(), Some(var) if var == "x3" || var == "x0"))
+            .collect();
+        expected.add_block(
+            "block_2",
+            Tail {
+                stmts: vec![
+                    Statement::assign(
+                        "x3",
+                        Expression::bin("n".into(), BinaryOperation::Add, "n".into()),
+                    ),
+                    bound(),
+                ],
+                cont: Continuation::Goto("block_0".to_owned()),
+            },
+        );
+        assert_eq!(result, expected);
+        assert_eq!(result.blocks.len(), expected.blocks.len())
+    }
+
+    #[test]
+    fn keep_conditional_and_changing() {
+        // `x` reads `n`, which the loop decrements, and `y` is only assigned
+        // in some iterations but read after the loop
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::assign("n", Expression::ReadInt),
+                    Statement::assign("m", Expression::ReadInt),
+                    Statement::set("i", Atom::Integer(0).into()),
+                    Statement::set("y", Atom::Integer(0).into()),
+                ],
+                cont: Continuation::Goto("block_0".to_owned()),
+            },
+        );
+        prog.add_block(
+            "block_0",
+            Tail {
+                stmts: vec![],
+                cont: Continuation::branch(
+                    "i".into(),
+                    Comparator::Lt,
+                    "n".into(),
+                    "block_1",
+                    "block_4",
+                ),
+            },
+        );
+        prog.add_block(
+            "block_1",
+            Tail {
+                stmts: vec![
+                    Statement::assign(
+                        "x",
+                        Expression::bin("n".into(), BinaryOperation::Add, 1.into()),
+                    ),
+                    Statement::print(PrintKind::Int, "x".into()),
+                    Statement::set(
+                        "n",
+                        Expression::bin("n".into(), BinaryOperation::Sub, 1.into()),
+                    ),
+                    Statement::set(
+                        "i",
+                        Expression::bin("i".into(), BinaryOperation::Add, 1.into()),
+                    ),
+                ],
+                cont: Continuation::branch(
+                    "i".into(),
+                    Comparator::Eq,
+                    3.into(),
+                    "block_2",
+                    "block_3",
+                ),
+            },
+        );
+        prog.add_block(
+            "block_2",
+            Tail {
+                stmts: vec![Statement::assign(
+                    "y",
+                    Expression::bin("m".into(), BinaryOperation::Add, "m".into()),
+                )],
+                cont: Continuation::Goto("block_3".to_owned()),
+            },
+        );
+        prog.add_block(
+            "block_3",
+            Tail {
+                stmts: vec![],
+                cont: Continuation::Goto("block_0".to_owned()),
+            },
+        );
+        prog.add_block(
+            "block_4",
+            Tail {
+                stmts: vec![Statement::print(PrintKind::Int, "y".into())],
+                cont: Continuation::Return(Atom::Unit),
+            },
+        );
+        let result = hoist_loop_invariants(prog.clone());
+        assert_eq!(result, prog)
+    }
+}
@@ -0,0 +1,11 @@
+mod licm;
+mod propagate;
+mod simplify_cfg;
+mod unreachable;
+mod value_numbering;
+
+pub use licm::hoist_loop_invariants;
+pub use propagate::propagate_copies;
+pub use simplify_cfg::simplify_cfg;
+pub use unreachable::remove_unreachable;
+pub use value_numbering::eliminate_common_subexpressions;
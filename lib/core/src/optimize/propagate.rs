@@ -0,0 +1,205 @@
+use crate::{Atom, Continuation, Expression, Program, Statement, Tail};
+use definitions::dataflow::{Analysis, Direction, MustSet, solve};
+use std::collections::{HashMap, HashSet};
+
+/// Copies `x = a` that reach a program point along every path,
+/// with `a` already resolved through earlier copies
+type Copies = HashMap<String, Atom>;
+
+/// Replaces uses of variables bound to a constant or another variable
+pub fn propagate_copies(mut prog: Program) -> Program {
+    let globals: HashSet<String> = prog.globals.iter().map(|gl| gl.name.clone()).collect();
+    let mut copies_in = available_copies(&prog, &globals);
+    for block in prog.blocks.iter_mut() {
+        let mut copies = copies_in.remove(&block.label).unwrap_or_default();
+        transfer_tail(&mut block.tail, &mut copies, &globals);
+    }
+    prog
+}
+
+/// Copies as pairs, so that joining keeps the copies found along every path
+struct AvailableCopies<'a> {
+    prog: &'a Program,
+    globals: &'a HashSet<String>,
+}
+
+impl Analysis for AvailableCopies<'_> {
+    type Fact = MustSet<(String, Atom)>;
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn initial(&self) -> Self::Fact {
+        MustSet::Set(HashSet::new())
+    }
+
+    fn transfer(&self, label: &str, copies_in: &Self::Fact) -> Self::Fact {
+        let (Some(block), MustSet::Set(copies_in)) = (self.prog.get_block(label), copies_in) else {
+            return copies_in.clone();
+        };
+        let mut copies: Copies = copies_in.iter().cloned().collect();
+        transfer_tail(&mut block.tail.clone(), &mut copies, self.globals);
+        MustSet::Set(copies.into_iter().collect())
+    }
+}
+
+/// Copies available at the start of each block, none for blocks not reached from `start`
+fn available_copies(prog: &Program, globals: &HashSet<String>) -> HashMap<String, Copies> {
+    solve(&AvailableCopies { prog, globals }, prog, "start")
+        .entry
+        .into_iter()
+        .map(|(label, copies)| match copies {
+            MustSet::All => (label, Copies::new()),
+            MustSet::Set(copies) => (label, copies.into_iter().collect()),
+        })
+        .collect()
+}
+
+fn transfer_tail(tail: &mut Tail, copies: &mut Copies, globals: &HashSet<String>) {
+    for stmt in tail.stmts.iter_mut() {
+        transfer_stmt(stmt, copies, globals);
+    }
+    match &mut tail.cont {
+        Continuation::Return(atm) => subst(atm, copies),
+        Continuation::Goto(_) => (),
+        Continuation::If { left, right, .. } => {
+            subst(left, copies);
+            subst(right, copies);
+        }
+    }
+}
+
+fn transfer_stmt(stmt: &mut Statement, copies: &mut Copies, globals: &HashSet<String>) {
+    match stmt {
+        Statement::Assign { var, bound } | Statement::Set { var, bound } => {
+            subst_exp(bound, copies);
+            copies.retain(|copy, atm| copy != var && *atm != Atom::Variable(var.clone()));
+            if let Expression::Atm(atm) = bound
+                && !globals.contains(var)
+                && !is_global_var(atm, globals)
+                && *atm != Atom::Variable(var.clone())
+            {
+                copies.insert(var.clone(), atm.clone());
+            }
+        }
+        Statement::Print { arg, .. } => subst(arg, copies),
+        Statement::PrintNewline => (),
+    }
+}
+
+fn subst_exp(exp: &mut Expression, copies: &Copies) {
+    match exp {
+        Expression::Atm(atm) => subst(atm, copies),
+        Expression::UnaryOp { arg, .. } => subst(arg, copies),
+        Expression::BinOp { fst, snd, .. } => {
+            subst(fst, copies);
+            subst(snd, copies);
+        }
+        Expression::Cmp { left, right, .. } => {
+            subst(left, copies);
+            subst(right, copies);
+        }
+        Expression::Call { args, .. } => {
+            for arg in args.iter_mut() {
+                subst(arg, copies);
+            }
+        }
+        Expression::Str { .. }
+        | Expression::ReadInt
+        | Expression::ReadBool
+        | Expression::TryReadInt => (),
+    }
+}
+
+fn subst(atm: &mut Atom, copies: &Copies) {
+    if let Atom::Variable(var) = atm
+        && let Some(copy) = copies.get(var)
+    {
+        *atm = copy.clone();
+    }
+}
+
+fn is_global_var(atm: &Atom, globals: &HashSet<String>) -> bool {
+    matches!(atm, Atom::Variable(var) if globals.contains(var))
+}
+
+#[cfg(test)]
+mod propagate_tests {
+    use super::propagate_copies;
+    use crate::{Atom, Continuation, Expression, Program, Statement, Tail};
+    use definitions::{BinaryOperation, PrintKind};
+
+    #[test]
+    fn propagate_across_goto() {
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::assign("x0", Atom::Integer(5).into()),
+                    Statement::assign("x1", Atom::Variable("x0".to_owned()).into()),
+                ],
+                cont: Continuation::Goto("block_0".to_owned()),
+            },
+        );
+        prog.add_block(
+            "block_0",
+            Tail {
+                stmts: vec![Statement::print(PrintKind::Int, "x1".into())],
+                cont: Continuation::Return("x1".into()),
+            },
+        );
+        let result = propagate_copies(prog);
+
+        let mut expected = Program::new();
+        expected.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::assign("x0", Atom::Integer(5).into()),
+                    Statement::assign("x1", Atom::Integer(5).into()),
+                ],
+                cont: Continuation::Goto("block_0".to_owned()),
+            },
+        );
+        expected.add_block(
+            "block_0",
+            Tail {
+                stmts: vec![Statement::print(PrintKind::Int, 5.into())],
+                cont: Continuation::Return(5.into()),
+            },
+        );
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn propagate_killed_in_loop() {
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::assign("i", Atom::Integer(0).into()),
+                    Statement::assign("c", Atom::Bool(true).into()),
+                ],
+                cont: Continuation::Goto("block_0".to_owned()),
+            },
+        );
+        let body = Tail {
+            stmts: vec![
+                Statement::print(PrintKind::Int, "i".into()),
+                Statement::set(
+                    "i",
+                    Expression::bin("i".into(), BinaryOperation::Add, 1.into()),
+                ),
+            ],
+            cont: Continuation::cond("c".into(), "block_0", "block_1"),
+        };
+        prog.add_block("block_0", body.clone());
+        prog.add_block("block_1", Atom::from("i").into());
+        let result = propagate_copies(prog.clone());
+
+        let mut expected = prog;
+        expected.get_block_mut("block_0").unwrap().tail.cont =
+            Continuation::cond(Atom::Bool(true), "block_0", "block_1");
+        assert_eq!(result, expected)
+    }
+}
@@ -0,0 +1,163 @@
+use super::remove_unreachable;
+use crate::{Continuation, Program};
+use std::collections::{HashMap, HashSet};
+
+/// Threads jumps through blocks that only contain a `goto`,
+/// then merges blocks into their only predecessor
+pub fn simplify_cfg(prog: Program) -> Program {
+    let prog = thread_jumps(prog);
+    let prog = remove_unreachable(prog);
+    merge_blocks(prog)
+}
+
+fn thread_jumps(mut prog: Program) -> Program {
+    let forwards: HashMap<String, String> = prog
+        .blocks
+        .iter()
+        .filter(|block| block.label != "start" && block.tail.stmts.is_empty())
+        .filter_map(|block| match &block.tail.cont {
+            Continuation::Goto(target) if *target != block.label => {
+                Some((block.label.clone(), target.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    let resolve = |label: &mut String| {
+        let mut visited = HashSet::new();
+        while let Some(target) = forwards.get(label.as_str())
+            && visited.insert(label.clone())
+        {
+            *label = target.clone();
+        }
+    };
+
+    for block in prog.blocks.iter_mut() {
+        match &mut block.tail.cont {
+            Continuation::Return(_) => (),
+            Continuation::Goto(label) => resolve(label),
+            Continuation::If {
+                then_label,
+                else_label,
+                ..
+            } => {
+                resolve(then_label);
+                resolve(else_label);
+                if then_label == else_label {
+                    block.tail.cont = Continuation::Goto(then_label.clone());
+                }
+            }
+        }
+    }
+    prog
+}
+
+fn merge_blocks(mut prog: Program) -> Program {
+    loop {
+        let preds = prog.predecessors();
+        let merge = prog.blocks.iter().enumerate().find_map(|(ind, block)| {
+            let Continuation::Goto(target) = &block.tail.cont else {
+                return None;
+            };
+            let single_pred = preds.get(target).is_some_and(|preds| preds.len() == 1);
+            (single_pred && *target != block.label && target != "start")
+                .then(|| (ind, target.clone()))
+        });
+        let Some((ind, target)) = merge else {
+            return prog;
+        };
+        let Some(target_ind) = prog.blocks.iter().position(|bl| bl.label == target) else {
+            return prog;
+        };
+        let merged = prog.blocks.remove(target_ind);
+        let ind = if target_ind < ind { ind - 1 } else { ind };
+        let tail = &mut prog.blocks[ind].tail;
+        tail.stmts.extend(merged.tail.stmts);
+        tail.cont = merged.tail.cont;
+    }
+}
+
+#[cfg(test)]
+mod simplify_cfg_tests {
+    use super::simplify_cfg;
+    use crate::{Atom, Continuation, Program, Statement, Tail};
+    use definitions::PrintKind;
+
+    #[test]
+    fn simplify_diamond() {
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![Statement::assign("c", Atom::Bool(true).into())],
+                cont: Continuation::cond("c".into(), "block_0", "block_1"),
+            },
+        );
+        prog.add_block(
+            "block_0",
+            Tail {
+                stmts: vec![],
+                cont: Continuation::Goto("block_2".to_owned()),
+            },
+        );
+        prog.add_block(
+            "block_1",
+            Tail {
+                stmts: vec![],
+                cont: Continuation::Goto("block_2".to_owned()),
+            },
+        );
+        prog.add_block(
+            "block_2",
+            Tail {
+                stmts: vec![Statement::print(PrintKind::Int, "c".into())],
+                cont: Continuation::Return(Atom::Unit),
+            },
+        );
+        let result = simplify_cfg(prog);
+
+        let mut expected = Program::new();
+        expected.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::assign("c", Atom::Bool(true).into()),
+                    Statement::print(PrintKind::Int, "c".into()),
+                ],
+                cont: Continuation::Return(Atom::Unit),
+            },
+        );
+        assert_eq!(result, expected);
+        assert_eq!(result.blocks.len(), 1)
+    }
+
+    #[test]
+    fn keep_join_block() {
+        // both arms do work, so the join keeps two predecessors and stays its own block
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![Statement::assign("c", Atom::Bool(true).into())],
+                cont: Continuation::cond("c".into(), "block_0", "block_1"),
+            },
+        );
+        for (label, value) in [("block_0", 1), ("block_1", 2)] {
+            prog.add_block(
+                label,
+                Tail {
+                    stmts: vec![Statement::print(PrintKind::Int, value.into())],
+                    cont: Continuation::Goto("block_2".to_owned()),
+                },
+            );
+        }
+        prog.add_block(
+            "block_2",
+            Tail {
+                stmts: vec![Statement::print(PrintKind::Int, "c".into())],
+                cont: Continuation::Return(Atom::Unit),
+            },
+        );
+        let result = simplify_cfg(prog.clone());
+        assert_eq!(result, prog)
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{Continuation, Program};
+use std::collections::HashSet;
+
+/// Removes blocks that cannot be reached from `start`
+/// Branches on a constant condition are turned into jumps first
+pub fn remove_unreachable(mut prog: Program) -> Program {
+    for block in prog.blocks.iter_mut() {
+        if let Continuation::If {
+            left,
+            cmp,
+            right,
+            then_label,
+            else_label,
+        } = &block.tail.cont
+            && let (Some(left), Some(right)) = (left.constant(), right.constant())
+        {
+            let target = if cmp.eval(left, right) {
+                then_label
+            } else {
+                else_label
+            };
+            block.tail.cont = Continuation::Goto(target.clone());
+        }
+    }
+
+    let mut reachable = HashSet::new();
+    let mut todo = vec!["start".to_owned()];
+    while let Some(label) = todo.pop() {
+        if !reachable.insert(label.clone()) {
+            continue;
+        }
+        if let Some(block) = prog.get_block(&label) {
+            todo.extend(block.tail.cont.successors().into_iter().cloned());
+        }
+    }
+    prog.blocks.retain(|block| reachable.contains(&block.label));
+    prog
+}
+
+#[cfg(test)]
+mod unreachable_tests {
+    use super::remove_unreachable;
+    use crate::{Atom, Continuation, Expression, Program, Statement, Tail};
+
+    #[test]
+    fn remove_constant_branch() {
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![],
+                cont: Continuation::cond(Atom::Bool(false), "block_0", "block_1"),
+            },
+        );
+        prog.add_block("block_0", Atom::Integer(1).into());
+        prog.add_block("block_1", Atom::Integer(2).into());
+        let result = remove_unreachable(prog);
+
+        let mut expected = Program::new();
+        expected.add_block(
+            "start",
+            Tail {
+                stmts: vec![],
+                cont: Continuation::Goto("block_1".to_owned()),
+            },
+        );
+        expected.add_block("block_1", Atom::Integer(2).into());
+        assert_eq!(result, expected);
+        assert_eq!(result.blocks.len(), 2)
+    }
+
+    #[test]
+    fn keep_conditional_targets() {
+        // both blocks are only reached through a branch on a value read at runtime
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![Statement::assign("c", Expression::ReadBool)],
+                cont: Continuation::cond("c".into(), "block_0", "block_1"),
+            },
+        );
+        prog.add_block("block_0", Atom::Integer(1).into());
+        prog.add_block("block_1", Atom::Integer(2).into());
+        let mut with_dead = prog.clone();
+        with_dead.add_block("block_2", Atom::Integer(3).into());
+        let result = remove_unreachable(with_dead);
+        assert_eq!(result, prog)
+    }
+}
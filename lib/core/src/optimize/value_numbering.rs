@@ -0,0 +1,220 @@
+use crate::{Atom, Expression, Program, Statement};
+use definitions::{BinaryOperation, Comparator, UnaryOperation};
+use std::{collections::HashMap, mem::swap};
+
+type ValueNumber = usize;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum ValueKey {
+    Const(Atom),
+    Un(UnaryOperation, ValueNumber),
+    Bin(BinaryOperation, ValueNumber, ValueNumber),
+    Cmp(Comparator, ValueNumber, ValueNumber),
+}
+
+#[derive(Default)]
+struct ValueTable {
+    next: ValueNumber,
+    var_values: HashMap<String, ValueNumber>,
+    key_values: HashMap<ValueKey, ValueNumber>,
+    homes: HashMap<ValueNumber, Vec<String>>,
+}
+
+/// Local value numbering within each block
+/// Repeated computations are replaced by the variable already holding their value
+pub fn eliminate_common_subexpressions(mut prog: Program) -> Program {
+    let globals: Vec<String> = prog.globals.iter().map(|gl| gl.name.clone()).collect();
+    for block in prog.blocks.iter_mut() {
+        let mut table = ValueTable::default();
+        for stmt in block.tail.stmts.iter_mut() {
+            number_stmt(stmt, &mut table, &globals);
+        }
+    }
+    prog
+}
+
+fn number_stmt(stmt: &mut Statement, table: &mut ValueTable, globals: &[String]) {
+    let (var, bound) = match stmt {
+        Statement::Assign { var, bound } | Statement::Set { var, bound } => (var, bound),
+        Statement::Print { .. } | Statement::PrintNewline => return,
+    };
+    let value = match bound {
+        Expression::Atm(atm) => table.atom_value(atm),
+        Expression::UnaryOp { arg, op } => {
+            let key = ValueKey::Un(*op, table.atom_value(arg));
+            table.reuse(bound, key)
+        }
+        Expression::BinOp { fst, op, snd } => {
+            let mut fst_value = table.atom_value(fst);
+            let mut snd_value = table.atom_value(snd);
+            if op.is_commutative() && snd_value < fst_value {
+                swap(&mut fst_value, &mut snd_value);
+            }
+            let key = ValueKey::Bin(*op, fst_value, snd_value);
+            table.reuse(bound, key)
+        }
+        Expression::Cmp { left, cmp, right } => {
+            let mut left_value = table.atom_value(left);
+            let mut right_value = table.atom_value(right);
+            if cmp.is_commutative() && right_value < left_value {
+                swap(&mut left_value, &mut right_value);
+            }
+            let key = ValueKey::Cmp(*cmp, left_value, right_value);
+            table.reuse(bound, key)
+        }
+        Expression::Call { .. } => {
+            // external functions might write to globals
+            for global in globals {
+                table.var_values.remove(global);
+            }
+            table.fresh()
+        }
+        Expression::Str { .. }
+        | Expression::ReadInt
+        | Expression::ReadBool
+        | Expression::TryReadInt => table.fresh(),
+    };
+    table.bind(var, value);
+}
+
+impl ValueTable {
+    fn fresh(&mut self) -> ValueNumber {
+        let value = self.next;
+        self.next += 1;
+        value
+    }
+
+    fn atom_value(&mut self, atm: &Atom) -> ValueNumber {
+        match atm {
+            Atom::Variable(var) => match self.var_values.get(var) {
+                Some(value) => *value,
+                None => {
+                    let value = self.fresh();
+                    self.bind(var, value);
+                    value
+                }
+            },
+            atm => self.key_value(ValueKey::Const(atm.clone())),
+        }
+    }
+
+    fn key_value(&mut self, key: ValueKey) -> ValueNumber {
+        if let Some(value) = self.key_values.get(&key) {
+            return *value;
+        }
+        let value = self.fresh();
+        self.key_values.insert(key, value);
+        value
+    }
+
+    /// Replaces `bound` with a variable holding the same value, if there is one
+    fn reuse(&mut self, bound: &mut Expression, key: ValueKey) -> ValueNumber {
+        let value = self.key_value(key);
+        if let Some(home) = self.home(value) {
+            *bound = Atom::Variable(home.clone()).into();
+        }
+        value
+    }
+
+    fn home(&self, value: ValueNumber) -> Option<&String> {
+        self.homes
+            .get(&value)?
+            .iter()
+            .find(|var| self.var_values.get(*var) == Some(&value))
+    }
+
+    fn bind(&mut self, var: &str, value: ValueNumber) {
+        self.var_values.insert(var.to_owned(), value);
+        self.homes.entry(value).or_default().push(var.to_owned());
+    }
+}
+
+#[cfg(test)]
+mod value_numbering_tests {
+    use super::eliminate_common_subexpressions;
+    use crate::{Atom, Continuation, Expression, Program, Statement, Tail};
+    use definitions::{BinaryOperation, Comparator};
+
+    #[test]
+    fn cse_commutative_and_set() {
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::assign(
+                        "a",
+                        Expression::bin("x".into(), BinaryOperation::Add, 1.into()),
+                    ),
+                    Statement::assign(
+                        "b",
+                        Expression::bin(1.into(), BinaryOperation::Add, "x".into()),
+                    ),
+                    Statement::assign("c", Expression::cmp("a".into(), Comparator::Eq, "b".into())),
+                    Statement::assign("d", Expression::cmp("b".into(), Comparator::Eq, "a".into())),
+                    Statement::set("x", Atom::Integer(5).into()),
+                    Statement::assign(
+                        "e",
+                        Expression::bin("x".into(), BinaryOperation::Add, 1.into()),
+                    ),
+                ],
+                cont: Continuation::Return("e".into()),
+            },
+        );
+        let result = eliminate_common_subexpressions(prog);
+
+        let mut expected = Program::new();
+        expected.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::assign(
+                        "a",
+                        Expression::bin("x".into(), BinaryOperation::Add, 1.into()),
+                    ),
+                    Statement::assign("b", Atom::from("a").into()),
+                    Statement::assign("c", Expression::cmp("a".into(), Comparator::Eq, "b".into())),
+                    Statement::assign("d", Atom::from("c").into()),
+                    Statement::set("x", Atom::Integer(5).into()),
+                    Statement::assign(
+                        "e",
+                        Expression::bin("x".into(), BinaryOperation::Add, 1.into()),
+                    ),
+                ],
+                cont: Continuation::Return("e".into()),
+            },
+        );
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn cse_keep_after_set() {
+        // `a` no longer holds `x + y` once it is set, and neither does `b` once `y` is
+        let stmts = vec![
+            Statement::assign(
+                "a",
+                Expression::bin("x".into(), BinaryOperation::Add, "y".into()),
+            ),
+            Statement::set("a", Atom::Integer(0).into()),
+            Statement::assign(
+                "b",
+                Expression::bin("x".into(), BinaryOperation::Add, "y".into()),
+            ),
+            Statement::set("y", Expression::ReadInt),
+            Statement::assign(
+                "c",
+                Expression::bin("y".into(), BinaryOperation::Add, "x".into()),
+            ),
+        ];
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts,
+                cont: Continuation::Return("c".into()),
+            },
+        );
+        let result = eliminate_common_subexpressions(prog.clone());
+        assert_eq!(result, prog)
+    }
+}
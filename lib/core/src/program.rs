@@ -1,20 +1,29 @@
 use super::{Block, Tail};
-use std::fmt;
+use definitions::{Global, dataflow::ControlFlow};
+use std::{collections::HashMap, fmt};
 
 #[derive(Debug, Clone)]
 pub struct Program {
+    pub globals: Vec<Global>,
     pub blocks: Vec<Block>,
 }
 
 impl Program {
     pub fn new() -> Program {
-        Program { blocks: vec![] }
+        Program {
+            globals: vec![],
+            blocks: vec![],
+        }
     }
 
     pub fn add_block(&mut self, label: &str, tail: Tail) {
         self.blocks.push(Block::new(label, tail));
     }
 
+    pub fn get_block(&self, label: &str) -> Option<&Block> {
+        self.blocks.iter().find(|bl| bl.label == label)
+    }
+
     pub fn get_block_mut(&mut self, label: &str) -> Option<&mut Block> {
         self.blocks.iter_mut().find(|bl| bl.label == label)
     }
@@ -22,10 +31,62 @@ impl Program {
     pub fn get_labels(&self) -> Vec<&String> {
         self.blocks.iter().map(|block| &block.label).collect()
     }
+
+    pub fn predecessors(&self) -> HashMap<String, Vec<String>> {
+        let mut preds: HashMap<String, Vec<String>> = self
+            .blocks
+            .iter()
+            .map(|block| (block.label.clone(), vec![]))
+            .collect();
+        for block in self.blocks.iter() {
+            for succ in block.tail.cont.successors() {
+                preds
+                    .entry(succ.clone())
+                    .or_default()
+                    .push(block.label.clone());
+            }
+        }
+        preds
+    }
+
+    /// A `block_n` label not used by any block yet
+    pub fn fresh_label(&self) -> String {
+        (0..)
+            .map(|num| format!("block_{num}"))
+            .find(|label| self.get_block(label).is_none())
+            .unwrap_or_default()
+    }
+
+    /// Labels of blocks reachable from `start`, in reverse postorder
+    pub fn reverse_postorder(&self) -> Vec<String> {
+        ControlFlow::reverse_postorder(self, "start")
+    }
+
+    pub fn is_global(&self, var: &str) -> bool {
+        self.globals.iter().any(|global| global.name == var)
+    }
+}
+
+impl ControlFlow for Program {
+    fn labels(&self) -> Vec<String> {
+        self.blocks
+            .iter()
+            .map(|block| block.label.clone())
+            .collect()
+    }
+
+    fn successors(&self, label: &str) -> Vec<String> {
+        self.get_block(label)
+            .map(|block| block.tail.cont.successors().into_iter().cloned().collect())
+            .unwrap_or_default()
+    }
 }
 
 impl PartialEq for Program {
     fn eq(&self, other: &Program) -> bool {
+        if self.globals != other.globals {
+            return false;
+        }
         for block in self.blocks.iter() {
             match other.blocks.iter().find(|bl| bl.label == block.label) {
                 None => return false,
@@ -44,6 +105,9 @@ impl Eq for Program {}
 
 impl fmt::Display for Program {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for global in self.globals.iter() {
+            writeln!(f, "{global}")?;
+        }
         for block in self.blocks.iter() {
             block.fmt(f)?;
             writeln!(f)?;
@@ -0,0 +1,189 @@
+use super::{Phi, SsaBlock, SsaProgram};
+use crate::{Atom, Dominators, Program, Statement, liveness::live_in};
+use std::collections::{HashMap, HashSet};
+
+/// Places phi nodes on the iterated dominance frontier of every definition,
+/// pruned to blocks where the variable is live, then renames along the dominator tree
+/// Globals live in memory and keep their names, unreachable blocks are dropped
+pub fn into_ssa(prog: Program) -> SsaProgram {
+    let doms = Dominators::new(&prog);
+    let phi_vars = place_phis(&prog, &doms);
+    let blocks = doms
+        .order
+        .iter()
+        .filter_map(|label| prog.get_block(label))
+        .map(|block| {
+            let phis = phi_vars[&block.label]
+                .iter()
+                .map(|var| Phi::new(var))
+                .collect();
+            (
+                block.label.clone(),
+                SsaBlock {
+                    label: block.label.clone(),
+                    phis,
+                    tail: block.tail.clone(),
+                },
+            )
+        })
+        .collect();
+
+    let mut renamer = Renamer {
+        prog: &prog,
+        children: doms.children(),
+        phi_vars,
+        blocks,
+        stacks: HashMap::new(),
+        versions: HashMap::new(),
+    };
+    if !doms.order.is_empty() {
+        renamer.rename_block("start");
+    }
+
+    let mut blocks = renamer.blocks;
+    SsaProgram {
+        globals: prog.globals.clone(),
+        blocks: prog
+            .blocks
+            .iter()
+            .filter_map(|block| blocks.remove(&block.label))
+            .collect(),
+    }
+}
+
+/// The variables that need a phi node at the start of each reachable block
+fn place_phis(prog: &Program, doms: &Dominators) -> HashMap<String, Vec<String>> {
+    let frontiers = doms.frontiers(prog);
+    let live_in = live_in(prog);
+
+    let mut def_blocks: HashMap<&String, HashSet<&String>> = HashMap::new();
+    for block in doms.order.iter().filter_map(|label| prog.get_block(label)) {
+        for var in block.tail.stmts.iter().filter_map(Statement::defined_var) {
+            if !prog.is_global(var) {
+                def_blocks.entry(var).or_default().insert(&block.label);
+            }
+        }
+    }
+    let mut vars: Vec<&String> = def_blocks.keys().copied().collect();
+    vars.sort();
+
+    let mut phi_vars: HashMap<String, Vec<String>> = doms
+        .order
+        .iter()
+        .map(|label| (label.clone(), vec![]))
+        .collect();
+    for var in vars {
+        let defs = &def_blocks[var];
+        let mut placed = HashSet::new();
+        let mut todo: Vec<&String> = defs.iter().copied().collect();
+        while let Some(label) = todo.pop() {
+            for frontier in frontiers[label].iter() {
+                if !live_in[frontier].contains(var) || !placed.insert(frontier) {
+                    continue;
+                }
+                phi_vars
+                    .entry(frontier.clone())
+                    .or_default()
+                    .push(var.clone());
+                if !defs.contains(frontier) {
+                    todo.push(frontier);
+                }
+            }
+        }
+    }
+    phi_vars
+}
+
+struct Renamer<'a> {
+    prog: &'a Program,
+    children: HashMap<String, Vec<String>>,
+    /// Original names of the phi nodes in each block
+    phi_vars: HashMap<String, Vec<String>>,
+    blocks: HashMap<String, SsaBlock>,
+    /// The current version of each variable along the path from `start`
+    stacks: HashMap<String, Vec<String>>,
+    versions: HashMap<String, usize>,
+}
+
+impl Renamer<'_> {
+    fn rename_block(&mut self, label: &str) {
+        let Some(mut block) = self.blocks.remove(label) else {
+            return;
+        };
+        let mut defined = vec![];
+
+        for (phi, var) in block.phis.iter_mut().zip(self.phi_vars[label].clone()) {
+            phi.var = self.define(&var);
+            defined.push(var);
+        }
+        for stmt in block.tail.stmts.iter_mut() {
+            for atm in stmt.atoms_mut() {
+                self.rename_atom(atm);
+            }
+            if let Statement::Assign { var, bound } | Statement::Set { var, bound } = stmt
+                && !self.prog.is_global(var)
+            {
+                let var = var.clone();
+                let bound = bound.clone();
+                *stmt = Statement::assign(&self.define(&var), bound);
+                defined.push(var);
+            }
+        }
+        for atm in block.tail.cont.atoms_mut() {
+            self.rename_atom(atm);
+        }
+
+        let mut succs: Vec<String> = block.tail.cont.successors().into_iter().cloned().collect();
+        succs.dedup();
+        self.blocks.insert(label.to_owned(), block);
+        for succ in succs {
+            let args: Vec<Atom> = self
+                .phi_vars
+                .get(&succ)
+                .into_iter()
+                .flatten()
+                .map(|var| self.current(var))
+                .collect();
+            if let Some(succ_block) = self.blocks.get_mut(&succ) {
+                for (phi, arg) in succ_block.phis.iter_mut().zip(args) {
+                    phi.args.push((label.to_owned(), arg));
+                }
+            }
+        }
+
+        for child in self.children[label].clone() {
+            self.rename_block(&child);
+        }
+        for var in defined {
+            self.stacks.entry(var).or_default().pop();
+        }
+    }
+
+    fn define(&mut self, var: &str) -> String {
+        let version = self.versions.entry(var.to_owned()).or_default();
+        let name = format!("{var}.{version}");
+        *version += 1;
+        self.stacks
+            .entry(var.to_owned())
+            .or_default()
+            .push(name.clone());
+        name
+    }
+
+    /// Variables without a definition on the current path keep their name
+    fn current(&self, var: &str) -> Atom {
+        let name = self
+            .stacks
+            .get(var)
+            .and_then(|stack| stack.last())
+            .map(String::as_str)
+            .unwrap_or(var);
+        Atom::Variable(name.to_owned())
+    }
+
+    fn rename_atom(&self, atm: &mut Atom) {
+        if let Atom::Variable(var) = atm {
+            *atm = self.current(var);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use super::{Phi, SsaProgram};
+use crate::{Atom, Continuation, Program, Statement, Tail};
+
+/// Replaces phi nodes by copies at the end of each predecessor
+/// Edges leaving a branch are split first so the copies only run on that edge,
+/// the copies of one edge happen in parallel and are sequentialized
+pub fn from_ssa(prog: SsaProgram) -> Program {
+    let mut fresh_tmp = 0;
+    let mut all_phis: Vec<(String, Vec<Phi>)> = vec![];
+    let mut result = Program {
+        globals: prog.globals,
+        blocks: vec![],
+    };
+    for block in prog.blocks {
+        result.add_block(&block.label, block.tail);
+        all_phis.push((block.label, block.phis));
+    }
+
+    for (label, phis) in all_phis {
+        let Some(fst) = phis.first() else {
+            continue;
+        };
+        let preds: Vec<String> = fst.args.iter().map(|(pred, _)| pred.clone()).collect();
+        for pred in preds {
+            let copies = phis
+                .iter()
+                .filter_map(|phi| {
+                    let (_, arg) = phi.args.iter().find(|(from, _)| *from == pred)?;
+                    Some((phi.var.clone(), arg.clone()))
+                })
+                .collect();
+            let copies = sequentialize(copies, &mut fresh_tmp);
+            let edge_label = result.fresh_label();
+            let Some(pred_block) = result.get_block_mut(&pred) else {
+                continue;
+            };
+            if pred_block.tail.cont.successors().len() < 2 {
+                pred_block.tail.stmts.extend(copies);
+                continue;
+            }
+            pred_block.tail.cont.redirect(&label, &edge_label);
+            result.add_block(
+                &edge_label,
+                Tail {
+                    stmts: copies,
+                    cont: Continuation::Goto(label.clone()),
+                },
+            );
+        }
+    }
+    result
+}
+
+/// Orders copies `var = atom` that are meant to happen at the same time,
+/// cycles are broken with a temporary
+fn sequentialize(mut copies: Vec<(String, Atom)>, fresh_tmp: &mut usize) -> Vec<Statement> {
+    copies.retain(|(var, atm)| atm.as_var() != Some(var));
+    let mut stmts = vec![];
+    while !copies.is_empty() {
+        let ready = copies
+            .iter()
+            .position(|(var, _)| !copies.iter().any(|(_, atm)| atm.as_var() == Some(var)));
+        match ready {
+            Some(ind) => {
+                let (var, atm) = copies.remove(ind);
+                stmts.push(Statement::assign(&var, atm.into()));
+            }
+            None => {
+                let var = copies[0].0.clone();
+                let tmp = format!("phi.tmp{fresh_tmp}");
+                *fresh_tmp += 1;
+                stmts.push(Statement::assign(&tmp, Atom::Variable(var.clone()).into()));
+                for (_, atm) in copies.iter_mut() {
+                    if atm.as_var() == Some(&var) {
+                        *atm = Atom::Variable(tmp.clone());
+                    }
+                }
+            }
+        }
+    }
+    stmts
+}
@@ -0,0 +1,279 @@
+use crate::{Atom, Program, Tail};
+use definitions::Global;
+use std::fmt;
+
+mod construct;
+mod destruct;
+
+pub use construct::into_ssa;
+pub use destruct::from_ssa;
+
+/// Core program in static single assignment form
+/// Every local variable is assigned exactly once,
+/// values merging at join points are selected by phi nodes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsaProgram {
+    pub globals: Vec<Global>,
+    pub blocks: Vec<SsaBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SsaBlock {
+    pub label: String,
+    pub phis: Vec<Phi>,
+    pub tail: Tail,
+}
+
+/// `var` takes the atom belonging to the predecessor control came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phi {
+    pub var: String,
+    pub args: Vec<(String, Atom)>,
+}
+
+impl SsaProgram {
+    pub fn get_block(&self, label: &str) -> Option<&SsaBlock> {
+        self.blocks.iter().find(|bl| bl.label == label)
+    }
+}
+
+/// Program without phi nodes, which translating out of SSA form leaves unchanged
+impl From<Program> for SsaProgram {
+    fn from(prog: Program) -> SsaProgram {
+        SsaProgram {
+            globals: prog.globals,
+            blocks: prog
+                .blocks
+                .into_iter()
+                .map(|block| SsaBlock {
+                    label: block.label,
+                    phis: vec![],
+                    tail: block.tail,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Phi {
+    pub fn new(var: &str) -> Phi {
+        Phi {
+            var: var.to_owned(),
+            args: vec![],
+        }
+    }
+}
+
+impl fmt::Display for SsaProgram {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for global in self.globals.iter() {
+            writeln!(f, "{global}")?;
+        }
+        for block in self.blocks.iter() {
+            writeln!(f, "{block}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SsaBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}:", self.label)?;
+        for phi in self.phis.iter() {
+            writeln!(f, "\t{phi}")?;
+        }
+        let tail_str = self.tail.to_string().replace("\n", "\n\t");
+        write!(f, "\t{tail_str}")
+    }
+}
+
+impl fmt::Display for Phi {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} = phi({});",
+            self.var,
+            self.args
+                .iter()
+                .map(|(pred, atm)| format!("{pred}: {atm}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod ssa_tests {
+    use super::{Phi, SsaBlock, SsaProgram, from_ssa, into_ssa};
+    use crate::{Atom, Continuation, Expression, Program, Statement, Tail};
+    use definitions::{BinaryOperation, Comparator, PrintKind};
+
+    fn phi(var: &str, args: &[(&str, &str)]) -> Phi {
+        Phi {
+            var: var.to_owned(),
+            args: args
+                .iter()
+                .map(|(pred, arg)| (pred.to_string(), Atom::from(*arg)))
+                .collect(),
+        }
+    }
+
+    fn loop_cond(var: &str) -> Continuation {
+        Continuation::branch(var.into(), Comparator::Gt, 0.into(), "block_0", "block_1")
+    }
+
+    #[test]
+    fn ssa_while_loop() {
+        let mut prog = Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::set("x", Atom::Integer(2).into()),
+                    Statement::set("y", Atom::Integer(0).into()),
+                ],
+                cont: loop_cond("x"),
+            },
+        );
+        prog.add_block(
+            "block_0",
+            Tail {
+                stmts: vec![
+                    Statement::set(
+                        "x",
+                        Expression::bin("x".into(), BinaryOperation::Sub, 1.into()),
+                    ),
+                    Statement::set(
+                        "y",
+                        Expression::bin("y".into(), BinaryOperation::Add, 1.into()),
+                    ),
+                ],
+                cont: loop_cond("x"),
+            },
+        );
+        prog.add_block(
+            "block_1",
+            Tail {
+                stmts: vec![Statement::print(PrintKind::Int, "y".into())],
+                cont: Continuation::Return(Atom::Unit),
+            },
+        );
+        let result = into_ssa(prog);
+
+        let expected = SsaProgram {
+            globals: vec![],
+            blocks: vec![
+                SsaBlock {
+                    label: "start".to_owned(),
+                    phis: vec![],
+                    tail: Tail {
+                        stmts: vec![
+                            Statement::assign("x.0", Atom::Integer(2).into()),
+                            Statement::assign("y.0", Atom::Integer(0).into()),
+                        ],
+                        cont: loop_cond("x.0"),
+                    },
+                },
+                SsaBlock {
+                    label: "block_0".to_owned(),
+                    phis: vec![
+                        phi("x.1", &[("start", "x.0"), ("block_0", "x.2")]),
+                        phi("y.1", &[("start", "y.0"), ("block_0", "y.2")]),
+                    ],
+                    tail: Tail {
+                        stmts: vec![
+                            Statement::assign(
+                                "x.2",
+                                Expression::bin("x.1".into(), BinaryOperation::Sub, 1.into()),
+                            ),
+                            Statement::assign(
+                                "y.2",
+                                Expression::bin("y.1".into(), BinaryOperation::Add, 1.into()),
+                            ),
+                        ],
+                        cont: loop_cond("x.2"),
+                    },
+                },
+                SsaBlock {
+                    label: "block_1".to_owned(),
+                    phis: vec![phi("y.3", &[("start", "y.0"), ("block_0", "y.2")])],
+                    tail: Tail {
+                        stmts: vec![Statement::print(PrintKind::Int, "y.3".into())],
+                        cont: Continuation::Return(Atom::Unit),
+                    },
+                },
+            ],
+        };
+        assert_eq!(result, expected);
+
+        let out = from_ssa(result);
+        let edge = out.get_block("block_2").unwrap();
+        assert_eq!(
+            edge.tail.stmts,
+            vec![
+                Statement::assign("x.1", Atom::from("x.0").into()),
+                Statement::assign("y.1", Atom::from("y.0").into()),
+            ]
+        );
+        assert_eq!(edge.tail.cont, Continuation::Goto("block_0".to_owned()));
+        assert_eq!(
+            out.get_block("start").unwrap().tail.cont,
+            Continuation::branch("x.0".into(), Comparator::Gt, 0.into(), "block_2", "block_4")
+        );
+        assert_eq!(out.blocks.len(), 7);
+    }
+
+    #[test]
+    fn out_of_ssa_swap() {
+        let prog = SsaProgram {
+            globals: vec![],
+            blocks: vec![
+                SsaBlock {
+                    label: "start".to_owned(),
+                    phis: vec![],
+                    tail: Tail {
+                        stmts: vec![],
+                        cont: Continuation::Goto("block_0".to_owned()),
+                    },
+                },
+                SsaBlock {
+                    label: "block_0".to_owned(),
+                    phis: vec![
+                        phi("a.1", &[("start", "a"), ("block_0", "b.1")]),
+                        phi("b.1", &[("start", "b"), ("block_0", "a.1")]),
+                    ],
+                    tail: Tail {
+                        stmts: vec![Statement::print(PrintKind::Int, "a.1".into())],
+                        cont: Continuation::Goto("block_0".to_owned()),
+                    },
+                },
+            ],
+        };
+        let result = from_ssa(prog);
+
+        let mut expected = Program::new();
+        expected.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::assign("a.1", Atom::from("a").into()),
+                    Statement::assign("b.1", Atom::from("b").into()),
+                ],
+                cont: Continuation::Goto("block_0".to_owned()),
+            },
+        );
+        expected.add_block(
+            "block_0",
+            Tail {
+                stmts: vec![
+                    Statement::print(PrintKind::Int, "a.1".into()),
+                    Statement::assign("phi.tmp0", Atom::from("a.1").into()),
+                    Statement::assign("a.1", Atom::from("b.1").into()),
+                    Statement::assign("b.1", Atom::from("phi.tmp0").into()),
+                ],
+                cont: Continuation::Goto("block_0".to_owned()),
+            },
+        );
+        assert_eq!(result, expected)
+    }
+}
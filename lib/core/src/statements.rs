@@ -1,15 +1,20 @@
 use super::{Atom, Expression};
-use definitions::PRINT_CALL;
+use definitions::{PRINT_NEWLINE_CALL, PrintKind};
 use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Statement {
     Assign { var: String, bound: Expression },
     Set { var: String, bound: Expression },
-    Print(Atom),
+    Print { kind: PrintKind, arg: Atom },
+    PrintNewline,
 }
 
 impl Statement {
+    pub fn print(kind: PrintKind, arg: Atom) -> Statement {
+        Statement::Print { kind, arg }
+    }
+
     pub fn assign(var: &str, bound: Expression) -> Statement {
         Statement::Assign {
             var: var.to_owned(),
@@ -23,6 +28,32 @@ impl Statement {
             bound,
         }
     }
+
+    /// The variable written by this statement
+    pub fn defined_var(&self) -> Option<&String> {
+        match self {
+            Statement::Assign { var, .. } | Statement::Set { var, .. } => Some(var),
+            Statement::Print { .. } | Statement::PrintNewline => None,
+        }
+    }
+
+    /// The atoms read by this statement
+    pub fn atoms_mut(&mut self) -> Vec<&mut Atom> {
+        match self {
+            Statement::Assign { bound, .. } | Statement::Set { bound, .. } => bound.atoms_mut(),
+            Statement::Print { arg, .. } => vec![arg],
+            Statement::PrintNewline => vec![],
+        }
+    }
+
+    /// Variables read by this statement
+    pub fn used_vars(&self) -> Vec<&String> {
+        match self {
+            Statement::Assign { bound, .. } | Statement::Set { bound, .. } => bound.used_vars(),
+            Statement::Print { arg, .. } => arg.as_var().into_iter().collect(),
+            Statement::PrintNewline => vec![],
+        }
+    }
 }
 
 impl fmt::Display for Statement {
@@ -30,7 +61,8 @@ impl fmt::Display for Statement {
         match self {
             Statement::Assign { var, bound } => write!(f, "{var} = {bound};"),
             Statement::Set { var, bound } => write!(f, "{var} := {bound};"),
-            Statement::Print(exp) => write!(f, "{PRINT_CALL}({exp});"),
+            Statement::Print { kind, arg } => write!(f, "{kind}({arg});"),
+            Statement::PrintNewline => write!(f, "{PRINT_NEWLINE_CALL}();"),
         }
     }
 }
@@ -20,21 +20,22 @@ impl SelectInstructions for core::Continuation {
             }
             core::Continuation::Goto(label) => vec![asm::Instruction::Jump { label }],
             core::Continuation::If {
-                cond,
+                left,
+                cmp,
+                right,
                 then_label,
                 else_label,
             } => {
-                let cond_dest = cond.select_instructions(());
-                let cmp = asm::Instruction::CmpQ {
-                    left: cond_dest,
-                    right: 1.into(),
+                let cmp_instr = asm::Instruction::CmpQ {
+                    left: left.select_instructions(()),
+                    right: right.select_instructions(()),
                 };
                 let jump_true = asm::Instruction::JumpCC {
-                    cc: asm::Cc::E,
+                    cc: cmp.select_instructions(()),
                     label: then_label,
                 };
                 let jump_false = asm::Instruction::Jump { label: else_label };
-                vec![cmp, jump_true, jump_false]
+                vec![cmp_instr, jump_true, jump_false]
             }
         }
     }
@@ -1,5 +1,8 @@
 use super::SelectInstructions;
-use definitions::{BinaryOperation, READ_INT_CALL, UnaryOperation};
+use definitions::{
+    BinaryOperation, READ_BOOL_CALL, READ_INT_CALL, STRING_CONCAT_CALL, STRING_LENGTH_CALL,
+    TRY_READ_INT_CALL, UnaryOperation,
+};
 
 impl SelectInstructions for core::Expression {
     type Target = Vec<asm::Instruction<asm::VarArg>>;
@@ -10,15 +13,17 @@ impl SelectInstructions for core::Expression {
                 src: atm.select_instructions(()),
                 dest,
             }],
-            core::Expression::ReadInt => vec![
-                asm::Instruction::CallQ {
-                    label: READ_INT_CALL.to_owned(),
-                },
-                asm::Instruction::MovQ {
-                    src: asm::Reg::Rax.into(),
-                    dest,
-                },
-            ],
+            core::Expression::Str { label, .. } => vec![asm::Instruction::LeaQ { label, dest }],
+            core::Expression::ReadInt => call_result(READ_INT_CALL, vec![], dest),
+            core::Expression::ReadBool => call_result(READ_BOOL_CALL, vec![], dest),
+            core::Expression::TryReadInt => call_result(TRY_READ_INT_CALL, vec![], dest),
+            core::Expression::Call { name, args } => {
+                let arg_locs = args
+                    .into_iter()
+                    .map(|arg| arg.select_instructions(()))
+                    .collect();
+                call_result(&name, arg_locs, dest)
+            }
             core::Expression::UnaryOp { arg, op } => {
                 let arg_loc = arg.select_instructions(());
                 match op {
@@ -39,6 +44,7 @@ impl SelectInstructions for core::Expression {
                             dest,
                         },
                     ],
+                    UnaryOperation::Length => call_result(STRING_LENGTH_CALL, vec![arg_loc], dest),
                 }
             }
             core::Expression::BinOp { fst, op, snd } => {
@@ -73,6 +79,9 @@ impl SelectInstructions for core::Expression {
                         },
                         asm::Instruction::OrQ { src: snd_loc, dest },
                     ],
+                    BinaryOperation::Concat => {
+                        call_result(STRING_CONCAT_CALL, vec![fst_loc, snd_loc], dest)
+                    }
                 }
             }
             core::Expression::Cmp { left, cmp, right } => {
@@ -97,3 +106,59 @@ impl SelectInstructions for core::Expression {
         }
     }
 }
+
+fn call_result(
+    label: &str,
+    args: Vec<asm::VarArg>,
+    dest: asm::VarArg,
+) -> Vec<asm::Instruction<asm::VarArg>> {
+    let mut instrs = select_call(label, args);
+    instrs.push(asm::Instruction::MovQ {
+        src: asm::Reg::Rax.into(),
+        dest,
+    });
+    instrs
+}
+
+/// Calls `label` following the System V calling convention.
+/// The first six arguments are passed in registers, the rest on the stack.
+pub(crate) fn select_call(
+    label: &str,
+    mut args: Vec<asm::VarArg>,
+) -> Vec<asm::Instruction<asm::VarArg>> {
+    let arg_regs = asm::Reg::arg_registers();
+    let stack_args = args.split_off(args.len().min(arg_regs.len()));
+    let arity = args.len();
+    let mut instrs = vec![];
+    for (arg, reg) in args.into_iter().zip(arg_regs) {
+        instrs.push(asm::Instruction::MovQ {
+            src: arg,
+            dest: reg.into(),
+        });
+    }
+
+    // keep the stack 16-byte aligned at the call
+    let padding = stack_args.len() % 2;
+    if padding == 1 {
+        instrs.push(asm::Instruction::SubQ {
+            src: 8.into(),
+            dest: asm::Reg::Rsp.into(),
+        });
+    }
+    let stack_space = 8 * (stack_args.len() + padding) as i64;
+    for arg in stack_args.into_iter().rev() {
+        instrs.push(asm::Instruction::PushQ { arg });
+    }
+
+    instrs.push(asm::Instruction::CallQ {
+        label: label.to_owned(),
+        arity,
+    });
+    if stack_space > 0 {
+        instrs.push(asm::Instruction::AddQ {
+            src: stack_space.into(),
+            dest: asm::Reg::Rsp.into(),
+        });
+    }
+    instrs
+}
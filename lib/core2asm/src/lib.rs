@@ -31,7 +31,7 @@ impl SelectInstructions for Comparator {
 #[cfg(test)]
 mod select_instructions_tests {
     use super::SelectInstructions;
-    use definitions::{BinaryOperation, Comparator, UnaryOperation};
+    use definitions::{BinaryOperation, Comparator, Global, UnaryOperation};
 
     #[test]
     fn select_sum() {
@@ -133,18 +133,14 @@ mod select_instructions_tests {
         prog.add_block(
             "start",
             core::Tail {
-                stmts: vec![
-                    core::Statement::assign("x0", core::Expression::ReadInt),
-                    core::Statement::assign(
-                        "x1",
-                        core::Expression::cmp("x0".into(), Comparator::Eq, 1.into()),
-                    ),
-                ],
-                cont: core::Continuation::If {
-                    cond: "x1".into(),
-                    then_label: "block_0".to_owned(),
-                    else_label: "block_1".to_owned(),
-                },
+                stmts: vec![core::Statement::assign("x0", core::Expression::ReadInt)],
+                cont: core::Continuation::branch(
+                    "x0".into(),
+                    Comparator::Eq,
+                    1.into(),
+                    "block_0",
+                    "block_1",
+                ),
             },
         );
         prog.add_block(
@@ -168,6 +164,7 @@ mod select_instructions_tests {
             vec![
                 asm::Instruction::CallQ {
                     label: "read_int".to_owned(),
+                    arity: 0,
                 },
                 asm::Instruction::MovQ {
                     src: asm::Reg::Rax.into(),
@@ -177,18 +174,6 @@ mod select_instructions_tests {
                     left: "x0".into(),
                     right: 1.into(),
                 },
-                asm::Instruction::SetCC {
-                    cc: asm::Cc::E,
-                    dest: asm::Arg::ByteReg(asm::ByteReg::Al).into(),
-                },
-                asm::Instruction::MovZBQ {
-                    src: asm::Arg::ByteReg(asm::ByteReg::Al).into(),
-                    dest: "x1".into(),
-                },
-                asm::Instruction::CmpQ {
-                    left: "x1".into(),
-                    right: 1.into(),
-                },
                 asm::Instruction::JumpCC {
                     cc: asm::Cc::E,
                     label: "block_0".to_owned(),
@@ -224,4 +209,98 @@ mod select_instructions_tests {
         );
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn select_call_stack_args() {
+        let mut prog = core::Program::new();
+        prog.add_block(
+            "start",
+            core::Tail {
+                stmts: vec![core::Statement::assign(
+                    "x0",
+                    core::Expression::call("f", (1..=7).map(|i| i.into()).collect()),
+                )],
+                cont: core::Continuation::Return("x0".into()),
+            },
+        );
+        let result = prog.select_instructions(());
+        let mut expected = asm::VarProgram::new();
+        let mut instrs: Vec<asm::Instruction<asm::VarArg>> = asm::Reg::arg_registers()
+            .into_iter()
+            .zip(1..)
+            .map(|(reg, i)| asm::Instruction::MovQ {
+                src: i.into(),
+                dest: reg.into(),
+            })
+            .collect();
+        instrs.extend([
+            asm::Instruction::SubQ {
+                src: 8.into(),
+                dest: asm::Reg::Rsp.into(),
+            },
+            asm::Instruction::PushQ { arg: 7.into() },
+            asm::Instruction::CallQ {
+                label: "f".to_owned(),
+                arity: 6,
+            },
+            asm::Instruction::AddQ {
+                src: 16.into(),
+                dest: asm::Reg::Rsp.into(),
+            },
+            asm::Instruction::MovQ {
+                src: asm::Reg::Rax.into(),
+                dest: "x0".into(),
+            },
+            asm::Instruction::MovQ {
+                src: "x0".into(),
+                dest: asm::Reg::Rax.into(),
+            },
+            asm::Instruction::Jump {
+                label: "conclusion".to_owned(),
+            },
+        ]);
+        expected.add_block("start", instrs);
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn select_global() {
+        let mut prog = core::Program::new();
+        prog.globals.push(Global::new("counter", 0));
+        prog.add_block(
+            "start",
+            core::Tail {
+                stmts: vec![core::Statement::set(
+                    "counter",
+                    core::Expression::bin("counter".into(), BinaryOperation::Add, 1.into()),
+                )],
+                cont: core::Continuation::Return(0.into()),
+            },
+        );
+        let result = prog.select_instructions(());
+        let mut expected = asm::VarProgram::new();
+        expected.globals.push(Global::new("counter", 0));
+        let global: asm::VarArg = asm::Arg::Global("global_counter".to_owned()).into();
+        expected.add_block(
+            "start",
+            vec![
+                asm::Instruction::MovQ {
+                    src: global.clone(),
+                    dest: global.clone(),
+                },
+                asm::Instruction::AddQ {
+                    src: 1.into(),
+                    dest: global,
+                },
+                asm::Instruction::MovQ {
+                    src: 0.into(),
+                    dest: asm::Reg::Rax.into(),
+                },
+                asm::Instruction::Jump {
+                    label: "conclusion".to_owned(),
+                },
+            ],
+        );
+        assert_eq!(result, expected)
+    }
 }
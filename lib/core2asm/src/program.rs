@@ -1,13 +1,54 @@
 use super::SelectInstructions;
+use std::collections::HashMap;
 
 impl SelectInstructions for core::Program {
     type Target = asm::VarProgram;
     type Arg = ();
     fn select_instructions(self, _: Self::Arg) -> Self::Target {
         let mut asm_prog = asm::VarProgram::new();
+        asm_prog.strings = collect_strings(&self);
+        let global_labels: HashMap<String, String> = self
+            .globals
+            .iter()
+            .map(|global| (global.name.clone(), global.label()))
+            .collect();
         for block in self.blocks {
-            asm_prog.add_block(&block.label, block.tail.select_instructions(()));
+            let instrs = block
+                .tail
+                .select_instructions(())
+                .into_iter()
+                .map(|instr| instr.map_args(|arg| global_arg(arg, &global_labels)))
+                .collect();
+            asm_prog.add_block(&block.label, instrs);
         }
+        asm_prog.globals = self.globals;
         asm_prog
     }
 }
+
+fn global_arg(arg: asm::VarArg, global_labels: &HashMap<String, String>) -> asm::VarArg {
+    match arg {
+        asm::VarArg::Var(v) => match global_labels.get(&v) {
+            Some(label) => asm::Arg::Global(label.clone()).into(),
+            None => asm::VarArg::Var(v),
+        },
+        arg => arg,
+    }
+}
+
+fn collect_strings(prog: &core::Program) -> Vec<asm::StringLiteral> {
+    let mut strings: Vec<asm::StringLiteral> = vec![];
+    for stmt in prog.blocks.iter().flat_map(|block| block.tail.stmts.iter()) {
+        let bound = match stmt {
+            core::Statement::Assign { bound, .. } => bound,
+            core::Statement::Set { bound, .. } => bound,
+            core::Statement::Print { .. } | core::Statement::PrintNewline => continue,
+        };
+        if let core::Expression::Str { label, value } = bound
+            && !strings.iter().any(|string| string.label == *label)
+        {
+            strings.push(asm::StringLiteral::new(label, value));
+        }
+    }
+    strings
+}
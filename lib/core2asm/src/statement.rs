@@ -1,5 +1,5 @@
-use super::SelectInstructions;
-use definitions::PRINT_CALL;
+use super::{SelectInstructions, expression::select_call};
+use definitions::PRINT_NEWLINE_CALL;
 
 impl SelectInstructions for core::Statement {
     type Target = Vec<asm::Instruction<asm::VarArg>>;
@@ -10,17 +10,11 @@ impl SelectInstructions for core::Statement {
                 bound.select_instructions(asm::VarArg::Var(var))
             }
             core::Statement::Set { var, bound } => bound.select_instructions(asm::VarArg::Var(var)),
-            core::Statement::Print(atm) => {
-                let arg_loc = atm.select_instructions(());
-                let mov = asm::Instruction::MovQ {
-                    src: arg_loc,
-                    dest: asm::Reg::Rdi.into(),
-                };
-                let print = asm::Instruction::CallQ {
-                    label: PRINT_CALL.to_owned(),
-                };
-                vec![mov, print]
+            core::Statement::Print { kind, arg } => {
+                let arg_loc = arg.select_instructions(());
+                select_call(kind.call(), vec![arg_loc])
             }
+            core::Statement::PrintNewline => select_call(PRINT_NEWLINE_CALL, vec![]),
         }
     }
 }
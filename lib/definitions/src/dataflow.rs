@@ -0,0 +1,413 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    hash::Hash,
+};
+
+/// Values of a dataflow analysis, `join` only ever moves up
+pub trait Lattice: Clone + PartialEq {
+    fn bottom() -> Self;
+    fn join(&mut self, other: &Self);
+}
+
+/// Sets joined by union, as used by liveness and reaching definitions
+impl<T: Clone + Eq + Hash> Lattice for HashSet<T> {
+    fn bottom() -> Self {
+        HashSet::new()
+    }
+
+    fn join(&mut self, other: &Self) {
+        self.extend(other.iter().cloned())
+    }
+}
+
+/// Sets joined by intersection, as used by available expressions
+/// `All` is the bottom element, so facts only shrink as the analysis runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MustSet<T: Eq + Hash> {
+    All,
+    Set(HashSet<T>),
+}
+
+impl<T: Clone + Eq + Hash> MustSet<T> {
+    pub fn contains(&self, elem: &T) -> bool {
+        match self {
+            MustSet::All => true,
+            MustSet::Set(set) => set.contains(elem),
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Lattice for MustSet<T> {
+    fn bottom() -> Self {
+        MustSet::All
+    }
+
+    fn join(&mut self, other: &Self) {
+        match (&mut *self, other) {
+            (_, MustSet::All) => (),
+            (MustSet::All, other) => *self = other.clone(),
+            (MustSet::Set(set), MustSet::Set(other)) => set.retain(|elem| other.contains(elem)),
+        }
+    }
+}
+
+/// Labelled blocks connected by jumps
+pub trait ControlFlow {
+    /// Labels of all blocks in program order
+    fn labels(&self) -> Vec<String>;
+    fn successors(&self, label: &str) -> Vec<String>;
+
+    /// Labels reachable from `entry`, in reverse postorder
+    fn reverse_postorder(&self, entry: &str) -> Vec<String> {
+        let labels: HashSet<String> = self.labels().into_iter().collect();
+        let mut order = vec![];
+        if !labels.contains(entry) {
+            return order;
+        }
+        let mut visited = HashSet::from([entry.to_owned()]);
+        let mut stack = vec![(entry.to_owned(), 0)];
+        while let Some((label, next)) = stack.pop() {
+            match self.successors(&label).get(next).cloned() {
+                Some(succ) => {
+                    stack.push((label, next + 1));
+                    if labels.contains(&succ) && visited.insert(succ.clone()) {
+                        stack.push((succ, 0));
+                    }
+                }
+                None => order.push(label),
+            }
+        }
+        order.reverse();
+        order
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+pub trait Analysis {
+    type Fact: Lattice;
+    const DIRECTION: Direction;
+
+    /// Fact flowing into the entry block of a forward analysis,
+    /// or out of the blocks without successors of a backward analysis
+    fn initial(&self) -> Self::Fact {
+        Self::Fact::bottom()
+    }
+
+    /// Fact on the other side of the block with `label`,
+    /// given the joined facts flowing into it
+    fn transfer(&self, label: &str, fact: &Self::Fact) -> Self::Fact;
+}
+
+/// Facts at the start and end of every block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Facts<F> {
+    pub entry: HashMap<String, F>,
+    pub exit: HashMap<String, F>,
+}
+
+/// Runs `analysis` to a fixpoint with a worklist ordered by reverse postorder
+/// from `entry` (postorder for backward analyses),
+/// blocks not reachable from `entry` are visited last
+pub fn solve<A: Analysis>(analysis: &A, graph: &impl ControlFlow, entry: &str) -> Facts<A::Fact> {
+    let mut order = graph.reverse_postorder(entry);
+    let reached: HashSet<String> = order.iter().cloned().collect();
+    order.extend(
+        graph
+            .labels()
+            .into_iter()
+            .filter(|label| !reached.contains(label)),
+    );
+    if A::DIRECTION == Direction::Backward {
+        order.reverse();
+    }
+    let index: HashMap<&String, usize> = order.iter().zip(0..).collect();
+
+    // neighbours facts flow from and to
+    let mut sources: HashMap<&String, Vec<String>> = HashMap::new();
+    let mut targets: HashMap<&String, Vec<String>> = HashMap::new();
+    for label in order.iter() {
+        for succ in graph.successors(label) {
+            let Some((succ, _)) = index.get_key_value(&succ) else {
+                continue;
+            };
+            let (from, to) = match A::DIRECTION {
+                Direction::Forward => (label, *succ),
+                Direction::Backward => (*succ, label),
+            };
+            sources.entry(to).or_default().push(from.clone());
+            targets.entry(from).or_default().push(to.clone());
+        }
+    }
+
+    let mut inputs: HashMap<String, A::Fact> = HashMap::new();
+    let mut outputs: HashMap<String, A::Fact> = order
+        .iter()
+        .map(|label| (label.clone(), A::Fact::bottom()))
+        .collect();
+    let mut worklist: BTreeSet<usize> = (0..order.len()).collect();
+    while let Some(ind) = worklist.pop_first() {
+        let label = &order[ind];
+        let mut input = A::Fact::bottom();
+        let boundary = match A::DIRECTION {
+            Direction::Forward => label == entry,
+            Direction::Backward => graph.successors(label).is_empty(),
+        };
+        if boundary {
+            input.join(&analysis.initial());
+        }
+        for source in sources.get(label).into_iter().flatten() {
+            input.join(&outputs[source]);
+        }
+        let output = analysis.transfer(label, &input);
+        inputs.insert(label.clone(), input);
+        if outputs[label] != output {
+            outputs.insert(label.clone(), output);
+            worklist.extend(
+                targets
+                    .get(label)
+                    .into_iter()
+                    .flatten()
+                    .map(|target| index[target]),
+            );
+        }
+    }
+
+    match A::DIRECTION {
+        Direction::Forward => Facts {
+            entry: inputs,
+            exit: outputs,
+        },
+        Direction::Backward => Facts {
+            entry: outputs,
+            exit: inputs,
+        },
+    }
+}
+
+#[cfg(test)]
+mod dataflow_tests {
+    use super::{Analysis, ControlFlow, Direction, MustSet, solve};
+    use std::collections::{HashMap, HashSet};
+
+    /// Block defining and using variables
+    struct TestBlock {
+        label: &'static str,
+        defs: Vec<&'static str>,
+        uses: Vec<&'static str>,
+        succs: Vec<&'static str>,
+    }
+
+    fn block(
+        label: &'static str,
+        defs: Vec<&'static str>,
+        uses: Vec<&'static str>,
+        succs: Vec<&'static str>,
+    ) -> TestBlock {
+        TestBlock {
+            label,
+            defs,
+            uses,
+            succs,
+        }
+    }
+
+    struct Graph {
+        blocks: Vec<TestBlock>,
+    }
+
+    impl Graph {
+        /// start -> loop -> loop, loop -> done
+        fn example() -> Graph {
+            Graph {
+                blocks: vec![
+                    block("start", vec!["x", "y"], vec![], vec!["loop"]),
+                    block("loop", vec!["x"], vec!["x", "y"], vec!["loop", "done"]),
+                    block("done", vec![], vec!["x"], vec![]),
+                    block("unreachable", vec!["z"], vec!["z"], vec!["done"]),
+                ],
+            }
+        }
+
+        fn block(&self, label: &str) -> (&[&str], &[&str]) {
+            let block = self
+                .blocks
+                .iter()
+                .find(|block| block.label == label)
+                .unwrap();
+            (&block.defs, &block.uses)
+        }
+    }
+
+    impl ControlFlow for Graph {
+        fn labels(&self) -> Vec<String> {
+            self.blocks
+                .iter()
+                .map(|block| block.label.to_owned())
+                .collect()
+        }
+
+        fn successors(&self, label: &str) -> Vec<String> {
+            self.blocks
+                .iter()
+                .find(|block| block.label == label)
+                .map(|block| block.succs.iter().map(|succ| succ.to_string()).collect())
+                .unwrap_or_default()
+        }
+    }
+
+    struct Liveness<'a>(&'a Graph);
+
+    impl Analysis for Liveness<'_> {
+        type Fact = HashSet<String>;
+        const DIRECTION: Direction = Direction::Backward;
+
+        fn transfer(&self, label: &str, live_out: &Self::Fact) -> Self::Fact {
+            let (defs, uses) = self.0.block(label);
+            let mut live: HashSet<String> = live_out
+                .iter()
+                .filter(|var| !defs.contains(&var.as_str()))
+                .cloned()
+                .collect();
+            live.extend(uses.iter().map(|var| var.to_string()));
+            live
+        }
+    }
+
+    /// Liveness with `ret` read after the program ends
+    struct LiveAtExit<'a>(&'a Graph);
+
+    impl Analysis for LiveAtExit<'_> {
+        type Fact = HashSet<String>;
+        const DIRECTION: Direction = Direction::Backward;
+
+        fn initial(&self) -> Self::Fact {
+            set(&["ret".to_owned()])
+        }
+
+        fn transfer(&self, label: &str, live_out: &Self::Fact) -> Self::Fact {
+            Liveness(self.0).transfer(label, live_out)
+        }
+    }
+
+    /// Blocks defining each variable that may reach a label
+    struct ReachingDefs<'a>(&'a Graph);
+
+    impl Analysis for ReachingDefs<'_> {
+        type Fact = HashSet<(String, String)>;
+        const DIRECTION: Direction = Direction::Forward;
+
+        fn transfer(&self, label: &str, reaching: &Self::Fact) -> Self::Fact {
+            let (defs, _) = self.0.block(label);
+            let mut out: Self::Fact = reaching
+                .iter()
+                .filter(|(var, _)| !defs.contains(&var.as_str()))
+                .cloned()
+                .collect();
+            out.extend(defs.iter().map(|var| (var.to_string(), label.to_owned())));
+            out
+        }
+    }
+
+    /// Variables defined on every path to a label
+    struct Defined<'a>(&'a Graph);
+
+    impl Analysis for Defined<'_> {
+        type Fact = MustSet<String>;
+        const DIRECTION: Direction = Direction::Forward;
+
+        fn initial(&self) -> Self::Fact {
+            MustSet::Set(HashSet::new())
+        }
+
+        fn transfer(&self, label: &str, defined: &Self::Fact) -> Self::Fact {
+            let (defs, _) = self.0.block(label);
+            match defined {
+                MustSet::All => MustSet::All,
+                MustSet::Set(set) => {
+                    let mut set = set.clone();
+                    set.extend(defs.iter().map(|var| var.to_string()));
+                    MustSet::Set(set)
+                }
+            }
+        }
+    }
+
+    fn set<T: Clone + Eq + std::hash::Hash>(elems: &[T]) -> HashSet<T> {
+        elems.iter().cloned().collect()
+    }
+
+    #[test]
+    fn reverse_postorder_example() {
+        let graph = Graph::example();
+        assert_eq!(
+            graph.reverse_postorder("start"),
+            vec!["start", "loop", "done"]
+        );
+    }
+
+    #[test]
+    fn liveness_loop() {
+        let graph = Graph::example();
+        let facts = solve(&Liveness(&graph), &graph, "start");
+        let expected = HashMap::from([
+            ("start".to_owned(), set(&[])),
+            ("loop".to_owned(), set(&["x".to_owned(), "y".to_owned()])),
+            ("done".to_owned(), set(&["x".to_owned()])),
+            (
+                "unreachable".to_owned(),
+                set(&["x".to_owned(), "z".to_owned()]),
+            ),
+        ]);
+        assert_eq!(facts.entry, expected);
+        assert_eq!(facts.exit["loop"], set(&["x".to_owned(), "y".to_owned()]));
+        assert!(facts.exit["done"].is_empty());
+    }
+
+    #[test]
+    fn liveness_at_exit() {
+        let graph = Graph::example();
+        let facts = solve(&LiveAtExit(&graph), &graph, "start");
+        assert_eq!(facts.exit["done"], set(&["ret".to_owned()]));
+        assert_eq!(
+            facts.entry["loop"],
+            set(&["x".to_owned(), "y".to_owned(), "ret".to_owned()])
+        );
+        assert_eq!(facts.entry["start"], set(&["ret".to_owned()]));
+    }
+
+    #[test]
+    fn reaching_definitions_loop() {
+        let graph = Graph::example();
+        let facts = solve(&ReachingDefs(&graph), &graph, "start");
+        let def = |var: &str, label: &str| (var.to_owned(), label.to_owned());
+        assert_eq!(
+            facts.entry["loop"],
+            set(&[def("x", "start"), def("y", "start"), def("x", "loop")])
+        );
+        assert_eq!(
+            facts.entry["done"],
+            set(&[def("y", "start"), def("x", "loop"), def("z", "unreachable")])
+        );
+    }
+
+    #[test]
+    fn must_defined_loop() {
+        let graph = Graph::example();
+        let facts = solve(&Defined(&graph), &graph, "start");
+        assert_eq!(facts.entry["start"], MustSet::Set(set(&[])));
+        assert_eq!(
+            facts.entry["loop"],
+            MustSet::Set(set(&["x".to_owned(), "y".to_owned()]))
+        );
+        assert_eq!(
+            facts.entry["done"],
+            MustSet::Set(set(&["x".to_owned(), "y".to_owned()]))
+        );
+        assert_eq!(facts.entry["unreachable"], MustSet::All);
+    }
+}
@@ -0,0 +1,28 @@
+use std::fmt;
+
+/// A global variable stored in the data section, initialized to `value`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Global {
+    pub name: String,
+    pub value: i64,
+}
+
+impl Global {
+    pub fn new(name: &str, value: i64) -> Global {
+        Global {
+            name: name.to_owned(),
+            value,
+        }
+    }
+
+    /// The assembly label of the global
+    pub fn label(&self) -> String {
+        format!("global_{}", self.name)
+    }
+}
+
+impl fmt::Display for Global {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "global {} = {};", self.name, self.value)
+    }
+}
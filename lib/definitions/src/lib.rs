@@ -1,8 +1,23 @@
+pub mod dataflow;
+mod global;
 mod ops;
+mod print;
 pub mod traits;
 
+pub use global::Global;
 pub use ops::{BinaryOperation, Comparator, UnaryOperation};
+pub use print::PrintKind;
 
 pub const READ_INT_CALL: &str = "read_int";
+pub const READ_BOOL_CALL: &str = "read_bool";
+pub const TRY_READ_INT_CALL: &str = "try_read_int";
 pub const PRINT_CALL: &str = "print_int";
+pub const PRINT_STRING_CALL: &str = "print_string";
+pub const PRINTLN_CALL: &str = "println";
+pub const PRINT_BOOL_CALL: &str = "print_bool";
+pub const PRINT_NEWLINE_CALL: &str = "print_newline";
+pub const PRINT_ANY: &str = "print";
+pub const FLUSH_OUTPUT_CALL: &str = "flush_output";
+pub const STRING_LENGTH_CALL: &str = "string_length";
+pub const STRING_CONCAT_CALL: &str = "string_concat";
 pub const RETURN_CALL: &str = "return";
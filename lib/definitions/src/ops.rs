@@ -1,20 +1,22 @@
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum BinaryOperation {
     Add,
     Sub,
     And,
     Or,
+    Concat,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum UnaryOperation {
     Neg,
     Not,
+    Length,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Comparator {
     Eq,
     Lt,
@@ -23,6 +25,31 @@ pub enum Comparator {
     Geq,
 }
 
+impl BinaryOperation {
+    pub fn is_commutative(&self) -> bool {
+        matches!(
+            self,
+            BinaryOperation::Add | BinaryOperation::And | BinaryOperation::Or
+        )
+    }
+}
+
+impl Comparator {
+    pub fn is_commutative(&self) -> bool {
+        matches!(self, Comparator::Eq)
+    }
+
+    pub fn eval(&self, left: i64, right: i64) -> bool {
+        match self {
+            Comparator::Eq => left == right,
+            Comparator::Lt => left < right,
+            Comparator::Leq => left <= right,
+            Comparator::Gt => left > right,
+            Comparator::Geq => left >= right,
+        }
+    }
+}
+
 impl fmt::Display for BinaryOperation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -30,6 +57,7 @@ impl fmt::Display for BinaryOperation {
             BinaryOperation::Sub => f.write_str("-"),
             BinaryOperation::And => f.write_str("&&"),
             BinaryOperation::Or => f.write_str("||"),
+            BinaryOperation::Concat => f.write_str("++"),
         }
     }
 }
@@ -39,6 +67,7 @@ impl fmt::Display for UnaryOperation {
         match self {
             UnaryOperation::Neg => f.write_str("-"),
             UnaryOperation::Not => f.write_str("!"),
+            UnaryOperation::Length => f.write_str("length"),
         }
     }
 }
@@ -0,0 +1,30 @@
+use crate::{PRINT_ANY, PRINT_BOOL_CALL, PRINT_CALL, PRINT_STRING_CALL, PRINTLN_CALL};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintKind {
+    Int,
+    Bool,
+    String,
+    Line,
+    /// `print(e)`, replaced by one of the other kinds during typechecking
+    Any,
+}
+
+impl PrintKind {
+    pub fn call(&self) -> &'static str {
+        match self {
+            PrintKind::Int => PRINT_CALL,
+            PrintKind::Bool => PRINT_BOOL_CALL,
+            PrintKind::String => PRINT_STRING_CALL,
+            PrintKind::Line => PRINTLN_CALL,
+            PrintKind::Any => PRINT_ANY,
+        }
+    }
+}
+
+impl fmt::Display for PrintKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.call())
+    }
+}
@@ -4,8 +4,10 @@ pub trait UsedVars {
     fn used_vars(&self) -> HashSet<String>;
 }
 
+/// Variable `x<n>` not in `used_vars`, searching from the number of used variables
+/// so that adding each fresh variable to `used_vars` keeps generating them linear
 pub fn fresh_var(used_vars: &HashSet<String>) -> String {
-    let mut num = 0;
+    let mut num = used_vars.len();
     let mut var = format!("x{num}");
     while used_vars.contains(&var) {
         num += 1;
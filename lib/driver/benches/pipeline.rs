@@ -0,0 +1,67 @@
+//! Times the whole compiler at `-O1` on generated programs with up to tens of thousands
+//! of variables
+//! Run with `cargo bench -p driver`
+use driver::Driver;
+use std::{
+    env::set_current_dir,
+    fs::{create_dir_all, write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// Number of earlier variables each new variable reads in the windowed programs
+const WINDOW: usize = 14;
+const SIZES: [usize; 3] = [1000, 10000, 30000];
+const OPT_LEVEL: u8 = 1;
+const BENCH_DIR: &str = "target/bench";
+
+/// `a_i = (a_(i-1) + i) - (a_(i-1) - 1)`, so only a few temporaries are live at a time
+fn chain(size: usize) -> String {
+    let mut source = "let a0 = read_int;\n".to_owned();
+    for i in 1..size {
+        source.push_str(&format!(
+            "let a{i} = (a{prev} + {i}) - (a{prev} - 1);\n",
+            prev = i - 1
+        ));
+    }
+    source.push_str(&format!("print(a{});\n", size - 1));
+    source
+}
+
+/// `a_i = a_(i-1) + a_(i-WINDOW)`, so about `WINDOW` variables are live at a time
+fn window(size: usize) -> String {
+    let mut source = String::new();
+    for i in 0..size {
+        if i < WINDOW {
+            source.push_str(&format!("let a{i} = read_int;\n"));
+        } else {
+            source.push_str(&format!("let a{i} = a{} + a{};\n", i - 1, i - WINDOW));
+        }
+    }
+    source.push_str(&format!("print(a{});\n", size - 1));
+    source
+}
+
+fn time(source: &Path) -> Duration {
+    let start = Instant::now();
+    let mut compiler =
+        Driver::new(false, source.to_path_buf(), None, None, None).expect("driver for benchmark");
+    compiler.passes.set_level(OPT_LEVEL);
+    compiler.run().expect("compiling generated program");
+    start.elapsed()
+}
+
+fn main() {
+    set_current_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")).expect("repository root");
+    create_dir_all(BENCH_DIR).expect("benchmark directory");
+    println!("{:>6} {:>14} {:>14}", "vars", "chain", "window");
+    for size in SIZES {
+        let [chain_time, window_time] =
+            [("chain", chain(size)), ("window", window(size))].map(|(name, source)| {
+                let path = PathBuf::from(BENCH_DIR).join(format!("{name}{size}.lang"));
+                write(&path, source).expect("writing generated program");
+                time(&path)
+            });
+        println!("{size:>6} {chain_time:>14?} {window_time:>14?}");
+    }
+}
@@ -6,6 +6,7 @@ pub enum Error {
     Parse(parser::Error),
     RegisterAllocation(register_allocation::Error),
     Typecheck(surface::typecheck::Error),
+    RemoveComplexOperands(surface2monadic::Error),
     ExplicateControl(monadic2core::Error),
     ReadFile(PathBuf),
     ParentNotFound(PathBuf),
@@ -15,6 +16,8 @@ pub enum Error {
     GetFileName(PathBuf),
     RunCommand(String),
     RemoveFile(PathBuf),
+    UnknownPass(String),
+    TooManyRegisters(usize),
 }
 
 impl fmt::Display for Error {
@@ -24,6 +27,9 @@ impl fmt::Display for Error {
             Error::Parse(err) => write!(f, "Error during parsing:\n{err}"),
             Error::RegisterAllocation(err) => write!(f, "Error during register allocation:\n{err}"),
             Error::Typecheck(err) => write!(f, "Error during typechecking:\n{err}"),
+            Error::RemoveComplexOperands(err) => {
+                write!(f, "Error in remove complex operands:\n{err}")
+            }
             Error::ExplicateControl(err) => write!(f, "Error in explicate control:\n{err}"),
             Error::ReadFile(path) => write!(f, "Could not read source file {path:?}"),
             Error::ParentNotFound(path) => write!(f, "Could not find parent of {path:?}"),
@@ -33,6 +39,12 @@ impl fmt::Display for Error {
             Error::GetFileName(path) => write!(f, "Could not get file name of {path:?}"),
             Error::RunCommand(cmd) => write!(f, "Could not run command {cmd}"),
             Error::RemoveFile(path) => write!(f, "Could not remove file {path:?}"),
+            Error::UnknownPass(name) => write!(f, "Unknown pass {name}"),
+            Error::TooManyRegisters(count) => write!(
+                f,
+                "Cannot allocate {count} registers, at most {} are available",
+                register_allocation::ALLOCATABLE_REGISTERS.len()
+            ),
         }
     }
 }
@@ -63,6 +75,12 @@ impl From<surface::typecheck::Error> for Error {
     }
 }
 
+impl From<surface2monadic::Error> for Error {
+    fn from(err: surface2monadic::Error) -> Error {
+        Error::RemoveComplexOperands(err)
+    }
+}
+
 impl From<monadic2core::Error> for Error {
     fn from(err: monadic2core::Error) -> Error {
         Error::ExplicateControl(err)
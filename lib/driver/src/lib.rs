@@ -1,21 +1,27 @@
 use std::{fs::read_to_string, path::PathBuf, process::Command};
 
 mod errors;
+mod pass_manager;
 mod passes;
 mod paths;
-use passes::{Parse, Pass};
+use passes::Parse;
 
 pub use errors::Error;
-use paths::{C_RUNTIME, get_asm_out, get_exe_out, get_object_out, get_runtime_object_out};
+pub use pass_manager::{OPTIONAL_PASSES, PASSES, PassManager};
+use paths::{
+    C_RUNTIME, get_asm_out, get_exe_out, get_object_out, get_runtime_object_out, is_up_to_date,
+};
 
 pub struct CompilerPaths {
     asm_out: PathBuf,
     object_out: PathBuf,
     pub exe_out: PathBuf,
+    /// Additional `.c` or `.o` files passed to the linker
+    pub link_inputs: Vec<PathBuf>,
 }
 
 pub struct Driver {
-    debug: bool,
+    pub passes: PassManager,
     pub paths: CompilerPaths,
     source: String,
 }
@@ -37,34 +43,54 @@ impl Driver {
         let source_contents = read_to_string(&source).map_err(|_| Error::ReadFile(source))?;
 
         Ok(Driver {
-            debug,
+            passes: PassManager::new(debug),
             paths: CompilerPaths {
                 asm_out,
                 object_out,
                 exe_out,
+                link_inputs: vec![],
             },
             source: source_contents,
         })
     }
 
     pub fn run(self) -> Result<(), Error> {
+        let passes = &self.passes;
+        let paths = &self.paths;
         let parse = Parse {
             source: self.source.clone(),
         };
-        let check_types = parse.run_debug(&self.paths, self.debug)?;
-        let uniquify = check_types.run_debug(&self.paths, self.debug)?;
-        let rco = uniquify.run_debug(&self.paths, self.debug)?;
-        let explicate = rco.run_debug(&self.paths, self.debug)?;
-        let select_instrs = explicate.run_debug(&self.paths, self.debug)?;
-        let uncover = select_instrs.run_debug(&self.paths, self.debug)?;
-        let build_interference = uncover.run_debug(&self.paths, self.debug)?;
-        let color_graph = build_interference.run_debug(&self.paths, self.debug)?;
-        let assign_hoems = color_graph.run_debug(&self.paths, self.debug)?;
-        let patch_instrs = assign_hoems.run_debug(&self.paths, self.debug)?;
-        let generate_prelude_conclusion = patch_instrs.run_debug(&self.paths, self.debug)?;
-        let assemble = generate_prelude_conclusion.run_debug(&self.paths, self.debug)?;
-        let link = assemble.run_debug(&self.paths, self.debug)?;
-        link.run_debug(&self.paths, self.debug)?;
+        let check_types = passes.run(parse, paths)?;
+        let uniquify = passes.run(check_types, paths)?;
+        let partial_eval = passes.run(uniquify, paths)?;
+        let rco = passes.run_optional(partial_eval, paths)?;
+        let explicate = passes.run(rco, paths)?;
+        let construct_ssa = passes.run(explicate, paths)?;
+        let destruct_ssa = passes.run_optional(construct_ssa, paths)?;
+        let cse = passes.run(destruct_ssa, paths)?;
+        let propagate = passes.run_optional(cse, paths)?;
+        let remove_unreachable = passes.run_optional(propagate, paths)?;
+        let hoist_invariants = passes.run_optional(remove_unreachable, paths)?;
+        let simplify_cfg = passes.run_optional(hoist_invariants, paths)?;
+        let select_instrs = passes.run_optional(simplify_cfg, paths)?;
+        let dead_stores = passes.run(select_instrs, paths)?;
+        let split = passes.run_optional(dead_stores, paths)?;
+        let uncover = passes.run_optional(split, paths)?;
+        let mut build_interference = passes.run(uncover, paths)?;
+        build_interference.registers = passes.registers.clone();
+        let assign_homes = if passes.linear_scan {
+            passes.run(build_interference.linear_scan(), paths)?
+        } else {
+            let color_graph = passes.run(build_interference, paths)?;
+            passes.run(color_graph, paths)?
+        };
+        let patch_instrs = passes.run(assign_homes, paths)?;
+        let generate_prelude_conclusion = passes.run(patch_instrs, paths)?;
+        let peephole = passes.run(generate_prelude_conclusion, paths)?;
+        let layout = passes.run_optional(peephole, paths)?;
+        let assemble = passes.run_optional(layout, paths)?;
+        let link = passes.run(assemble, paths)?;
+        passes.run(link, paths)?;
         Ok(())
     }
 }
@@ -72,7 +98,7 @@ impl Driver {
 pub fn assemble_runtime() -> Result<(), Error> {
     let runtime_in = PathBuf::from(C_RUNTIME);
     let runtime_out = get_runtime_object_out();
-    if runtime_out.exists() {
+    if is_up_to_date(&runtime_out, &runtime_in) {
         return Ok(());
     }
 
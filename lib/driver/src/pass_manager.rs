@@ -0,0 +1,278 @@
+use crate::{
+    CompilerPaths, Error,
+    passes::{OptionalPass, Pass},
+};
+use register_allocation::{ALLOCATABLE_REGISTERS, RegisterSet};
+use std::collections::HashSet;
+
+/// Every pass in pipeline order, as named by `Pass::name`
+pub const PASSES: [&str; 27] = [
+    "parse",
+    "typecheck",
+    "uniquify",
+    "constfold",
+    "rco",
+    "explicate",
+    "ssa",
+    "outofssa",
+    "cse",
+    "copyprop",
+    "unreachable",
+    "licm",
+    "simplifycfg",
+    "select",
+    "dce",
+    "split",
+    "liveness",
+    "interference",
+    "color",
+    "linearscan",
+    "homes",
+    "patch",
+    "prelude",
+    "peephole",
+    "layout",
+    "assemble",
+    "link",
+];
+
+/// Passes the pipeline can leave out
+pub const OPTIONAL_PASSES: [&str; 11] = [
+    "constfold",
+    "ssa",
+    "cse",
+    "copyprop",
+    "unreachable",
+    "licm",
+    "simplifycfg",
+    "dce",
+    "split",
+    "peephole",
+    "layout",
+];
+
+/// Runs passes and decides which optional passes run
+/// and after which passes the program is printed
+pub struct PassManager {
+    /// Print the program after every pass
+    pub debug: bool,
+    /// Allocate registers by linear scan instead of graph coloring
+    pub linear_scan: bool,
+    /// Registers available to variables
+    pub registers: RegisterSet,
+    enabled: HashSet<&'static str>,
+    print_after: HashSet<&'static str>,
+}
+
+impl PassManager {
+    pub fn new(debug: bool) -> PassManager {
+        let mut manager = PassManager {
+            debug,
+            linear_scan: false,
+            registers: RegisterSet::default(),
+            enabled: HashSet::new(),
+            print_after: HashSet::new(),
+        };
+        manager.set_level(1);
+        manager
+    }
+
+    /// `0` runs no optional passes, `1` the cheap ones and `2` or higher all of them
+    pub fn set_level(&mut self, level: u8) {
+        self.enabled = match level {
+            0 => HashSet::new(),
+            1 => HashSet::from([
+                "constfold",
+                "copyprop",
+                "unreachable",
+                "licm",
+                "simplifycfg",
+                "dce",
+                "split",
+                "peephole",
+                "layout",
+            ]),
+            _ => HashSet::from(OPTIONAL_PASSES),
+        };
+    }
+
+    /// Runs exactly the given optional passes
+    pub fn set_passes<S: AsRef<str>>(&mut self, names: &[S]) -> Result<(), Error> {
+        self.enabled.clear();
+        for name in names {
+            self.enable(name.as_ref())?;
+        }
+        Ok(())
+    }
+
+    pub fn enable(&mut self, name: &str) -> Result<(), Error> {
+        let pass = lookup(&OPTIONAL_PASSES, name)?;
+        self.enabled.insert(pass);
+        Ok(())
+    }
+
+    pub fn disable(&mut self, name: &str) -> Result<(), Error> {
+        let pass = lookup(&OPTIONAL_PASSES, name)?;
+        self.enabled.remove(pass);
+        Ok(())
+    }
+
+    /// Allocates only the first `count` registers, to force spilling
+    pub fn set_registers(&mut self, count: usize) -> Result<(), Error> {
+        if count > ALLOCATABLE_REGISTERS.len() {
+            return Err(Error::TooManyRegisters(count));
+        }
+        self.registers = RegisterSet::new(count);
+        Ok(())
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.enabled.contains(name)
+    }
+
+    pub fn print_after(&mut self, name: &str) -> Result<(), Error> {
+        let pass = lookup(&PASSES, name)?;
+        self.print_after.insert(pass);
+        Ok(())
+    }
+
+    pub fn run<P: Pass>(&self, pass: P, paths: &CompilerPaths) -> Result<P::Next, P::Error> {
+        let print = self.debug || self.print_after.contains(P::name());
+        pass.run_debug(paths, print)
+    }
+
+    pub fn run_optional<P: OptionalPass>(
+        &self,
+        pass: P,
+        paths: &CompilerPaths,
+    ) -> Result<P::Next, P::Error> {
+        if self.is_enabled(P::name()) {
+            self.run(pass, paths)
+        } else {
+            Ok(pass.skip())
+        }
+    }
+}
+
+fn lookup(passes: &[&'static str], name: &str) -> Result<&'static str, Error> {
+    passes
+        .iter()
+        .find(|pass| **pass == name)
+        .copied()
+        .ok_or(Error::UnknownPass(name.to_owned()))
+}
+
+#[cfg(test)]
+mod pass_manager_tests {
+    use super::{OPTIONAL_PASSES, PASSES, PassManager};
+    use crate::{
+        CompilerPaths, Error,
+        passes::{ConstructSsa, PartialEvaluation},
+    };
+    use definitions::{BinaryOperation, PrintKind};
+    use surface::{Expression, Program, Statement};
+
+    #[test]
+    fn select_passes() {
+        let mut manager = PassManager::new(false);
+        assert!(manager.is_enabled("licm"));
+        assert!(!manager.is_enabled("cse"));
+
+        manager.set_passes(&["constfold", "dce"]).unwrap();
+        assert!(manager.is_enabled("dce"));
+        assert!(!manager.is_enabled("licm"));
+
+        manager.set_level(2);
+        assert!(OPTIONAL_PASSES.iter().all(|pass| manager.is_enabled(pass)));
+        assert!(OPTIONAL_PASSES.iter().all(|pass| PASSES.contains(pass)));
+
+        assert!(matches!(
+            manager.enable("select"),
+            Err(Error::UnknownPass(_))
+        ));
+        assert!(manager.print_after("select").is_ok());
+    }
+
+    #[test]
+    fn select_registers() {
+        let mut manager = PassManager::new(false);
+        manager.set_registers(3).unwrap();
+        assert_eq!(manager.registers.registers.len(), 3);
+        assert!(matches!(
+            manager.set_registers(14),
+            Err(Error::TooManyRegisters(14))
+        ));
+    }
+
+    fn paths() -> CompilerPaths {
+        CompilerPaths {
+            asm_out: "".into(),
+            object_out: "".into(),
+            exe_out: "".into(),
+            link_inputs: vec![],
+        }
+    }
+
+    #[test]
+    fn skip_constfold() {
+        let paths = paths();
+        let prog = Program::new(vec![Statement::print(
+            PrintKind::Int,
+            Expression::bin(
+                Expression::lit(40),
+                BinaryOperation::Add,
+                Expression::lit(3),
+            ),
+        )]);
+        let mut manager = PassManager::new(false);
+        let folded = manager
+            .run_optional(PartialEvaluation { prog: prog.clone() }, &paths)
+            .unwrap();
+        let expected = Program::new(vec![Statement::print(PrintKind::Int, Expression::lit(43))]);
+        assert_eq!(folded.prog, expected);
+
+        manager.set_level(0);
+        let unchanged = manager
+            .run_optional(PartialEvaluation { prog: prog.clone() }, &paths)
+            .unwrap();
+        assert_eq!(unchanged.prog, prog);
+    }
+
+    #[test]
+    fn skip_ssa() {
+        use core::{Atom, Continuation, Expression, Statement, Tail};
+        let paths = paths();
+        let mut prog = core::Program::new();
+        prog.add_block(
+            "start",
+            Tail {
+                stmts: vec![
+                    Statement::assign("x", Expression::Atm(Atom::Integer(1))),
+                    Statement::set(
+                        "x",
+                        Expression::bin(
+                            Atom::Variable("x".to_owned()),
+                            BinaryOperation::Add,
+                            Atom::Integer(1),
+                        ),
+                    ),
+                ],
+                cont: Continuation::Return(Atom::Variable("x".to_owned())),
+            },
+        );
+        // skipped construction still goes through destruction, which leaves the program as is
+        let mut manager = PassManager::new(false);
+        let destruct = manager
+            .run_optional(ConstructSsa { prog: prog.clone() }, &paths)
+            .unwrap();
+        let unchanged = manager.run(destruct, &paths).unwrap();
+        assert_eq!(unchanged.prog, prog);
+
+        manager.enable("ssa").unwrap();
+        let destruct = manager
+            .run_optional(ConstructSsa { prog: prog.clone() }, &paths)
+            .unwrap();
+        let renamed = manager.run(destruct, &paths).unwrap();
+        assert_ne!(renamed.prog, prog);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{GeneratePreludeConclusion, Link, Pass};
+use super::{LayoutBlocks, Link, Pass};
 use crate::{CompilerPaths, Error};
 use asm::Program;
 use std::{
@@ -13,9 +13,13 @@ pub struct Assemble {
 
 impl Pass for Assemble {
     type Next = Link;
-    type Prev = GeneratePreludeConclusion;
+    type Prev = LayoutBlocks;
     type Error = Error;
 
+    fn name() -> &'static str {
+        "assemble"
+    }
+
     fn description() -> &'static str {
         "Assembled"
     }
@@ -1,10 +1,11 @@
 use super::{ColorGraph, Pass, PatchInstrs};
 use crate::CompilerPaths;
-use register_allocation::{Coloring, LiveProg, assign_homes};
+use register_allocation::{Coloring, LiveProg, RegisterSet, assign_homes, verify_homes};
 
 pub struct AssignHomes {
     pub prog: LiveProg,
     pub coloring: Coloring,
+    pub registers: RegisterSet,
 }
 
 impl Pass for AssignHomes {
@@ -12,6 +13,10 @@ impl Pass for AssignHomes {
     type Prev = ColorGraph;
     type Error = register_allocation::Error;
 
+    fn name() -> &'static str {
+        "homes"
+    }
+
     fn description() -> &'static str {
         "Assign Homes"
     }
@@ -21,7 +26,8 @@ impl Pass for AssignHomes {
     }
 
     fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
-        let out = assign_homes(self.prog, self.coloring)?;
+        let out = assign_homes(self.prog.clone(), self.coloring.clone(), &self.registers)?;
+        verify_homes(&self.prog, &self.coloring, &self.registers, &out)?;
         Ok(PatchInstrs { prog: out })
     }
 }
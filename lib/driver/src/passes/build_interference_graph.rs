@@ -1,10 +1,21 @@
-use super::{ColorGraph, Pass, UncoverLive};
+use super::{ColorGraph, LinearScan, Pass, UncoverLive};
 use crate::CompilerPaths;
-use register_allocation::{LiveProg, build_interference_graph, build_move_graph};
+use register_allocation::{LiveProg, RegisterSet, build_interference_graph, build_move_graph};
 use std::convert::Infallible;
 
 pub struct BuildInterferenceGraph {
     pub prog: LiveProg,
+    pub registers: RegisterSet,
+}
+
+impl BuildInterferenceGraph {
+    /// Allocate registers by linear scan instead of graph coloring
+    pub fn linear_scan(self) -> LinearScan {
+        LinearScan {
+            prog: self.prog,
+            registers: self.registers,
+        }
+    }
 }
 
 impl Pass for BuildInterferenceGraph {
@@ -12,6 +23,10 @@ impl Pass for BuildInterferenceGraph {
     type Prev = UncoverLive;
     type Error = Infallible;
 
+    fn name() -> &'static str {
+        "interference"
+    }
+
     fn description() -> &'static str {
         "Interference Graph"
     }
@@ -27,6 +42,7 @@ impl Pass for BuildInterferenceGraph {
             prog: self.prog,
             interference_graph,
             move_graph,
+            registers: self.registers,
         })
     }
 }
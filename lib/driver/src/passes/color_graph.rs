@@ -1,11 +1,14 @@
 use super::{AssignHomes, BuildInterferenceGraph, Pass};
 use crate::CompilerPaths;
-use register_allocation::{LiveProg, LocationGraph, color_graph};
+use register_allocation::{
+    LiveProg, LocationGraph, RegisterSet, color_graph, spill_costs, spill_report,
+};
 
 pub struct ColorGraph {
     pub prog: LiveProg,
     pub interference_graph: LocationGraph,
     pub move_graph: LocationGraph,
+    pub registers: RegisterSet,
 }
 
 impl Pass for ColorGraph {
@@ -13,6 +16,10 @@ impl Pass for ColorGraph {
     type Prev = BuildInterferenceGraph;
     type Error = register_allocation::Error;
 
+    fn name() -> &'static str {
+        "color"
+    }
+
     fn description() -> &'static str {
         "Colored Graph"
     }
@@ -25,10 +32,44 @@ impl Pass for ColorGraph {
     }
 
     fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
-        let coloring = color_graph(self.interference_graph, self.move_graph)?;
+        let costs = spill_costs(&self.prog);
+        let coloring = color_graph(
+            self.interference_graph,
+            self.move_graph,
+            &costs,
+            &self.registers,
+        )?;
         Ok(AssignHomes {
             prog: self.prog,
             coloring,
+            registers: self.registers,
         })
     }
+
+    fn run_debug(self, paths: &CompilerPaths, debug: bool) -> Result<Self::Next, Self::Error> {
+        if !debug {
+            return self.run(paths);
+        }
+        let costs = spill_costs(&self.prog);
+        let coloring = color_graph(
+            self.interference_graph,
+            self.move_graph,
+            &costs,
+            &self.registers,
+        )?;
+        let report = spill_report(&coloring, &costs, &self.registers);
+        let next = AssignHomes {
+            prog: self.prog,
+            coloring,
+            registers: self.registers,
+        };
+        println!("=== {} ===", Self::description());
+        println!("{}", next.show_input());
+        println!("Spill decisions:");
+        for line in report.lines() {
+            println!("\t{line}");
+        }
+        println!();
+        Ok(next)
+    }
 }
@@ -0,0 +1,39 @@
+use super::{DestructSsa, Explicate, OptionalPass, Pass};
+use crate::CompilerPaths;
+use core::{Program, ssa::into_ssa};
+use std::convert::Infallible;
+
+pub struct ConstructSsa {
+    pub prog: Program,
+}
+
+impl Pass for ConstructSsa {
+    type Next = DestructSsa;
+    type Prev = Explicate;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "ssa"
+    }
+
+    fn description() -> &'static str {
+        "Construct SSA Form"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let prog = into_ssa(self.prog);
+        Ok(DestructSsa { prog })
+    }
+}
+
+impl OptionalPass for ConstructSsa {
+    fn skip(self) -> Self::Next {
+        DestructSsa {
+            prog: self.prog.into(),
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use super::{ConstructSsa, Cse, Pass};
+use crate::CompilerPaths;
+use core::ssa::{SsaProgram, from_ssa};
+use std::convert::Infallible;
+
+pub struct DestructSsa {
+    pub prog: SsaProgram,
+}
+
+impl Pass for DestructSsa {
+    type Next = Cse;
+    type Prev = ConstructSsa;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "outofssa"
+    }
+
+    fn description() -> &'static str {
+        "Translate out of SSA Form"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let prog = from_ssa(self.prog);
+        Ok(Cse { prog })
+    }
+}
@@ -0,0 +1,37 @@
+use super::{DestructSsa, OptionalPass, Pass, PropagateCopies};
+use crate::CompilerPaths;
+use core::{Program, optimize::eliminate_common_subexpressions};
+use std::convert::Infallible;
+
+pub struct Cse {
+    pub prog: Program,
+}
+
+impl Pass for Cse {
+    type Next = PropagateCopies;
+    type Prev = DestructSsa;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "cse"
+    }
+
+    fn description() -> &'static str {
+        "Eliminate Common Subexpressions"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let prog = eliminate_common_subexpressions(self.prog);
+        Ok(PropagateCopies { prog })
+    }
+}
+
+impl OptionalPass for Cse {
+    fn skip(self) -> Self::Next {
+        PropagateCopies { prog: self.prog }
+    }
+}
@@ -0,0 +1,37 @@
+use super::{OptionalPass, Pass, SelectInstrs, SplitLiveRanges};
+use crate::CompilerPaths;
+use asm::VarProgram;
+use register_allocation::eliminate_dead_stores;
+
+pub struct EliminateDeadStores {
+    pub prog: VarProgram,
+}
+
+impl Pass for EliminateDeadStores {
+    type Next = SplitLiveRanges;
+    type Prev = SelectInstrs;
+    type Error = register_allocation::Error;
+
+    fn name() -> &'static str {
+        "dce"
+    }
+
+    fn description() -> &'static str {
+        "Eliminate Dead Stores"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let prog = eliminate_dead_stores(self.prog)?;
+        Ok(SplitLiveRanges { prog })
+    }
+}
+
+impl OptionalPass for EliminateDeadStores {
+    fn skip(self) -> Self::Next {
+        SplitLiveRanges { prog: self.prog }
+    }
+}
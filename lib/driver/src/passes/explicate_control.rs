@@ -1,4 +1,4 @@
-use super::{Pass, Rco, SelectInstrs};
+use super::{ConstructSsa, Pass, Rco};
 use crate::CompilerPaths;
 use monadic::Program;
 use monadic2core::{Error, explicate_control};
@@ -8,10 +8,14 @@ pub struct Explicate {
 }
 
 impl Pass for Explicate {
-    type Next = SelectInstrs;
+    type Next = ConstructSsa;
     type Prev = Rco;
     type Error = Error;
 
+    fn name() -> &'static str {
+        "explicate"
+    }
+
     fn description() -> &'static str {
         "Explicate Control"
     }
@@ -22,6 +26,6 @@ impl Pass for Explicate {
 
     fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
         let prog = explicate_control(self.prog)?;
-        Ok(SelectInstrs { prog })
+        Ok(ConstructSsa { prog })
     }
 }
@@ -1,4 +1,4 @@
-use super::{Assemble, Pass, PatchInstrs};
+use super::{Pass, PatchInstrs, Peephole};
 use crate::CompilerPaths;
 use asm::{Program, generate_prelude_conclusion};
 use std::convert::Infallible;
@@ -8,10 +8,14 @@ pub struct GeneratePreludeConclusion {
 }
 
 impl Pass for GeneratePreludeConclusion {
-    type Next = Assemble;
+    type Next = Peephole;
     type Prev = PatchInstrs;
     type Error = Infallible;
 
+    fn name() -> &'static str {
+        "prelude"
+    }
+
     fn description() -> &'static str {
         "Generate Prelude and Conclusion"
     }
@@ -22,6 +26,6 @@ impl Pass for GeneratePreludeConclusion {
 
     fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
         let prog = generate_prelude_conclusion(self.prog);
-        Ok(Assemble { prog })
+        Ok(Peephole { prog })
     }
 }
@@ -0,0 +1,37 @@
+use super::{OptionalPass, Pass, RemoveUnreachable, SimplifyCfg};
+use crate::CompilerPaths;
+use core::{Program, optimize::hoist_loop_invariants};
+use std::convert::Infallible;
+
+pub struct HoistInvariants {
+    pub prog: Program,
+}
+
+impl Pass for HoistInvariants {
+    type Next = SimplifyCfg;
+    type Prev = RemoveUnreachable;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "licm"
+    }
+
+    fn description() -> &'static str {
+        "Hoist Loop Invariants"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let prog = hoist_loop_invariants(self.prog);
+        Ok(SimplifyCfg { prog })
+    }
+}
+
+impl OptionalPass for HoistInvariants {
+    fn skip(self) -> Self::Next {
+        SimplifyCfg { prog: self.prog }
+    }
+}
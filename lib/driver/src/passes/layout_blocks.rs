@@ -0,0 +1,37 @@
+use super::{Assemble, OptionalPass, Pass, Peephole};
+use crate::CompilerPaths;
+use asm::{Program, layout_blocks};
+use std::convert::Infallible;
+
+pub struct LayoutBlocks {
+    pub prog: Program,
+}
+
+impl Pass for LayoutBlocks {
+    type Next = Assemble;
+    type Prev = Peephole;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "layout"
+    }
+
+    fn description() -> &'static str {
+        "Layout Blocks"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let prog = layout_blocks(self.prog);
+        Ok(Assemble { prog })
+    }
+}
+
+impl OptionalPass for LayoutBlocks {
+    fn skip(self) -> Self::Next {
+        Assemble { prog: self.prog }
+    }
+}
@@ -0,0 +1,52 @@
+use super::{AssignHomes, Pass, UncoverLive};
+use crate::CompilerPaths;
+use register_allocation::{LiveProg, RegisterSet, linear_scan, live_intervals};
+use std::convert::Infallible;
+
+pub struct LinearScan {
+    pub prog: LiveProg,
+    pub registers: RegisterSet,
+}
+
+impl Pass for LinearScan {
+    type Next = AssignHomes;
+    type Prev = UncoverLive;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "linearscan"
+    }
+
+    fn description() -> &'static str {
+        "Linear Scan"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let coloring = linear_scan(&self.prog, &self.registers);
+        Ok(AssignHomes {
+            prog: self.prog,
+            coloring,
+            registers: self.registers,
+        })
+    }
+
+    fn run_debug(self, paths: &CompilerPaths, debug: bool) -> Result<Self::Next, Self::Error> {
+        if !debug {
+            return self.run(paths);
+        }
+        let intervals = live_intervals(&self.prog);
+        let next = self.run(paths)?;
+        println!("=== {} ===", Self::description());
+        println!("Live intervals:");
+        for interval in intervals {
+            println!("\t{interval}");
+        }
+        println!("{}", next.show_input());
+        println!();
+        Ok(next)
+    }
+}
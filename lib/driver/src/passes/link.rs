@@ -9,6 +9,10 @@ impl Pass for Link {
     type Prev = Assemble;
     type Error = Error;
 
+    fn name() -> &'static str {
+        "link"
+    }
+
     fn description() -> &'static str {
         "Link"
     }
@@ -22,14 +26,13 @@ impl Pass for Link {
             return Err(Error::ReadFile(compiler.object_out.clone()));
         }
         let runtime_out = get_runtime_object_out();
-        if !runtime_out.exists() {
-            assemble_runtime()?;
-        }
+        assemble_runtime()?;
 
         let mut gcc_cmd = Command::new("gcc");
         gcc_cmd
             .arg(&compiler.object_out)
             .arg(&runtime_out)
+            .args(&compiler.link_inputs)
             .arg("-o")
             .arg(&compiler.exe_out);
         let res = gcc_cmd
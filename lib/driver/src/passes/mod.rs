@@ -6,6 +6,8 @@ pub trait Pass: Sized {
     type Prev: Pass;
     type Error: Into<crate::Error>;
 
+    /// Used to select the pass on the command line
+    fn name() -> &'static str;
     fn description() -> &'static str;
     fn show_input(&self) -> String;
 
@@ -22,6 +24,12 @@ pub trait Pass: Sized {
     }
 }
 
+/// A pass the pipeline can leave out
+pub trait OptionalPass: Pass {
+    /// Continue with the input unchanged
+    fn skip(self) -> Self::Next;
+}
+
 pub struct Done;
 
 impl Pass for Done {
@@ -29,6 +37,10 @@ impl Pass for Done {
     type Prev = Link;
     type Error = Infallible;
 
+    fn name() -> &'static str {
+        "done"
+    }
+
     fn description() -> &'static str {
         "Successfully compiled Program"
     }
@@ -46,13 +58,26 @@ mod assemble;
 mod assign_homes;
 mod build_interference_graph;
 mod color_graph;
+mod construct_ssa;
+mod destruct_ssa;
+mod eliminate_common_subexpressions;
+mod eliminate_dead_stores;
 mod explicate_control;
 mod generate_prelude_conclusion;
+mod hoist_invariants;
+mod layout_blocks;
+mod linear_scan;
 mod link;
 mod parse;
+mod partial_eval;
 mod patch_instructions;
+mod peephole;
+mod propagate_copies;
 mod remove_complex_operands;
+mod remove_unreachable;
 mod select_instructions;
+mod simplify_cfg;
+mod split_live_ranges;
 mod typecheck;
 mod uncover_live;
 mod uniquify;
@@ -61,13 +86,26 @@ pub use assemble::Assemble;
 pub use assign_homes::AssignHomes;
 pub use build_interference_graph::BuildInterferenceGraph;
 pub use color_graph::ColorGraph;
+pub use construct_ssa::ConstructSsa;
+pub use destruct_ssa::DestructSsa;
+pub use eliminate_common_subexpressions::Cse;
+pub use eliminate_dead_stores::EliminateDeadStores;
 pub use explicate_control::Explicate;
 pub use generate_prelude_conclusion::GeneratePreludeConclusion;
+pub use hoist_invariants::HoistInvariants;
+pub use layout_blocks::LayoutBlocks;
+pub use linear_scan::LinearScan;
 pub use link::Link;
 pub use parse::Parse;
+pub use partial_eval::PartialEvaluation;
 pub use patch_instructions::PatchInstrs;
+pub use peephole::Peephole;
+pub use propagate_copies::PropagateCopies;
 pub use remove_complex_operands::Rco;
+pub use remove_unreachable::RemoveUnreachable;
 pub use select_instructions::SelectInstrs;
+pub use simplify_cfg::SimplifyCfg;
+pub use split_live_ranges::SplitLiveRanges;
 pub use typecheck::CheckTypes;
 pub use uncover_live::UncoverLive;
 pub use uniquify::UniquifyVariables;
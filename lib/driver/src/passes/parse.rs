@@ -11,6 +11,10 @@ impl Pass for Parse {
     type Prev = Done;
     type Error = parser::Error;
 
+    fn name() -> &'static str {
+        "parse"
+    }
+
     fn description() -> &'static str {
         "Parse"
     }
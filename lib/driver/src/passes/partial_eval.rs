@@ -0,0 +1,38 @@
+use super::{OptionalPass, Pass, Rco, UniquifyVariables};
+use crate::CompilerPaths;
+use std::convert::Infallible;
+use surface::{ConstEnv, PartialEval, Program};
+
+pub struct PartialEvaluation {
+    pub prog: Program,
+}
+
+impl Pass for PartialEvaluation {
+    type Next = Rco;
+    type Prev = UniquifyVariables;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "constfold"
+    }
+
+    fn description() -> &'static str {
+        "Partial Evaluation"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let mut env = ConstEnv::new(&self.prog);
+        let prog = self.prog.partial_eval(&mut env);
+        Ok(Rco { prog })
+    }
+}
+
+impl OptionalPass for PartialEvaluation {
+    fn skip(self) -> Self::Next {
+        Rco { prog: self.prog }
+    }
+}
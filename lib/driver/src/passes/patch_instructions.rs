@@ -12,6 +12,10 @@ impl Pass for PatchInstrs {
     type Prev = AssignHomes;
     type Error = Infallible;
 
+    fn name() -> &'static str {
+        "patch"
+    }
+
     fn description() -> &'static str {
         "Patch Instructions"
     }
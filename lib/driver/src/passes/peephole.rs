@@ -0,0 +1,53 @@
+use super::{GeneratePreludeConclusion, LayoutBlocks, OptionalPass, Pass};
+use crate::CompilerPaths;
+use asm::{Program, peephole};
+use std::convert::Infallible;
+
+pub struct Peephole {
+    pub prog: Program,
+}
+
+impl Pass for Peephole {
+    type Next = LayoutBlocks;
+    type Prev = GeneratePreludeConclusion;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "peephole"
+    }
+
+    fn description() -> &'static str {
+        "Peephole Optimization"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let (prog, _) = peephole(self.prog);
+        Ok(LayoutBlocks { prog })
+    }
+
+    fn run_debug(self, paths: &CompilerPaths, debug: bool) -> Result<Self::Next, Self::Error> {
+        if !debug {
+            return self.run(paths);
+        }
+        let (prog, fired) = peephole(self.prog);
+        let next = LayoutBlocks { prog };
+        println!("=== {} ===", Self::description());
+        println!("{}", next.show_input());
+        println!("Fired rules:");
+        for (rule, count) in fired {
+            println!("\t{rule}: {count}");
+        }
+        println!();
+        Ok(next)
+    }
+}
+
+impl OptionalPass for Peephole {
+    fn skip(self) -> Self::Next {
+        LayoutBlocks { prog: self.prog }
+    }
+}
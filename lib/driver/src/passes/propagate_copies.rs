@@ -0,0 +1,37 @@
+use super::{Cse, OptionalPass, Pass, RemoveUnreachable};
+use crate::CompilerPaths;
+use core::{Program, optimize::propagate_copies};
+use std::convert::Infallible;
+
+pub struct PropagateCopies {
+    pub prog: Program,
+}
+
+impl Pass for PropagateCopies {
+    type Next = RemoveUnreachable;
+    type Prev = Cse;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "copyprop"
+    }
+
+    fn description() -> &'static str {
+        "Propagate Copies"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let prog = propagate_copies(self.prog);
+        Ok(RemoveUnreachable { prog })
+    }
+}
+
+impl OptionalPass for PropagateCopies {
+    fn skip(self) -> Self::Next {
+        RemoveUnreachable { prog: self.prog }
+    }
+}
@@ -1,6 +1,5 @@
-use super::{Explicate, Pass, UniquifyVariables};
+use super::{Explicate, PartialEvaluation, Pass};
 use crate::CompilerPaths;
-use std::convert::Infallible;
 use surface::Program;
 use surface2monadic::RemoveComplexOperands;
 
@@ -10,8 +9,12 @@ pub struct Rco {
 
 impl Pass for Rco {
     type Next = Explicate;
-    type Prev = UniquifyVariables;
-    type Error = Infallible;
+    type Prev = PartialEvaluation;
+    type Error = surface2monadic::Error;
+
+    fn name() -> &'static str {
+        "rco"
+    }
 
     fn description() -> &'static str {
         "Remove Complex Operands"
@@ -22,7 +25,7 @@ impl Pass for Rco {
     }
 
     fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
-        let prog = self.prog.remove_complex_operands(&mut Default::default());
+        let prog = self.prog.remove_complex_operands(&mut Default::default())?;
         Ok(Explicate { prog })
     }
 }
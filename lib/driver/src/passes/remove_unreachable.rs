@@ -0,0 +1,37 @@
+use super::{HoistInvariants, OptionalPass, Pass, PropagateCopies};
+use crate::CompilerPaths;
+use core::{Program, optimize::remove_unreachable};
+use std::convert::Infallible;
+
+pub struct RemoveUnreachable {
+    pub prog: Program,
+}
+
+impl Pass for RemoveUnreachable {
+    type Next = HoistInvariants;
+    type Prev = PropagateCopies;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "unreachable"
+    }
+
+    fn description() -> &'static str {
+        "Remove Unreachable Blocks"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let prog = remove_unreachable(self.prog);
+        Ok(HoistInvariants { prog })
+    }
+}
+
+impl OptionalPass for RemoveUnreachable {
+    fn skip(self) -> Self::Next {
+        HoistInvariants { prog: self.prog }
+    }
+}
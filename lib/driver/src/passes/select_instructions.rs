@@ -1,4 +1,4 @@
-use super::{Explicate, Pass, UncoverLive};
+use super::{EliminateDeadStores, Pass, SimplifyCfg};
 use crate::CompilerPaths;
 use core::Program;
 use core2asm::SelectInstructions;
@@ -9,10 +9,14 @@ pub struct SelectInstrs {
 }
 
 impl Pass for SelectInstrs {
-    type Next = UncoverLive;
-    type Prev = Explicate;
+    type Next = EliminateDeadStores;
+    type Prev = SimplifyCfg;
     type Error = Infallible;
 
+    fn name() -> &'static str {
+        "select"
+    }
+
     fn description() -> &'static str {
         "Select Instructions"
     }
@@ -23,6 +27,6 @@ impl Pass for SelectInstrs {
 
     fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
         let prog = self.prog.select_instructions(());
-        Ok(UncoverLive { prog })
+        Ok(EliminateDeadStores { prog })
     }
 }
@@ -0,0 +1,37 @@
+use super::{HoistInvariants, OptionalPass, Pass, SelectInstrs};
+use crate::CompilerPaths;
+use core::{Program, optimize::simplify_cfg};
+use std::convert::Infallible;
+
+pub struct SimplifyCfg {
+    pub prog: Program,
+}
+
+impl Pass for SimplifyCfg {
+    type Next = SelectInstrs;
+    type Prev = HoistInvariants;
+    type Error = Infallible;
+
+    fn name() -> &'static str {
+        "simplifycfg"
+    }
+
+    fn description() -> &'static str {
+        "Simplify Control Flow"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let prog = simplify_cfg(self.prog);
+        Ok(SelectInstrs { prog })
+    }
+}
+
+impl OptionalPass for SimplifyCfg {
+    fn skip(self) -> Self::Next {
+        SelectInstrs { prog: self.prog }
+    }
+}
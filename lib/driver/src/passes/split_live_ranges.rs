@@ -0,0 +1,37 @@
+use super::{EliminateDeadStores, OptionalPass, Pass, UncoverLive};
+use crate::CompilerPaths;
+use asm::VarProgram;
+use register_allocation::split_around_calls;
+
+pub struct SplitLiveRanges {
+    pub prog: VarProgram,
+}
+
+impl Pass for SplitLiveRanges {
+    type Next = UncoverLive;
+    type Prev = EliminateDeadStores;
+    type Error = register_allocation::Error;
+
+    fn name() -> &'static str {
+        "split"
+    }
+
+    fn description() -> &'static str {
+        "Split Live Ranges"
+    }
+
+    fn show_input(&self) -> String {
+        self.prog.to_string()
+    }
+
+    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+        let prog = split_around_calls(self.prog)?;
+        Ok(UncoverLive { prog })
+    }
+}
+
+impl OptionalPass for SplitLiveRanges {
+    fn skip(self) -> Self::Next {
+        UncoverLive { prog: self.prog }
+    }
+}
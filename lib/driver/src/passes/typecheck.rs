@@ -11,6 +11,10 @@ impl Pass for CheckTypes {
     type Prev = Parse;
     type Error = surface::typecheck::Error;
 
+    fn name() -> &'static str {
+        "typecheck"
+    }
+
     fn description() -> &'static str {
         "Typecheck"
     }
@@ -19,7 +23,7 @@ impl Pass for CheckTypes {
         self.prog.to_string()
     }
 
-    fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
+    fn run(mut self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
         self.prog.check(&mut Default::default())?;
         Ok(UniquifyVariables { prog: self.prog })
     }
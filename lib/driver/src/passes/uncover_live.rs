@@ -1,7 +1,7 @@
-use super::{BuildInterferenceGraph, Pass, SelectInstrs};
+use super::{BuildInterferenceGraph, Pass, SplitLiveRanges};
 use crate::CompilerPaths;
 use asm::VarProgram;
-use register_allocation::uncover_live;
+use register_allocation::{RegisterSet, uncover_live};
 
 pub struct UncoverLive {
     pub prog: VarProgram,
@@ -9,9 +9,13 @@ pub struct UncoverLive {
 
 impl Pass for UncoverLive {
     type Next = BuildInterferenceGraph;
-    type Prev = SelectInstrs;
+    type Prev = SplitLiveRanges;
     type Error = register_allocation::Error;
 
+    fn name() -> &'static str {
+        "liveness"
+    }
+
     fn description() -> &'static str {
         "Uncover Live"
     }
@@ -22,6 +26,9 @@ impl Pass for UncoverLive {
 
     fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
         let prog = uncover_live(self.prog)?;
-        Ok(BuildInterferenceGraph { prog })
+        Ok(BuildInterferenceGraph {
+            prog,
+            registers: RegisterSet::default(),
+        })
     }
 }
@@ -1,4 +1,4 @@
-use super::{CheckTypes, Pass, Rco};
+use super::{CheckTypes, PartialEvaluation, Pass};
 use crate::CompilerPaths;
 use std::convert::Infallible;
 use surface::{Program, Uniquify};
@@ -8,10 +8,14 @@ pub struct UniquifyVariables {
 }
 
 impl Pass for UniquifyVariables {
-    type Next = Rco;
+    type Next = PartialEvaluation;
     type Prev = CheckTypes;
     type Error = Infallible;
 
+    fn name() -> &'static str {
+        "uniquify"
+    }
+
     fn description() -> &'static str {
         "Uniquify"
     }
@@ -22,6 +26,6 @@ impl Pass for UniquifyVariables {
 
     fn run(self, _: &CompilerPaths) -> Result<Self::Next, Self::Error> {
         let prog = self.prog.uniquify(&mut Default::default());
-        Ok(Rco { prog })
+        Ok(PartialEvaluation { prog })
     }
 }
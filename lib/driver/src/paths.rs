@@ -1,8 +1,8 @@
 use crate::Error;
 use std::{
     ffi::OsStr,
-    fs::{create_dir_all, remove_file},
-    path::PathBuf,
+    fs::{create_dir_all, metadata, remove_file},
+    path::{Path, PathBuf},
 };
 
 const DEFAULT_ASM_OUT: &str = "target/asm";
@@ -55,3 +55,12 @@ pub fn get_exe_out(cli_arg: Option<PathBuf>, prog_name: &OsStr) -> Result<PathBu
 pub fn get_runtime_object_out() -> PathBuf {
     PathBuf::from(DEFAULT_OBJECT_OUT).join("runtime.o")
 }
+
+pub fn is_up_to_date(out: &Path, source: &Path) -> bool {
+    let out_modified = metadata(out).and_then(|meta| meta.modified());
+    let source_modified = metadata(source).and_then(|meta| meta.modified());
+    match (out_modified, source_modified) {
+        (Ok(out_time), Ok(source_time)) => out_time >= source_time,
+        _ => false,
+    }
+}
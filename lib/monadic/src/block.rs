@@ -25,7 +25,10 @@ impl UsedVars for Block {
 
 impl From<Block> for Program {
     fn from(b: Block) -> Program {
-        Program { main: b }
+        Program {
+            globals: vec![],
+            main: b,
+        }
     }
 }
 
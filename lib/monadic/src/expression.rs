@@ -1,11 +1,17 @@
 use super::Atom;
-use definitions::{BinaryOperation, Comparator, READ_INT_CALL, UnaryOperation, traits::UsedVars};
+use definitions::{
+    BinaryOperation, Comparator, READ_BOOL_CALL, READ_INT_CALL, TRY_READ_INT_CALL, UnaryOperation,
+    traits::UsedVars,
+};
 use std::{collections::HashSet, fmt};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Expression {
     Atm(Atom),
+    Str(String),
     ReadInt,
+    ReadBool,
+    TryReadInt,
     UnaryOp {
         arg: Atom,
         op: UnaryOperation,
@@ -20,6 +26,10 @@ pub enum Expression {
         cmp: Comparator,
         right: Atom,
     },
+    Call {
+        name: String,
+        args: Vec<Atom>,
+    },
 }
 
 impl Expression {
@@ -34,16 +44,25 @@ impl Expression {
     pub fn cmp(left: Atom, cmp: Comparator, right: Atom) -> Expression {
         Expression::Cmp { left, cmp, right }
     }
+
+    pub fn call(name: &str, args: Vec<Atom>) -> Expression {
+        Expression::Call {
+            name: name.to_owned(),
+            args,
+        }
+    }
 }
 
 impl UsedVars for Expression {
     fn used_vars(&self) -> HashSet<String> {
         match self {
             Expression::Atm(atm) => atm.used_vars(),
-            Expression::ReadInt => HashSet::new(),
+            Expression::Str(_) => HashSet::new(),
+            Expression::ReadInt | Expression::ReadBool | Expression::TryReadInt => HashSet::new(),
             Expression::UnaryOp { arg, .. } => arg.used_vars(),
             Expression::BinaryOp { fst, snd, .. } => &fst.used_vars() | &snd.used_vars(),
             Expression::Cmp { left, right, .. } => &left.used_vars() | &right.used_vars(),
+            Expression::Call { args, .. } => args.iter().flat_map(|arg| arg.used_vars()).collect(),
         }
     }
 }
@@ -52,10 +71,21 @@ impl fmt::Display for Expression {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Expression::Atm(atm) => atm.fmt(f),
+            Expression::Str(s) => write!(f, "{s:?}"),
             Expression::ReadInt => f.write_str(READ_INT_CALL),
+            Expression::ReadBool => f.write_str(READ_BOOL_CALL),
+            Expression::TryReadInt => f.write_str(TRY_READ_INT_CALL),
             Expression::UnaryOp { arg, op } => write!(f, "{op}({arg})"),
             Expression::BinaryOp { fst, op, snd } => write!(f, "{fst} {op} {snd}"),
             Expression::Cmp { left, cmp, right } => write!(f, "{left} {cmp} {right}"),
+            Expression::Call { name, args } => write!(
+                f,
+                "{name}({})",
+                args.iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
         }
     }
 }
@@ -1,15 +1,17 @@
 use super::{Block, Statement};
-use definitions::traits::UsedVars;
+use definitions::{Global, traits::UsedVars};
 use std::{collections::HashSet, fmt};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Program {
+    pub globals: Vec<Global>,
     pub main: Block,
 }
 
 impl Program {
     pub fn new(exps: Vec<Statement>) -> Program {
         Program {
+            globals: vec![],
             main: Block::new(exps),
         }
     }
@@ -17,12 +19,17 @@ impl Program {
 
 impl UsedVars for Program {
     fn used_vars(&self) -> HashSet<String> {
-        self.main.used_vars()
+        let mut used = self.main.used_vars();
+        used.extend(self.globals.iter().map(|global| global.name.clone()));
+        used
     }
 }
 
 impl fmt::Display for Program {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for global in self.globals.iter() {
+            writeln!(f, "{global}")?;
+        }
         self.main.fmt(f)
     }
 }
@@ -1,11 +1,15 @@
 use super::{Atom, Block, Expression};
-use definitions::{PRINT_CALL, RETURN_CALL, traits::UsedVars};
+use definitions::{PRINT_NEWLINE_CALL, PrintKind, RETURN_CALL, traits::UsedVars};
 use std::{collections::HashSet, fmt};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Statement {
     Return(Atom),
-    Print(Atom),
+    Print {
+        kind: PrintKind,
+        arg: Atom,
+    },
+    PrintNewline,
     Assign {
         var: String,
         bound: Expression,
@@ -14,18 +18,23 @@ pub enum Statement {
         var: String,
         bound: Expression,
     },
+    /// `cond` is a comparison, an atom or the negation of an atom
     If {
-        cond: Atom,
+        cond: Expression,
         then_block: Block,
         else_block: Block,
     },
     While {
-        cond: Atom,
+        cond: Expression,
         while_block: Block,
     },
 }
 
 impl Statement {
+    pub fn print(kind: PrintKind, arg: Atom) -> Statement {
+        Statement::Print { kind, arg }
+    }
+
     pub fn assign(var: &str, bound_exp: Expression) -> Statement {
         Statement::Assign {
             var: var.to_owned(),
@@ -40,7 +49,7 @@ impl Statement {
         }
     }
 
-    pub fn cond(cond_exp: Atom, then_block: Block, else_block: Block) -> Statement {
+    pub fn cond(cond_exp: Expression, then_block: Block, else_block: Block) -> Statement {
         Statement::If {
             cond: cond_exp,
             then_block,
@@ -53,7 +62,8 @@ impl UsedVars for Statement {
     fn used_vars(&self) -> HashSet<String> {
         match self {
             Statement::Return(atm) => atm.used_vars(),
-            Statement::Print(atm) => atm.used_vars(),
+            Statement::Print { arg, .. } => arg.used_vars(),
+            Statement::PrintNewline => HashSet::new(),
             Statement::Assign { var, bound } => {
                 let mut used = bound.used_vars();
                 used.insert(var.clone());
@@ -78,7 +88,8 @@ impl fmt::Display for Statement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Statement::Return(atm) => write!(f, "{RETURN_CALL}({atm})"),
-            Statement::Print(atm) => write!(f, "{PRINT_CALL}({atm})"),
+            Statement::Print { kind, arg } => write!(f, "{kind}({arg})"),
+            Statement::PrintNewline => write!(f, "{PRINT_NEWLINE_CALL}()"),
             Statement::Assign { var, bound } => write!(f, "let {var} = {bound};"),
             Statement::Set { var, bound } => write!(f, "set {var} = {bound};"),
             Statement::If {
@@ -6,7 +6,7 @@ impl ExplicateControl for monadic::Block {
         for stmt in self.stmts {
             stmt.explicate_control(accum)?;
         }
-        if !accum.current_statements.is_empty() {
+        if !accum.current_statements.is_empty() || !accum.has_block(&accum.current_label) {
             let cont = accum
                 .next_cont
                 .clone()
@@ -1,4 +1,7 @@
-use std::{collections::HashSet, mem::swap};
+use std::{
+    collections::{HashMap, HashSet},
+    mem::swap,
+};
 
 pub struct BlockAccum {
     blocks: Vec<core::Block>,
@@ -6,6 +9,7 @@ pub struct BlockAccum {
     pub current_label: String,
     pub next_cont: Option<core::Continuation>,
     used_labels: HashSet<String>,
+    string_labels: HashMap<String, String>,
 }
 
 impl BlockAccum {
@@ -16,6 +20,7 @@ impl BlockAccum {
             current_label: "start".to_owned(),
             used_labels: HashSet::from(["start".to_owned()]),
             next_cont: None,
+            string_labels: HashMap::new(),
         }
     }
 
@@ -39,6 +44,10 @@ impl BlockAccum {
         self.blocks.push(block);
     }
 
+    pub fn has_block(&self, label: &str) -> bool {
+        self.blocks.iter().any(|block| block.label == label)
+    }
+
     pub fn fresh_label(&mut self) -> String {
         let mut num = 0;
         let mut next = format!("block_{}", num);
@@ -50,6 +59,15 @@ impl BlockAccum {
         next
     }
 
+    pub fn string_label(&mut self, value: &str) -> String {
+        if let Some(label) = self.string_labels.get(value) {
+            return label.clone();
+        }
+        let label = format!("str_{}", self.string_labels.len());
+        self.string_labels.insert(value.to_owned(), label.clone());
+        label
+    }
+
     pub fn build_prog(self) -> core::Program {
         let mut prog = core::Program::new();
         for block in self.blocks {
@@ -7,7 +7,13 @@ impl ExplicateControl for monadic::Expression {
             monadic::Expression::Atm(atm) => {
                 Ok(core::Expression::Atm(atm.explicate_control(accum)?))
             }
+            monadic::Expression::Str(value) => Ok(core::Expression::Str {
+                label: accum.string_label(&value),
+                value,
+            }),
             monadic::Expression::ReadInt => Ok(core::Expression::ReadInt),
+            monadic::Expression::ReadBool => Ok(core::Expression::ReadBool),
+            monadic::Expression::TryReadInt => Ok(core::Expression::TryReadInt),
             monadic::Expression::UnaryOp { arg, op } => {
                 let arg_exp = arg.explicate_control(accum)?;
                 Ok(core::Expression::un(arg_exp, op))
@@ -22,6 +28,16 @@ impl ExplicateControl for monadic::Expression {
                 let right_exp = right.explicate_control(accum)?;
                 Ok(core::Expression::cmp(left_exp, cmp, right_exp))
             }
+            monadic::Expression::Call { name, args } => {
+                let mut arg_atms = Vec::with_capacity(args.len());
+                for arg in args {
+                    arg_atms.push(arg.explicate_control(accum)?);
+                }
+                Ok(core::Expression::Call {
+                    name,
+                    args: arg_atms,
+                })
+            }
         }
     }
 }
@@ -10,7 +10,9 @@ pub use errors::Error;
 pub fn explicate_control(prog: monadic::Program) -> Result<core::Program, Error> {
     let mut accum = BlockAccum::new();
     prog.main.explicate_control(&mut accum)?;
-    Ok(accum.build_prog())
+    let mut core_prog = accum.build_prog();
+    core_prog.globals = prog.globals;
+    Ok(core_prog)
 }
 
 pub trait ExplicateControl {
@@ -21,48 +23,38 @@ pub trait ExplicateControl {
 #[cfg(test)]
 mod explicate_tests {
     use super::explicate_control;
-    use definitions::{BinaryOperation, Comparator};
+    use definitions::{BinaryOperation, Comparator, PrintKind, UnaryOperation};
 
     #[test]
     fn explicate_if_nested() {
         let prog = monadic::Program::new(vec![
             monadic::Statement::assign("x", monadic::Expression::Atm(0.into())),
             monadic::Statement::assign("y", monadic::Expression::Atm(5.into())),
-            monadic::Statement::assign(
-                "z",
-                monadic::Expression::cmp("x".into(), Comparator::Lt, 1.into()),
-            ),
             monadic::Statement::cond(
-                "z".into(),
-                monadic::Block::new(vec![
-                    monadic::Statement::assign(
-                        "w",
-                        monadic::Expression::cmp("x".into(), Comparator::Eq, 0.into()),
-                    ),
-                    monadic::Statement::cond(
-                        "w".into(),
-                        monadic::Block::new(vec![
-                            monadic::Statement::assign(
-                                "z",
-                                monadic::Expression::bin(
-                                    "y".into(),
-                                    BinaryOperation::Add,
-                                    2.into(),
-                                ),
-                            ),
-                            monadic::Statement::Print("z".into()),
-                        ]),
-                        monadic::Block::new(vec![monadic::Statement::Print("y".into())]),
-                    ),
-                ]),
+                monadic::Expression::cmp("x".into(), Comparator::Lt, 1.into()),
+                monadic::Block::new(vec![monadic::Statement::cond(
+                    monadic::Expression::cmp("x".into(), Comparator::Eq, 0.into()),
+                    monadic::Block::new(vec![
+                        monadic::Statement::assign(
+                            "z",
+                            monadic::Expression::bin("y".into(), BinaryOperation::Add, 2.into()),
+                        ),
+                        monadic::Statement::print(PrintKind::Int, "z".into()),
+                    ]),
+                    monadic::Block::new(vec![monadic::Statement::print(
+                        PrintKind::Int,
+                        "y".into(),
+                    )]),
+                )]),
                 monadic::Block::new(vec![
                     monadic::Statement::assign(
                         "z",
                         monadic::Expression::bin("y".into(), BinaryOperation::Add, 10.into()),
                     ),
-                    monadic::Statement::Print("z".into()),
+                    monadic::Statement::print(PrintKind::Int, "z".into()),
                 ]),
             ),
+            monadic::Statement::print(PrintKind::Int, "x".into()),
         ]);
         let result = explicate_control(prog).unwrap();
         let mut expected = core::Program::new();
@@ -72,50 +64,54 @@ mod explicate_tests {
                 stmts: vec![
                     core::Statement::assign("x", core::Expression::Atm(0.into())),
                     core::Statement::assign("y", core::Expression::Atm(5.into())),
-                    core::Statement::assign(
-                        "z",
-                        core::Expression::cmp("x".into(), Comparator::Lt, 1.into()),
-                    ),
                 ],
-                cont: core::Continuation::If {
-                    cond: "z".into(),
-                    then_label: "block_0".to_owned(),
-                    else_label: "block_1".to_owned(),
-                },
+                cont: core::Continuation::branch(
+                    "x".into(),
+                    Comparator::Lt,
+                    1.into(),
+                    "block_0",
+                    "block_1",
+                ),
             },
         );
         expected.add_block(
             "block_0",
             core::Tail {
-                stmts: vec![core::Statement::assign(
-                    "w".into(),
-                    core::Expression::cmp("x".into(), Comparator::Eq, 0.into()),
-                )],
-                cont: core::Continuation::If {
-                    cond: "w".into(),
-                    then_label: "block_2".to_owned(),
-                    else_label: "block_3".to_owned(),
-                },
+                stmts: vec![],
+                cont: core::Continuation::branch(
+                    "x".into(),
+                    Comparator::Eq,
+                    0.into(),
+                    "block_3",
+                    "block_4",
+                ),
             },
         );
         expected.add_block(
             "block_3",
-            core::Tail {
-                stmts: vec![core::Statement::Print("y".into())],
-                cont: core::Continuation::Return(core::Atom::Unit),
-            },
-        );
-        expected.add_block(
-            "block_2",
             core::Tail {
                 stmts: vec![
                     core::Statement::assign(
                         "z",
                         core::Expression::bin("y".into(), BinaryOperation::Add, 2.into()),
                     ),
-                    core::Statement::Print("z".into()),
+                    core::Statement::print(PrintKind::Int, "z".into()),
                 ],
-                cont: core::Continuation::Return(core::Atom::Unit),
+                cont: core::Continuation::Goto("block_5".to_owned()),
+            },
+        );
+        expected.add_block(
+            "block_4",
+            core::Tail {
+                stmts: vec![core::Statement::print(PrintKind::Int, "y".into())],
+                cont: core::Continuation::Goto("block_5".to_owned()),
+            },
+        );
+        expected.add_block(
+            "block_5",
+            core::Tail {
+                stmts: vec![],
+                cont: core::Continuation::Goto("block_2".to_owned()),
             },
         );
         expected.add_block(
@@ -126,11 +122,91 @@ mod explicate_tests {
                         "z",
                         core::Expression::bin("y".into(), BinaryOperation::Add, 10.into()),
                     ),
-                    core::Statement::Print("z".into()),
+                    core::Statement::print(PrintKind::Int, "z".into()),
                 ],
+                cont: core::Continuation::Goto("block_2".to_owned()),
+            },
+        );
+        expected.add_block(
+            "block_2",
+            core::Tail {
+                stmts: vec![core::Statement::print(PrintKind::Int, "x".into())],
+                cont: core::Continuation::Return(core::Atom::Unit),
+            },
+        );
+        assert_eq!(result, expected);
+        assert_eq!(result.blocks.len(), expected.blocks.len())
+    }
+
+    #[test]
+    fn explicate_while_not() {
+        let prog = monadic::Program::new(vec![monadic::Statement::While {
+            cond: monadic::Expression::un("b".into(), UnaryOperation::Not),
+            while_block: monadic::Block::new(vec![monadic::Statement::PrintNewline]),
+        }]);
+        let result = explicate_control(prog).unwrap();
+        let mut expected = core::Program::new();
+        let cont = core::Continuation::cond("b".into(), "block_1", "block_0");
+        expected.add_block(
+            "start",
+            core::Tail {
+                stmts: vec![],
+                cont: cont.clone(),
+            },
+        );
+        expected.add_block(
+            "block_0",
+            core::Tail {
+                stmts: vec![core::Statement::PrintNewline],
+                cont,
+            },
+        );
+        expected.add_block("block_1", core::Atom::Unit.into());
+        assert_eq!(result, expected);
+        assert_eq!(result.blocks.len(), expected.blocks.len())
+    }
+
+    #[test]
+    fn explicate_if_join() {
+        let prog = monadic::Program::new(vec![
+            monadic::Statement::cond(
+                monadic::Expression::Atm("b".into()),
+                monadic::Block::new(vec![monadic::Statement::print(PrintKind::Int, 1.into())]),
+                monadic::Block::new(vec![]),
+            ),
+            monadic::Statement::print(PrintKind::Int, 2.into()),
+        ]);
+        let result = explicate_control(prog).unwrap();
+        let mut expected = core::Program::new();
+        expected.add_block(
+            "start",
+            core::Tail {
+                stmts: vec![],
+                cont: core::Continuation::cond("b".into(), "block_0", "block_1"),
+            },
+        );
+        expected.add_block(
+            "block_0",
+            core::Tail {
+                stmts: vec![core::Statement::print(PrintKind::Int, 1.into())],
+                cont: core::Continuation::Goto("block_2".to_owned()),
+            },
+        );
+        expected.add_block(
+            "block_1",
+            core::Tail {
+                stmts: vec![],
+                cont: core::Continuation::Goto("block_2".to_owned()),
+            },
+        );
+        expected.add_block(
+            "block_2",
+            core::Tail {
+                stmts: vec![core::Statement::print(PrintKind::Int, 2.into())],
                 cont: core::Continuation::Return(core::Atom::Unit),
             },
         );
-        assert_eq!(result, expected)
+        assert_eq!(result, expected);
+        assert_eq!(result.blocks.len(), expected.blocks.len())
     }
 }
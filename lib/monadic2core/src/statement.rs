@@ -1,4 +1,5 @@
 use super::{BlockAccum, Error, ExplicateControl};
+use definitions::UnaryOperation;
 
 impl ExplicateControl for monadic::Statement {
     type Target = ();
@@ -7,13 +8,19 @@ impl ExplicateControl for monadic::Statement {
             monadic::Statement::Return(atm) => {
                 let cont = core::Continuation::Return(atm.explicate_control(state)?);
                 state.next_block(cont);
+                // anything after returning is unreachable
+                state.current_label = state.fresh_label();
                 Ok(())
             }
-            monadic::Statement::Print(atm) => {
-                let stmt = core::Statement::Print(atm.explicate_control(state)?);
+            monadic::Statement::Print { kind, arg } => {
+                let stmt = core::Statement::print(kind, arg.explicate_control(state)?);
                 state.push_stmt(stmt);
                 Ok(())
             }
+            monadic::Statement::PrintNewline => {
+                state.push_stmt(core::Statement::PrintNewline);
+                Ok(())
+            }
             monadic::Statement::Assign { var, bound } => {
                 let bound_exp = bound.explicate_control(state)?;
                 state.push_stmt(core::Statement::assign(&var, bound_exp));
@@ -25,42 +32,58 @@ impl ExplicateControl for monadic::Statement {
                 Ok(())
             }
             monadic::Statement::If {
-                cond: cond_exp,
+                cond,
                 then_block,
                 else_block,
             } => {
-                let cond = cond_exp.explicate_control(state)?;
                 let then_label = state.fresh_label();
                 let else_label = state.fresh_label();
-                let cont = core::Continuation::If {
-                    cond,
-                    then_label: then_label.clone(),
-                    else_label: else_label.clone(),
-                };
+                let join_label = state.fresh_label();
+                let cont = cond_cont(cond, &then_label, &else_label, state)?;
                 state.next_block(cont);
+                let outer_cont = state
+                    .next_cont
+                    .replace(core::Continuation::Goto(join_label.clone()));
                 state.current_label = then_label;
                 then_block.explicate_control(state)?;
                 state.current_label = else_label;
                 else_block.explicate_control(state)?;
+                state.next_cont = outer_cont;
+                state.current_label = join_label;
                 Ok(())
             }
             monadic::Statement::While { cond, while_block } => {
-                let cond = cond.explicate_control(state)?;
                 let block_label = state.fresh_label();
                 let next_label = state.fresh_label();
-                let cont = core::Continuation::If {
-                    cond,
-                    then_label: block_label.clone(),
-                    else_label: next_label.clone(),
-                };
+                let cont = cond_cont(cond, &block_label, &next_label, state)?;
                 state.next_block(cont.clone());
+                let outer_cont = state.next_cont.replace(cont);
                 state.current_label = block_label;
-                state.next_cont = Some(cont);
                 while_block.explicate_control(state)?;
-                state.next_cont = None;
+                state.next_cont = outer_cont;
                 state.current_label = next_label;
                 Ok(())
             }
         }
     }
 }
+
+/// Branches directly on comparisons, negations swap the branch labels
+fn cond_cont(
+    cond: monadic::Expression,
+    then_label: &str,
+    else_label: &str,
+    state: &mut BlockAccum,
+) -> Result<core::Continuation, Error> {
+    match cond.explicate_control(state)? {
+        core::Expression::Cmp { left, cmp, right } => Ok(core::Continuation::branch(
+            left, cmp, right, then_label, else_label,
+        )),
+        core::Expression::Atm(atm) => Ok(core::Continuation::cond(atm, then_label, else_label)),
+        core::Expression::UnaryOp {
+            arg,
+            op: UnaryOperation::Not,
+        } => Ok(core::Continuation::cond(arg, else_label, then_label)),
+        exp => Err(Error::BadCmp(exp)),
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
     Error, Rule, pair_to_n_inner,
-    symbols::{parse_bin_op, parse_cmp, parse_un_op},
+    symbols::{parse_bin_op, parse_cmp, parse_un_op, unescape_str},
 };
+use definitions::UnaryOperation;
 use pest::iterators::Pair;
 use surface::Expression;
 
@@ -45,10 +46,29 @@ fn parse_prim_expression(pair: Pair<'_, Rule>) -> Result<Expression, Error> {
         }
         Rule::unary_op => parse_unary_expr(pair),
         Rule::read_int => Ok(Expression::ReadInt),
+        Rule::read_bool => Ok(Expression::ReadBool),
+        Rule::try_read_int => Ok(Expression::TryReadInt),
+        Rule::call => {
+            let mut inner = pair.into_inner();
+            let name_pair = inner.next().ok_or(Error::missing(Rule::variable))?;
+            let args = inner
+                .map(parse_expression)
+                .collect::<Result<Vec<_>, Error>>()?;
+            Ok(Expression::call(name_pair.as_str().trim(), args))
+        }
+        Rule::length => {
+            let arg_pair = pair_to_n_inner(pair, &[Rule::expression])?.remove(0);
+            let arg = parse_expression(arg_pair)?;
+            Ok(Expression::un(arg, UnaryOperation::Length))
+        }
         Rule::literal => {
             let num = pair.as_str().trim().parse::<i64>()?;
             Ok(Expression::lit(num))
         }
+        Rule::string => {
+            let inner_pair = pair_to_n_inner(pair, &[Rule::string_inner])?.remove(0);
+            Ok(Expression::Str(unescape_str(inner_pair)?))
+        }
         Rule::bool => {
             let b = pair.as_str().trim().parse::<bool>()?;
             Ok(Expression::bool(b))
@@ -1,6 +1,6 @@
 use pest::{Parser, iterators::Pair};
 use pest_derive::Parser;
-use surface::Program;
+use surface::{ExternDecl, GlobalDecl, Program};
 
 mod errors;
 mod expressions;
@@ -8,6 +8,7 @@ mod statements;
 mod symbols;
 use expressions::parse_expression;
 use statements::parse_statement;
+use symbols::parse_type;
 
 pub use errors::Error;
 
@@ -22,12 +23,22 @@ pub fn parse_program(input: &str) -> Result<Program, Error> {
         return Err(Error::remaining(p.as_rule()));
     }
 
+    let mut externs = vec![];
+    let mut globals = vec![];
     let mut stmts = vec![];
     let mut prog_inner = prog_pair.into_inner();
     for pair in prog_inner.by_ref() {
         if pair.as_rule() == Rule::EOI {
             break;
         }
+        if pair.as_rule() == Rule::extern_decl {
+            externs.push(parse_extern_decl(pair)?);
+            continue;
+        }
+        if pair.as_rule() == Rule::global_decl {
+            globals.push(parse_global_decl(pair)?);
+            continue;
+        }
         let mut stmt_inner = pair.into_inner();
         let stmt_pair = stmt_inner.next().ok_or(Error::missing(Rule::statement))?;
         if let Some(n) = stmt_inner.next() {
@@ -39,7 +50,26 @@ pub fn parse_program(input: &str) -> Result<Program, Error> {
     if let Some(p) = prog_inner.next() {
         return Err(Error::remaining(p.as_rule()));
     }
-    Ok(Program::new(stmts))
+    let mut prog = Program::new(stmts);
+    prog.externs = externs;
+    prog.globals = globals;
+    Ok(prog)
+}
+
+fn parse_global_decl(pair: Pair<'_, Rule>) -> Result<GlobalDecl, Error> {
+    let mut inner = pair_to_n_inner(pair, &[Rule::variable, Rule::ty, Rule::expression])?;
+    let name = inner.remove(0).as_str().trim().to_owned();
+    let ty = parse_type(inner.remove(0))?;
+    let init = parse_expression(inner.remove(0))?;
+    Ok(GlobalDecl::new(&name, ty, init))
+}
+
+fn parse_extern_decl(pair: Pair<'_, Rule>) -> Result<ExternDecl, Error> {
+    let mut inner = pair.into_inner();
+    let name_pair = inner.next().ok_or(Error::missing(Rule::variable))?;
+    let mut tys = inner.map(parse_type).collect::<Result<Vec<_>, Error>>()?;
+    let ret = tys.pop().ok_or(Error::missing(Rule::ty))?;
+    Ok(ExternDecl::new(name_pair.as_str().trim(), tys, ret))
 }
 
 fn pair_to_n_inner<'a>(
@@ -1,4 +1,4 @@
-use crate::{Error, Rule, pair_to_n_inner, parse_expression};
+use crate::{Error, Rule, pair_to_n_inner, parse_expression, symbols::parse_print_fn};
 use pest::iterators::Pair;
 use surface::{Block, Statement};
 
@@ -11,6 +11,7 @@ pub fn parse_statement(pair: Pair<'_, Rule>) -> Result<Statement, Error> {
         Rule::if_statement => parse_if(pair),
         Rule::while_statement => parse_while(pair),
         Rule::print_statement => parse_print(pair),
+        Rule::print_newline_statement => Ok(Statement::PrintNewline),
         Rule::let_statement => parse_let(pair),
         Rule::set_statement => parse_set(pair),
         r => Err(Error::unexpected(r, "Statement")),
@@ -70,9 +71,10 @@ fn parse_while(pair: Pair<'_, Rule>) -> Result<Statement, Error> {
 }
 
 fn parse_print(pair: Pair<'_, Rule>) -> Result<Statement, Error> {
-    let exp_rule = pair_to_n_inner(pair, &[Rule::expression])?.remove(0);
-    let exp = parse_expression(exp_rule)?;
-    Ok(Statement::Print(exp))
+    let mut inner = pair_to_n_inner(pair, &[Rule::print_fn, Rule::expression])?;
+    let kind = parse_print_fn(inner.remove(0))?;
+    let exp = parse_expression(inner.remove(0))?;
+    Ok(Statement::print(kind, exp))
 }
 
 fn parse_let(pair: Pair<'_, Rule>) -> Result<Statement, Error> {
@@ -1,6 +1,10 @@
 use crate::{Error, Rule};
-use definitions::{BinaryOperation, Comparator, UnaryOperation};
+use definitions::{
+    BinaryOperation, Comparator, PRINT_ANY, PRINT_BOOL_CALL, PRINT_CALL, PRINT_STRING_CALL,
+    PRINTLN_CALL, PrintKind, UnaryOperation,
+};
 use pest::iterators::Pair;
+use surface::Type;
 
 pub(crate) fn parse_un_op(pair: Pair<'_, Rule>) -> Result<UnaryOperation, Error> {
     match pair.as_str().trim() {
@@ -12,6 +16,7 @@ pub(crate) fn parse_un_op(pair: Pair<'_, Rule>) -> Result<UnaryOperation, Error>
 
 pub(crate) fn parse_bin_op(pair: Pair<'_, Rule>) -> Result<BinaryOperation, Error> {
     match pair.as_str().trim() {
+        "++" => Ok(BinaryOperation::Concat),
         "+" => Ok(BinaryOperation::Add),
         "-" => Ok(BinaryOperation::Sub),
         "&&" => Ok(BinaryOperation::And),
@@ -30,3 +35,46 @@ pub(crate) fn parse_cmp(pair: Pair<'_, Rule>) -> Result<Comparator, Error> {
         s => Err(Error::unknown(s)),
     }
 }
+
+pub(crate) fn parse_type(pair: Pair<'_, Rule>) -> Result<Type, Error> {
+    match pair.as_str().trim() {
+        "Int" => Ok(Type::Integer),
+        "Bool" => Ok(Type::Bool),
+        "String" => Ok(Type::String),
+        "()" => Ok(Type::Unit),
+        s => Err(Error::unknown(s)),
+    }
+}
+
+pub(crate) fn parse_print_fn(pair: Pair<'_, Rule>) -> Result<PrintKind, Error> {
+    match pair.as_str().trim() {
+        PRINT_CALL => Ok(PrintKind::Int),
+        PRINT_BOOL_CALL => Ok(PrintKind::Bool),
+        PRINT_STRING_CALL => Ok(PrintKind::String),
+        PRINTLN_CALL => Ok(PrintKind::Line),
+        PRINT_ANY => Ok(PrintKind::Any),
+        s => Err(Error::unknown(s)),
+    }
+}
+
+pub(crate) fn unescape_str(pair: Pair<'_, Rule>) -> Result<String, Error> {
+    let mut unescaped = String::new();
+    let mut chars = pair.as_str().chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('"') => unescaped.push('"'),
+            Some('\\') => unescaped.push('\\'),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('0') => unescaped.push('\0'),
+            Some(c) => return Err(Error::unknown(&format!("\\{c}"))),
+            None => return Err(Error::unknown("\\")),
+        }
+    }
+    Ok(unescaped)
+}
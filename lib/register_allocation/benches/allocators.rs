@@ -0,0 +1,87 @@
+//! Times liveness and the interference graph, and compares graph coloring and linear scan
+//! on generated programs with up to tens of thousands of variables
+//! Run with `cargo bench -p register_allocation`
+use asm::{Instruction, Reg, VarProgram};
+use register_allocation::{
+    Coloring, LiveProg, RegisterSet, build_interference_graph, build_move_graph, color_graph,
+    linear_scan, spill_costs, uncover_live,
+};
+use std::time::{Duration, Instant};
+
+/// Number of earlier variables each new variable reads, and so roughly how many are live
+const WINDOW: usize = 14;
+const SIZES: [usize; 4] = [100, 1000, 10000, 30000];
+const RUNS: u32 = 2;
+
+/// `x_i = x_(i-1) + x_(i-WINDOW)`, with the last variables summed into `%rax`
+fn generate(size: usize) -> VarProgram {
+    let var = |i: usize| format!("x{i}");
+    let mut instrs = vec![];
+    for i in 0..size {
+        if i < WINDOW {
+            instrs.push(Instruction::mov(i as i64, var(i).as_str()));
+            continue;
+        }
+        instrs.push(Instruction::mov(var(i - 1).as_str(), var(i).as_str()));
+        instrs.push(Instruction::add(var(i - WINDOW).as_str(), var(i).as_str()));
+    }
+    instrs.push(Instruction::mov(0, Reg::Rax));
+    for i in size.saturating_sub(WINDOW)..size {
+        instrs.push(Instruction::add(var(i).as_str(), Reg::Rax));
+    }
+    instrs.push(Instruction::jmp("conclusion"));
+    let mut prog = VarProgram::new();
+    prog.add_block("start", instrs);
+    prog
+}
+
+fn graph_coloring(prog: &LiveProg, registers: &RegisterSet) -> Coloring {
+    let interference_graph = build_interference_graph(prog);
+    let move_graph = build_move_graph(prog);
+    let costs = spill_costs(prog);
+    color_graph(interference_graph, move_graph, &costs, registers)
+        .expect("coloring of generated program")
+}
+
+fn time(prog: &LiveProg, allocate: fn(&LiveProg, &RegisterSet) -> Coloring) -> (Duration, usize) {
+    let registers = RegisterSet::default();
+    let mut spills = 0;
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        let coloring = allocate(prog, &registers);
+        spills = coloring
+            .0
+            .values()
+            .filter(|color| **color > registers.max_color())
+            .count();
+    }
+    (start.elapsed() / RUNS, spills)
+}
+
+fn time_once<T>(run: impl Fn() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let mut result = run();
+    for _ in 1..RUNS {
+        result = run();
+    }
+    (start.elapsed() / RUNS, result)
+}
+
+fn main() {
+    println!(
+        "{:>6} {:>14} {:>14} {:>14} {:>8} {:>14} {:>8}",
+        "vars", "liveness", "interference", "coloring", "spills", "linear scan", "spills"
+    );
+    for size in SIZES {
+        let var_prog = generate(size);
+        let (live_time, prog) =
+            time_once(|| uncover_live(var_prog.clone()).expect("liveness of generated program"));
+        let (graph_time, _) = time_once(|| build_interference_graph(&prog));
+        let (color_time, color_spills) = time(&prog, graph_coloring);
+        let (scan_time, scan_spills) = time(&prog, linear_scan);
+        println!(
+            "{size:>6} {:>14?} {:>14?} {:>14?} {color_spills:>8} {:>14?} {scan_spills:>8}",
+            live_time, graph_time, color_time, scan_time
+        );
+    }
+}
@@ -17,6 +17,7 @@ fn collect_instr(instr: &Instruction<VarArg>) -> HashSet<Reg> {
         Instruction::AddQ { src, dest } => &collect_arg(src) | &collect_arg(dest),
         Instruction::SubQ { src, dest } => &collect_arg(src) | &collect_arg(dest),
         Instruction::NegQ { arg } => collect_arg(arg),
+        Instruction::IncQ { arg } => collect_arg(arg),
         Instruction::MovQ { src, dest } => &collect_arg(src) | &collect_arg(dest),
         Instruction::PushQ { arg } => collect_arg(arg),
         Instruction::PopQ { arg } => collect_arg(arg),
@@ -30,6 +31,7 @@ fn collect_instr(instr: &Instruction<VarArg>) -> HashSet<Reg> {
         Instruction::JumpCC { .. } => HashSet::new(),
         Instruction::AndQ { src, dest } => &collect_arg(src) | &collect_arg(dest),
         Instruction::OrQ { src, dest } => &collect_arg(src) | &collect_arg(dest),
+        Instruction::LeaQ { dest, .. } => collect_arg(dest),
     }
 }
 
@@ -37,20 +39,20 @@ fn collect_arg(arg: &VarArg) -> HashSet<Reg> {
     match arg {
         VarArg::Var(_) => HashSet::new(),
         VarArg::Arg(Arg::Immediate(_)) => HashSet::new(),
-        VarArg::Arg(Arg::Register(reg)) => {
-            if Reg::callee_saved().contains(reg) {
-                HashSet::from([reg.clone()])
-            } else {
-                HashSet::new()
-            }
-        }
-        VarArg::Arg(Arg::Deref(reg, _)) => {
-            if Reg::callee_saved().contains(reg) {
+        VarArg::Arg(Arg::Register(reg)) | VarArg::Arg(Arg::Deref(reg, _)) => {
+            if needs_saving(reg) {
                 HashSet::from([reg.clone()])
             } else {
                 HashSet::new()
             }
         }
         VarArg::Arg(Arg::ByteReg(_)) => HashSet::new(),
+        VarArg::Arg(Arg::Global(_)) => HashSet::new(),
     }
 }
+
+/// Callee saved registers the prelude has to push,
+/// the stack and base pointers are restored by the prelude and conclusion themselves
+pub fn needs_saving(reg: &Reg) -> bool {
+    Reg::callee_saved().contains(reg) && !matches!(reg, Reg::Rsp | Reg::Rbp)
+}
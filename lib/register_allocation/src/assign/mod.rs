@@ -1,28 +1,46 @@
 use crate::{
-    colors::{Coloring, coloring_to_assignment},
+    colors::{Coloring, RegisterSet},
     errors::Error,
     program::LiveProg,
 };
-use asm::{Arg, Block, Instruction, Program, VarArg};
+use asm::{Arg, Block, Instruction, Program, Reg, SCRATCH_REGISTER, VarArg};
 use std::collections::HashMap;
 
 mod collect_callee;
-mod collect_vars;
-use collect_callee::collect_callee;
-use collect_vars::collect_vars;
+use collect_callee::{collect_callee, needs_saving};
 
-pub fn assign_homes(prog: LiveProg, coloring: Coloring) -> Result<Program, Error> {
-    let used_callee = collect_callee(&prog);
-    let vars = collect_vars(&prog);
-    let stack_space = vars.len() as u64 * 8;
-    let assignments = coloring_to_assignment(coloring);
+pub fn assign_homes(
+    prog: LiveProg,
+    coloring: Coloring,
+    registers: &RegisterSet,
+) -> Result<Program, Error> {
+    let mut used_callee = collect_callee(&prog);
+    let mut assignments = registers.coloring_to_assignment(coloring);
+    used_callee.extend(assignments.values().filter_map(|arg| match arg {
+        Arg::Register(reg) if needs_saving(reg) => Some(reg.clone()),
+        _ => None,
+    }));
+    let spilled = assignments
+        .values()
+        .any(|arg| matches!(arg, Arg::Deref(Reg::Rbp, _)));
+    if spilled {
+        // patching moves between stack slots saves the scratch register,
+        // which has to happen before the slots are placed below the saved registers
+        used_callee.insert(SCRATCH_REGISTER);
+    }
+    let stack_space = stack_space(&mut assignments, used_callee.len());
     let mut assigned = Program::new(stack_space, used_callee);
+    assigned.strings = prog.strings;
+    assigned.globals = prog.globals;
     for block in prog.blocks {
-        let assigned_instrs = block
+        let mut assigned_instrs = block
             .instrs
             .into_iter()
             .map(|instr| assign_instr(instr.instr, &assignments))
             .collect::<Result<Vec<_>, Error>>()?;
+        // moves between coalesced variables
+        assigned_instrs
+            .retain(|instr| !matches!(instr, Instruction::MovQ { src, dest } if src == dest));
         assigned
             .blocks
             .push(Block::new(&block.label, assigned_instrs));
@@ -30,6 +48,24 @@ pub fn assign_homes(prog: LiveProg, coloring: Coloring) -> Result<Program, Error
     Ok(assigned)
 }
 
+/// Moves stack slots below the `saved` callee saved registers the prelude pushes
+/// and returns the bytes down to the deepest slot below them,
+/// spilled variables that do not interfere share their slots
+fn stack_space(assignments: &mut HashMap<String, Arg>, saved: usize) -> u64 {
+    let saved_space = 8 * saved as i64;
+    assignments
+        .values_mut()
+        .filter_map(|arg| match arg {
+            Arg::Deref(Reg::Rbp, offset) => {
+                *offset -= saved_space;
+                Some(offset.unsigned_abs() - saved_space as u64)
+            }
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+}
+
 fn assign_instr(
     instr: Instruction<VarArg>,
     assignments: &HashMap<String, Arg>,
@@ -46,6 +82,9 @@ fn assign_instr(
         Instruction::NegQ { arg } => Ok(Instruction::NegQ {
             arg: assign_arg(arg, assignments)?,
         }),
+        Instruction::IncQ { arg } => Ok(Instruction::IncQ {
+            arg: assign_arg(arg, assignments)?,
+        }),
         Instruction::MovQ { src, dest } => Ok(Instruction::MovQ {
             src: assign_arg(src, assignments)?,
             dest: assign_arg(dest, assignments)?,
@@ -56,7 +95,7 @@ fn assign_instr(
         Instruction::PopQ { arg } => Ok(Instruction::PopQ {
             arg: assign_arg(arg, assignments)?,
         }),
-        Instruction::CallQ { label } => Ok(Instruction::CallQ { label }),
+        Instruction::CallQ { label, arity } => Ok(Instruction::CallQ { label, arity }),
         Instruction::RetQ => Ok(Instruction::RetQ),
         Instruction::Jump { label } => Ok(Instruction::Jump { label }),
         Instruction::XorQ { src, dest } => Ok(Instruction::XorQ {
@@ -84,6 +123,10 @@ fn assign_instr(
             src: assign_arg(src, assignments)?,
             dest: assign_arg(dest, assignments)?,
         }),
+        Instruction::LeaQ { label, dest } => Ok(Instruction::LeaQ {
+            label,
+            dest: assign_arg(dest, assignments)?,
+        }),
     }
 }
 fn assign_arg(arg: VarArg, assignments: &HashMap<String, Arg>) -> Result<Arg, Error> {
@@ -95,9 +138,12 @@ fn assign_arg(arg: VarArg, assignments: &HashMap<String, Arg>) -> Result<Arg, Er
 
 #[cfg(test)]
 mod assign_homes_tests {
-    use super::{Coloring, LiveProg, assign_homes};
-    use crate::program::{LiveBlock, LiveInstruction};
-    use asm::{Arg, Block, Instruction, Program, Reg, VarArg};
+    use super::{Coloring, LiveProg, RegisterSet, assign_homes};
+    use crate::{
+        graph::BitSet,
+        program::{LiveBlock, LiveInstruction},
+    };
+    use asm::{Arg, Block, Instruction, Program, Reg, SCRATCH_REGISTER, VarArg};
     use std::collections::{HashMap, HashSet};
 
     #[test]
@@ -111,30 +157,31 @@ mod assign_homes_tests {
                         src: Arg::Immediate(42).into(),
                         dest: VarArg::Var("a".to_owned()),
                     },
-                    live_before: HashSet::new(),
-                    live_after: HashSet::new(),
+                    live_before: BitSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
                     instr: Instruction::MovQ {
                         src: VarArg::Var("a".to_owned()),
                         dest: VarArg::Var("b".to_owned()),
                     },
-                    live_before: HashSet::new(),
-                    live_after: HashSet::new(),
+                    live_before: BitSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
                     instr: Instruction::MovQ {
                         src: VarArg::Var("b".to_owned()),
                         dest: Reg::Rax.into(),
                     },
-                    live_before: HashSet::new(),
-                    live_after: HashSet::new(),
+                    live_before: BitSet::new(),
+                    live_after: BitSet::new(),
                 },
             ],
         });
         let result = assign_homes(
             prog,
             Coloring(HashMap::from([("a".into(), 11), ("b".into(), 12)])),
+            &RegisterSet::new(11),
         )
         .unwrap();
         let block_fun = |offset1: i64, offset2: i64| {
@@ -153,14 +200,175 @@ mod assign_homes_tests {
                 },
             ]
         };
-        let mut expected1 = Program::new(16, HashSet::new());
+        let mut expected1 = Program::new(16, HashSet::from([SCRATCH_REGISTER]));
         expected1
             .blocks
-            .push(Block::new("start", block_fun(-8, -16)));
-        let mut expected2 = Program::new(16, HashSet::new());
+            .push(Block::new("start", block_fun(-16, -24)));
+        let mut expected2 = Program::new(16, HashSet::from([SCRATCH_REGISTER]));
         expected2
             .blocks
-            .push(Block::new("start", block_fun(-16, -8)));
+            .push(Block::new("start", block_fun(-24, -16)));
         assert!(result == expected1 || result == expected2)
     }
+
+    #[test]
+    fn assign_coalesced() {
+        let mut prog = LiveProg::new();
+        prog.blocks.push(LiveBlock {
+            label: "start".to_owned(),
+            instrs: vec![
+                LiveInstruction {
+                    instr: Instruction::mov(42, "a"),
+                    live_before: BitSet::new(),
+                    live_after: BitSet::new(),
+                },
+                LiveInstruction {
+                    instr: Instruction::mov("a", "b"),
+                    live_before: BitSet::new(),
+                    live_after: BitSet::new(),
+                },
+                LiveInstruction {
+                    instr: Instruction::mov("b", Reg::Rax),
+                    live_before: BitSet::new(),
+                    live_after: BitSet::new(),
+                },
+            ],
+        });
+        let result = assign_homes(
+            prog,
+            Coloring(HashMap::from([("a".into(), 0), ("b".into(), 0)])),
+            &RegisterSet::default(),
+        )
+        .unwrap();
+        let mut expected = Program::new(0, HashSet::new());
+        expected.blocks.push(Block::new(
+            "start",
+            vec![
+                Instruction::MovQ {
+                    src: Arg::Immediate(42),
+                    dest: Reg::Rcx.into(),
+                },
+                Instruction::MovQ {
+                    src: Reg::Rcx.into(),
+                    dest: Reg::Rax.into(),
+                },
+            ],
+        ));
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn assign_shared_slot() {
+        let mut prog = LiveProg::new();
+        prog.blocks.push(LiveBlock {
+            label: "start".to_owned(),
+            instrs: vec![
+                Instruction::mov(1, "a").into(),
+                Instruction::mov("a", Reg::Rax).into(),
+                Instruction::mov(2, "b").into(),
+                Instruction::add("b", Reg::Rax).into(),
+            ],
+        });
+        let result = assign_homes(
+            prog,
+            Coloring(HashMap::from([("a".into(), 11), ("b".into(), 11)])),
+            &RegisterSet::new(11),
+        )
+        .unwrap();
+        assert_eq!(result.stack_space, 8);
+        assert_eq!(
+            result.blocks[0].instrs[2],
+            Instruction::MovQ {
+                src: Arg::Immediate(2),
+                dest: Arg::Deref(Reg::Rbp, -16),
+            }
+        );
+    }
+
+    #[test]
+    fn assign_stack_args() {
+        let mut prog = LiveProg::new();
+        prog.blocks.push(LiveBlock {
+            label: "start".to_owned(),
+            instrs: vec![
+                Instruction::sub(16, Reg::Rsp).into(),
+                Instruction::mov("a", Arg::Deref(Reg::Rsp, 0)).into(),
+                Instruction::call("weighted_sum", 9).into(),
+                Instruction::add(16, Reg::Rsp).into(),
+                Instruction::mov(Reg::Rbx, Reg::Rax).into(),
+            ],
+        });
+        let result = assign_homes(
+            prog,
+            Coloring(HashMap::from([("a".into(), 0)])),
+            &RegisterSet::default(),
+        )
+        .unwrap();
+        assert_eq!(result.used_callee, HashSet::from([Reg::Rbx]));
+    }
+
+    #[test]
+    fn assign_below_saved() {
+        let mut prog = LiveProg::new();
+        prog.blocks.push(LiveBlock {
+            label: "start".to_owned(),
+            instrs: vec![
+                Instruction::mov(1, "a").into(),
+                Instruction::mov(2, "b").into(),
+                Instruction::add("b", "a").into(),
+                Instruction::mov("a", Reg::Rax).into(),
+            ],
+        });
+        let result = assign_homes(
+            prog,
+            Coloring(HashMap::from([("a".into(), 9), ("b".into(), 13)])),
+            &RegisterSet::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            result.used_callee,
+            HashSet::from([Reg::Rbx, SCRATCH_REGISTER])
+        );
+        assert_eq!(result.stack_space, 8);
+        assert_eq!(
+            result.blocks[0].instrs[1],
+            Instruction::MovQ {
+                src: Arg::Immediate(2),
+                dest: Arg::Deref(Reg::Rbp, -24),
+            }
+        );
+    }
+
+    #[test]
+    fn assign_saves_callee() {
+        let mut prog = LiveProg::new();
+        prog.blocks.push(LiveBlock {
+            label: "start".to_owned(),
+            instrs: vec![
+                LiveInstruction {
+                    instr: Instruction::MovQ {
+                        src: Arg::Immediate(42).into(),
+                        dest: VarArg::Var("a".to_owned()),
+                    },
+                    live_before: BitSet::new(),
+                    live_after: BitSet::new(),
+                },
+                LiveInstruction {
+                    instr: Instruction::MovQ {
+                        src: VarArg::Var("a".to_owned()),
+                        dest: Reg::Rax.into(),
+                    },
+                    live_before: BitSet::new(),
+                    live_after: BitSet::new(),
+                },
+            ],
+        });
+        let result = assign_homes(
+            prog,
+            Coloring(HashMap::from([("a".into(), 9)])),
+            &RegisterSet::default(),
+        )
+        .unwrap();
+        assert_eq!(result.used_callee, HashSet::from([Reg::Rbx]));
+    }
 }
@@ -0,0 +1,167 @@
+use crate::{colors::RegisterSet, graph::LocationGraph, program::Location};
+use std::collections::{BTreeSet, HashMap};
+
+/// Merges move related variables that do not interfere, as long as the
+/// Briggs or George test guarantees the merged graph is no harder to color
+/// Moves failing both tests are retried when one of their variables is merged, or when a
+/// merge leaves one of their variables or its neighbours with insignificant degree
+/// Returns the representative each merged variable was coalesced into
+pub fn coalesce(
+    interference_graph: &mut LocationGraph,
+    move_graph: &mut LocationGraph,
+    registers: &RegisterSet,
+) -> HashMap<Location, Location> {
+    let registers = registers.registers.len();
+    let mut aliases: HashMap<Location, Location> = HashMap::new();
+    let mut moves: BTreeSet<(Location, Location)> = move_graph
+        .edges()
+        .map(|(left, right)| {
+            (
+                left.clone().min(right.clone()),
+                left.clone().max(right.clone()),
+            )
+        })
+        .collect();
+    // moves that failed the tests, by the variables they were merging
+    let mut rejected: HashMap<Location, Vec<(Location, Location)>> = HashMap::new();
+    while let Some((left, right)) = moves.pop_first() {
+        let keep = representative(&aliases, left.clone());
+        let remove = representative(&aliases, right.clone());
+        if keep == remove
+            || !matches!(keep, Location::Variable(_))
+            || !matches!(remove, Location::Variable(_))
+            || interference_graph.has_edge(&keep, &remove)
+        {
+            continue;
+        }
+        if !briggs(interference_graph, &keep, &remove, registers)
+            && !george(interference_graph, &keep, &remove, registers)
+            && !george(interference_graph, &remove, &keep, registers)
+        {
+            for var in [keep, remove] {
+                rejected
+                    .entry(var)
+                    .or_default()
+                    .push((left.clone(), right.clone()));
+            }
+            continue;
+        }
+        // common neighbours lose an edge, which can make them insignificant and
+        // so let moves of their neighbours pass the tests
+        let insignificant: Vec<Location> = interference_graph
+            .neighbours(&remove)
+            .filter(|vert| {
+                matches!(vert, Location::Variable(_))
+                    && interference_graph.degree(vert) == registers
+                    && interference_graph.has_edge(vert, &keep)
+            })
+            .cloned()
+            .collect();
+        interference_graph.merge(&remove, &keep);
+        move_graph.merge(&remove, &keep);
+        let mut changed = vec![remove.clone(), keep.clone()];
+        for vert in insignificant {
+            changed.extend(interference_graph.neighbours(&vert).cloned());
+            changed.push(vert);
+        }
+        for var in changed {
+            moves.extend(rejected.remove(&var).into_iter().flatten());
+        }
+        aliases.insert(remove, keep);
+    }
+    aliases
+        .keys()
+        .map(|var| (var.clone(), representative(&aliases, var.clone())))
+        .collect()
+}
+
+fn representative(aliases: &HashMap<Location, Location>, mut var: Location) -> Location {
+    while let Some(alias) = aliases.get(&var) {
+        var = alias.clone();
+    }
+    var
+}
+
+/// Registers are precolored, so they always count as significant
+fn significant(interference_graph: &LocationGraph, vert: &Location, registers: usize) -> bool {
+    !matches!(vert, Location::Variable(_)) || interference_graph.degree(vert) >= registers
+}
+
+/// The merged node has fewer than `registers` neighbours of significant degree
+fn briggs(
+    interference_graph: &LocationGraph,
+    left: &Location,
+    right: &Location,
+    registers: usize,
+) -> bool {
+    let mut neighbours = interference_graph.adjacent(left);
+    neighbours.extend(interference_graph.adjacent(right));
+    neighbours
+        .iter()
+        .filter(|vert| significant(interference_graph, vert, registers))
+        .count()
+        < registers
+}
+
+/// Every neighbour of `remove` already interferes with `keep` or has insignificant degree
+fn george(
+    interference_graph: &LocationGraph,
+    keep: &Location,
+    remove: &Location,
+    registers: usize,
+) -> bool {
+    interference_graph.neighbours(remove).all(|vert| {
+        interference_graph.has_edge(vert, keep) || !significant(interference_graph, vert, registers)
+    })
+}
+
+#[cfg(test)]
+mod coalesce_tests {
+    use super::coalesce;
+    use crate::{colors::RegisterSet, graph::LocationGraph, program::Location};
+    use asm::Reg;
+    use std::collections::HashMap;
+
+    #[test]
+    fn coalesce_chain() {
+        let mut interference_graph = LocationGraph::new();
+        interference_graph.add_edge("x".into(), "w".into());
+        interference_graph.add_edge("y".into(), Reg::Rax.into());
+        interference_graph.add_edge("z".into(), "y".into());
+        let mut move_graph = LocationGraph::new();
+        move_graph.add_edge("x".into(), "y".into());
+        move_graph.add_edge("y".into(), "z".into());
+        let aliases = coalesce(
+            &mut interference_graph,
+            &mut move_graph,
+            &RegisterSet::default(),
+        );
+
+        assert_eq!(aliases, HashMap::from([("y".into(), "x".into())]));
+        assert!(interference_graph.has_edge(&"x".into(), &Reg::Rax.into()));
+        assert!(interference_graph.has_edge(&"x".into(), &"z".into()));
+        assert!(!interference_graph.contains(&"y".into()));
+    }
+
+    #[test]
+    fn coalesce_conservative() {
+        // both endpoints have many disjoint neighbours of high degree
+        let registers = RegisterSet::new(4);
+        let count = registers.registers.len();
+        let mut interference_graph = LocationGraph::new();
+        let clique: Vec<Location> = (0..2 * count)
+            .map(|i| Location::Variable(format!("c{i}")))
+            .collect();
+        for (ind, var) in clique.iter().enumerate() {
+            for other in clique[ind + 1..].iter() {
+                interference_graph.add_edge(var.clone(), other.clone());
+            }
+            let endpoint = if ind < count { "a" } else { "b" };
+            interference_graph.add_edge(endpoint.into(), var.clone());
+        }
+        let mut move_graph = LocationGraph::new();
+        move_graph.add_edge("a".into(), "b".into());
+        let aliases = coalesce(&mut interference_graph, &mut move_graph, &registers);
+        assert!(aliases.is_empty());
+    }
+}
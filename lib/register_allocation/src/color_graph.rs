@@ -1,72 +1,283 @@
 use crate::{
-    colors::{Color, Coloring, empty_coloring, saturation},
+    coalesce::coalesce,
+    colors::{Color, Coloring, RegisterSet, saturation},
     errors::Error,
     graph::LocationGraph,
     program::Location,
+    spill_costs::SpillCosts,
+};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap, HashSet},
 };
-use std::collections::HashSet;
 
+/// Colors the interference graph, using stack slots only where registers run out
+/// Move related variables are coalesced first where this is safe, so they share a color
+/// Whenever a variable would not get a register, it goes on the stack unless a neighbour
+/// with a lower spill cost per interfering variable can free a register for it
 pub fn color_graph(
-    interference_graph: LocationGraph,
-    move_graph: LocationGraph,
+    mut interference_graph: LocationGraph,
+    mut move_graph: LocationGraph,
+    costs: &SpillCosts,
+    registers: &RegisterSet,
 ) -> Result<Coloring, Error> {
-    let mut vert_set = interference_graph.verts.clone();
-    let mut coloring = empty_coloring();
-    vert_set.retain(|vert| matches!(vert, Location::Variable(_)));
-    'outer: while !vert_set.is_empty() {
-        let mut next_candidates = get_next_candidates(&vert_set, &interference_graph, &coloring)?;
-        next_candidates.sort();
-        for candidate in next_candidates.iter() {
-            if let Some(color) =
-                get_move_related(candidate, &interference_graph, &move_graph, &coloring)
-            {
-                vert_set.remove(candidate);
-                coloring.0.insert(candidate.clone(), color);
-                continue 'outer;
+    let aliases = coalesce(&mut interference_graph, &mut move_graph, registers);
+    let mut costs = costs.clone();
+    for (var, alias) in aliases.iter() {
+        let cost = costs.get(var).copied().unwrap_or_default();
+        *costs.entry(alias.clone()).or_default() += cost;
+    }
+    let (mut coloring, spilled) =
+        color_registers(&interference_graph, &move_graph, &costs, registers)?;
+    color_spilled(
+        &mut coloring,
+        &interference_graph,
+        spilled,
+        &costs,
+        registers,
+    );
+    for (var, alias) in aliases {
+        let color = coloring.0[&alias];
+        coloring.0.insert(var, color);
+    }
+    Ok(coloring)
+}
+
+/// Uncolored variables with how many colored neighbours have each color
+/// Saturation is the number of distinct colors, kept up to date as neighbours
+/// are colored or spilled again instead of being recomputed
+struct Saturations<'a> {
+    colors: HashMap<&'a Location, HashMap<Color, usize>>,
+    /// Uncolored variables by saturation
+    queue: BTreeSet<(usize, &'a Location)>,
+    /// Uncolored variables with a colored move related variable, by saturation
+    move_ready: BTreeSet<(usize, &'a Location)>,
+}
+
+impl<'a> Saturations<'a> {
+    fn new(interference_graph: &'a LocationGraph, coloring: &Coloring) -> Saturations<'a> {
+        let mut colors: HashMap<&Location, HashMap<Color, usize>> = HashMap::new();
+        for vert in interference_graph
+            .verts()
+            .filter(|vert| matches!(vert, Location::Variable(_)))
+        {
+            let counts = colors.entry(vert).or_default();
+            for adj in interference_graph.neighbours(vert) {
+                if let Some(color) = coloring.0.get(adj) {
+                    *counts.entry(*color).or_default() += 1;
+                }
             }
         }
-        let next_candidate = next_candidates.first().ok_or(Error::NextVertex)?;
-        let adjacent_colors: Vec<&Color> = interference_graph
-            .verts
+        let queue = colors
             .iter()
-            .filter_map(|vert| coloring.0.get(vert))
+            .map(|(vert, counts)| (counts.len(), *vert))
             .collect();
-        let mut next_color = 0;
-        while adjacent_colors.contains(&&next_color) {
-            next_color += 1;
+        Saturations {
+            colors,
+            queue,
+            move_ready: BTreeSet::new(),
         }
-        vert_set.remove(next_candidate);
-        coloring.0.insert(next_candidate.clone(), next_color);
     }
-    Ok(coloring)
+
+    fn saturation(&self, vert: &Location) -> usize {
+        self.colors.get(vert).map_or(0, |counts| counts.len())
+    }
+
+    /// Updates the saturation of `vert` in both queues
+    fn requeue(&mut self, vert: &'a Location, old: usize) {
+        let new = self.saturation(vert);
+        if new == old {
+            return;
+        }
+        self.queue.remove(&(old, vert));
+        self.queue.insert((new, vert));
+        if self.move_ready.remove(&(old, vert)) {
+            self.move_ready.insert((new, vert));
+        }
+    }
+
+    fn add_color(&mut self, vert: &'a Location, color: Color) {
+        let old = self.saturation(vert);
+        let Some(counts) = self.colors.get_mut(vert) else {
+            return;
+        };
+        *counts.entry(color).or_default() += 1;
+        self.requeue(vert, old);
+    }
+
+    fn remove_color(&mut self, vert: &'a Location, color: Color) {
+        let old = self.saturation(vert);
+        let Some(counts) = self.colors.get_mut(vert) else {
+            return;
+        };
+        if let Some(count) = counts.get_mut(&color) {
+            *count -= 1;
+            if *count == 0 {
+                counts.remove(&color);
+            }
+        }
+        self.requeue(vert, old);
+    }
+
+    fn mark_move_ready(&mut self, vert: &'a Location) {
+        if self.colors.contains_key(vert) {
+            self.move_ready.insert((self.saturation(vert), vert));
+        }
+    }
+
+    /// Takes `vert` out once it is colored or spilled
+    fn remove(&mut self, vert: &'a Location) {
+        let sat = self.saturation(vert);
+        self.queue.remove(&(sat, vert));
+        self.move_ready.remove(&(sat, vert));
+        self.colors.remove(vert);
+    }
 }
 
-fn get_next_candidates(
-    vert_set: &HashSet<Location>,
-    interference_graph: &LocationGraph,
+/// Colors all variables with registers, always picking a variable with the fewest colors
+/// among its neighbours next and preferring a variable that can share the color of a move
+/// related one
+/// When a variable would not get a register, a cheaper neighbour that alone holds a register
+/// color is spilled to free that color, otherwise the variable itself is spilled
+/// Spilled variables get another chance at a register once all variables are colored
+fn color_registers<'a>(
+    interference_graph: &'a LocationGraph,
+    move_graph: &'a LocationGraph,
+    costs: &SpillCosts,
+    registers: &RegisterSet,
+) -> Result<(Coloring, HashSet<Location>), Error> {
+    let mut coloring = registers.empty_coloring();
+    let mut spilled = HashSet::new();
+    let mut saturations = Saturations::new(interference_graph, &coloring);
+    let spill_cost = |vert: &Location| {
+        let cost = costs.get(vert).copied().unwrap_or_default() as f64;
+        cost / interference_graph.degree(vert).max(1) as f64
+    };
+
+    while let Some((min_saturation, first)) = saturations.queue.first().copied() {
+        // variables that cannot share a color now are dropped until another
+        // move related variable is colored
+        let mut move_related = None;
+        let mut blocked = vec![];
+        for (sat, vert) in saturations
+            .move_ready
+            .iter()
+            .take_while(|(sat, _)| *sat == min_saturation)
+        {
+            match get_move_related(vert, interference_graph, move_graph, &coloring) {
+                Some(color) => {
+                    move_related = Some((*vert, color));
+                    break;
+                }
+                None => blocked.push((*sat, *vert)),
+            }
+        }
+        for entry in blocked {
+            saturations.move_ready.remove(&entry);
+        }
+        let (next, mut color) = move_related
+            .unwrap_or_else(|| (first, lowest_color(first, interference_graph, &coloring)));
+        saturations.remove(next);
+
+        if color > registers.max_color() {
+            match evictable(next, interference_graph, &coloring, registers, &spill_cost) {
+                Some((evicted, freed)) => {
+                    coloring.0.remove(evicted);
+                    for adj in interference_graph.neighbours(evicted) {
+                        saturations.remove_color(adj, freed);
+                    }
+                    spilled.insert(evicted.clone());
+                    color = freed;
+                }
+                None => {
+                    spilled.insert(next.clone());
+                    continue;
+                }
+            }
+        }
+
+        coloring.0.insert(next.clone(), color);
+        for adj in interference_graph.neighbours(next) {
+            saturations.add_color(adj, color);
+        }
+        for related in move_graph.neighbours(next) {
+            saturations.mark_move_ready(related);
+        }
+    }
+
+    // evicted variables may fit again once everything else is colored
+    let mut retry: Vec<Location> = spilled.iter().cloned().collect();
+    retry.sort_by_key(|vert| (Reverse(costs.get(vert).copied()), vert.clone()));
+    for vert in retry {
+        let color = lowest_color(&vert, interference_graph, &coloring);
+        if color <= registers.max_color() {
+            spilled.remove(&vert);
+            coloring.0.insert(vert, color);
+        }
+    }
+    Ok((coloring, spilled))
+}
+
+/// The cheapest colored neighbour of `vert` that is the only neighbour with its register
+/// color and cheaper to spill than `vert`, together with the color spilling it frees
+fn evictable<'a>(
+    vert: &Location,
+    interference_graph: &'a LocationGraph,
     coloring: &Coloring,
-) -> Result<Vec<Location>, Error> {
-    let saturations: Vec<(&Location, usize)> = vert_set
-        .iter()
-        .map(|vert| {
-            (
-                vert,
-                saturation(interference_graph, vert, coloring).len() as usize,
-            )
+    registers: &RegisterSet,
+    spill_cost: &impl Fn(&Location) -> f64,
+) -> Option<(&'a Location, Color)> {
+    let mut holders: HashMap<Color, Vec<&Location>> = HashMap::new();
+    for adj in interference_graph.neighbours(vert) {
+        if let Some(color) = coloring.0.get(adj) {
+            holders.entry(*color).or_default().push(adj);
+        }
+    }
+    let vert_cost = spill_cost(vert);
+    holders
+        .into_iter()
+        .filter_map(|(color, holders)| match holders[..] {
+            [holder] if color >= 0 && color <= registers.max_color() => Some((holder, color)),
+            _ => None,
         })
-        .collect();
-    let min_saturation = saturations
-        .iter()
-        .map(|(_, s)| s)
-        .min()
-        .copied()
-        .unwrap_or(0);
-    let min_verts: Vec<Location> = saturations
-        .iter()
-        .filter_map(|(vert, sat)| (*sat == min_saturation).then_some(*vert))
-        .cloned()
-        .collect();
-    Ok(min_verts)
+        .filter(|(holder, _)| matches!(holder, Location::Variable(_)))
+        .map(|(holder, color)| (spill_cost(holder), holder, color))
+        .filter(|(cost, _, _)| *cost < vert_cost)
+        .min_by(|(cost1, vert1, _), (cost2, vert2, _)| {
+            cost1.total_cmp(cost2).then(vert1.cmp(vert2))
+        })
+        .map(|(_, holder, color)| (holder, color))
+}
+
+/// Smallest register color not used by a neighbour
+fn lowest_color(vert: &Location, interference_graph: &LocationGraph, coloring: &Coloring) -> Color {
+    let adjacent_colors = saturation(interference_graph, vert, coloring);
+    let mut color = 0;
+    while adjacent_colors.contains(&color) {
+        color += 1;
+    }
+    color
+}
+
+/// Gives spilled variables stack slots not used by interfering variables,
+/// the most expensive ones first
+fn color_spilled(
+    coloring: &mut Coloring,
+    interference_graph: &LocationGraph,
+    spilled: HashSet<Location>,
+    costs: &SpillCosts,
+    registers: &RegisterSet,
+) {
+    let mut spilled: Vec<Location> = spilled.into_iter().collect();
+    spilled.sort_by_key(|vert| (Reverse(costs.get(vert).copied()), vert.clone()));
+    for vert in spilled {
+        let adjacent_colors = saturation(interference_graph, &vert, coloring);
+        let mut next_color = registers.max_color() + 1;
+        while adjacent_colors.contains(&next_color) {
+            next_color += 1;
+        }
+        coloring.0.insert(vert, next_color);
+    }
 }
 
 fn get_move_related(
@@ -78,17 +289,16 @@ fn get_move_related(
     if !matches!(loc, Location::Variable(_)) {
         return None;
     };
-    let interfering = interference_graph.adjacent(loc);
-    let interfering_colors: Vec<Color> = interfering
-        .iter()
+    let interfering_colors: HashSet<Color> = interference_graph
+        .neighbours(loc)
         .filter_map(|vert| coloring.0.get(vert))
         .copied()
         .collect();
-    for move_related in move_graph.adjacent(loc) {
-        if interfering.contains(&move_related) {
+    for move_related in move_graph.neighbours(loc) {
+        if interference_graph.has_edge(loc, move_related) {
             continue;
         }
-        let related_color = if let Some(color) = coloring.0.get(&move_related) {
+        let related_color = if let Some(color) = coloring.0.get(move_related) {
             color
         } else {
             continue;
@@ -103,10 +313,11 @@ fn get_move_related(
 
 #[cfg(test)]
 mod color_graph_tests {
-    use super::{Coloring, color_graph};
-    use crate::graph::LocationGraph;
+    use super::color_graph;
+    use crate::{
+        colors::RegisterSet, graph::LocationGraph, program::Location, spill_costs::SpillCosts,
+    };
     use asm::Reg;
-    use std::collections::HashMap;
 
     #[test]
     fn color_example() {
@@ -129,20 +340,119 @@ mod color_graph_tests {
         move_graph.add_edge("y".into(), "x".into());
         move_graph.add_edge("z".into(), "x".into());
         move_graph.add_edge("x".into(), "v".into());
-        let result = color_graph(interference_graph, move_graph).unwrap();
-        let expected = Coloring(HashMap::from([
-            (Reg::Rax.into(), -1),
-            (Reg::Rsp.into(), -2),
-            (Reg::Rbp.into(), -3),
-            (Reg::R11.into(), -4),
-            (Reg::R15.into(), -5),
-            ("v".into(), 0),
-            ("x".into(), 0),
-            ("y".into(), 0),
-            ("t".into(), 0),
-            ("z".into(), 2),
-            ("w".into(), 1),
-        ]));
+        let registers = RegisterSet::default();
+        let result = color_graph(
+            interference_graph,
+            move_graph,
+            &SpillCosts::new(),
+            &registers,
+        )
+        .unwrap();
+        let mut expected = registers.empty_coloring();
+        expected.0.extend([
+            ("v".into(), 2),
+            ("x".into(), 2),
+            ("y".into(), 2),
+            ("t".into(), 2),
+            ("z".into(), 1),
+            ("w".into(), 0),
+        ]);
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn spill_cheapest() {
+        let vars: Vec<Location> = (0..12)
+            .map(|i| Location::Variable(format!("x{i}")))
+            .chain([Location::from("cold")])
+            .collect();
+        let mut interference_graph = LocationGraph::new();
+        for (ind, var) in vars.iter().enumerate() {
+            for other in vars[ind + 1..].iter() {
+                interference_graph.add_edge(var.clone(), other.clone());
+            }
+        }
+        let mut costs: SpillCosts = vars.iter().map(|var| (var.clone(), 100)).collect();
+        costs.insert("cold".into(), 1);
+        costs.insert("x3".into(), 10);
+        let registers = RegisterSet::new(11);
+        let result =
+            color_graph(interference_graph, LocationGraph::new(), &costs, &registers).unwrap();
+
+        for var in vars.iter() {
+            let spilled = *var == Location::from("cold") || *var == Location::from("x3");
+            assert_eq!(result.0[var] > registers.max_color(), spilled, "{var}");
+        }
+        assert_eq!(result.0[&Location::from("x3")], registers.max_color() + 1);
+        assert_eq!(result.0[&Location::from("cold")], registers.max_color() + 2);
+    }
+
+    #[test]
+    fn spill_share_slot() {
+        let mut interference_graph = LocationGraph::new();
+        interference_graph.add_edge("a".into(), "c".into());
+        interference_graph.add_vert("b".into());
+        let registers = RegisterSet::new(0);
+        let result = color_graph(
+            interference_graph,
+            LocationGraph::new(),
+            &SpillCosts::new(),
+            &registers,
+        )
+        .unwrap();
+        let slot = registers.max_color() + 1;
+        assert_eq!(result.0[&Location::from("a")], slot);
+        assert_eq!(result.0[&Location::from("b")], slot);
+        assert_eq!(result.0[&Location::from("c")], slot + 1);
+    }
+
+    #[test]
+    fn spill_clique_excess() {
+        // every outer variable interferes with all but four clique variables,
+        // so spilling three clique variables is enough for eleven registers
+        let clique: Vec<Location> = (0..14)
+            .map(|i| Location::Variable(format!("c{i}")))
+            .collect();
+        let mut interference_graph = LocationGraph::new();
+        let mut costs = SpillCosts::new();
+        for (ind, var) in clique.iter().enumerate() {
+            for other in clique[ind + 1..].iter() {
+                interference_graph.add_edge(var.clone(), other.clone());
+            }
+            costs.insert(var.clone(), 100 + ind);
+        }
+        for i in 0..30 {
+            let outer = Location::Variable(format!("o{i}"));
+            for (ind, var) in clique.iter().enumerate() {
+                if (ind + 14 - i % 14) % 14 >= 4 {
+                    interference_graph.add_edge(outer.clone(), var.clone());
+                }
+            }
+            costs.insert(outer, 1 + i % 5);
+        }
+        let registers = RegisterSet::new(11);
+        let result =
+            color_graph(interference_graph, LocationGraph::new(), &costs, &registers).unwrap();
+        let spilled = result
+            .0
+            .values()
+            .filter(|color| **color > registers.max_color())
+            .count();
+        assert!(spilled <= clique.len() - 11, "{spilled} variables spilled");
+    }
+
+    #[test]
+    fn color_ignores_non_interfering() {
+        let mut interference_graph = LocationGraph::new();
+        interference_graph.add_edge("a".into(), "b".into());
+        interference_graph.add_vert("c".into());
+        let result = color_graph(
+            interference_graph,
+            LocationGraph::new(),
+            &SpillCosts::new(),
+            &RegisterSet::default(),
+        )
+        .unwrap();
+        assert_eq!(result.0[&"c".into()], 0);
+    }
 }
@@ -6,68 +6,113 @@ use std::{
 };
 
 pub type Color = i64;
-#[derive(Debug, PartialEq)]
+
+/// Registers that can hold variables, caller saved ones first
+pub const ALLOCATABLE_REGISTERS: [Reg; 13] = [
+    Reg::Rcx,
+    Reg::Rdx,
+    Reg::Rsi,
+    Reg::Rdi,
+    Reg::R8,
+    Reg::R9,
+    Reg::R10,
+    Reg::R11,
+    Reg::Rax,
+    Reg::Rbx,
+    Reg::R12,
+    Reg::R13,
+    Reg::R14,
+];
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Coloring(pub HashMap<Location, Color>);
 
-pub fn color_to_arg(color: Color) -> Arg {
-    match color {
-        0 => Reg::Rcx.into(),
-        1 => Reg::Rdx.into(),
-        2 => Reg::Rsi.into(),
-        3 => Reg::Rdi.into(),
-        4 => Reg::R8.into(),
-        5 => Reg::R9.into(),
-        6 => Reg::R10.into(),
-        7 => Reg::Rbx.into(),
-        8 => Reg::R12.into(),
-        9 => Reg::R13.into(),
-        10 => Reg::R14.into(),
-        -1 => Reg::Rax.into(),
-        -2 => Reg::Rsp.into(),
-        -3 => Reg::Rbp.into(),
-        -4 => Reg::R11.into(),
-        -5 => Reg::R15.into(),
-        i if i > 10 => Arg::Deref(Reg::Rbp, -8 * (i - 10)),
-        _ => panic!("Cannot assign color {color}"),
+/// Registers variables are allocated to, color `i` being the `i`th one
+/// Colors above `max_color` are stack slots,
+/// all other registers are precolored with negative colors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterSet {
+    pub registers: Vec<Reg>,
+}
+
+impl RegisterSet {
+    /// The first `count` allocatable registers
+    pub fn new(count: usize) -> RegisterSet {
+        RegisterSet {
+            registers: ALLOCATABLE_REGISTERS.into_iter().take(count).collect(),
+        }
+    }
+
+    /// Colors above this one are stack slots
+    pub fn max_color(&self) -> Color {
+        self.registers.len() as Color - 1
+    }
+
+    fn reserved(&self) -> impl Iterator<Item = Reg> {
+        Reg::caller_saved()
+            .into_iter()
+            .chain(Reg::callee_saved())
+            .filter(|reg| !self.registers.contains(reg))
+    }
+
+    pub fn color_to_arg(&self, color: Color) -> Arg {
+        let max_color = self.max_color();
+        match color {
+            i if i > max_color => Arg::Deref(Reg::Rbp, -8 * (i - max_color)),
+            i if i >= 0 => self.registers[i as usize].clone().into(),
+            i => match self.reserved().nth((-i - 1) as usize) {
+                Some(reg) => reg.into(),
+                None => panic!("Cannot assign color {color}"),
+            },
+        }
+    }
+
+    /// Coloring of all registers
+    pub fn empty_coloring(&self) -> Coloring {
+        let allocatable = self.registers.iter().cloned().zip(0..);
+        let reserved = self.reserved().zip(1..).map(|(reg, color)| (reg, -color));
+        Coloring(
+            allocatable
+                .chain(reserved)
+                .map(|(reg, color)| (reg.into(), color))
+                .collect(),
+        )
+    }
+
+    pub fn coloring_to_assignment(&self, coloring: Coloring) -> HashMap<String, Arg> {
+        let mut assignments = HashMap::new();
+        for (loc, color) in coloring.0 {
+            let arg = self.color_to_arg(color);
+            match (loc, &arg) {
+                (Location::Variable(v), _) => {
+                    assignments.insert(v, arg);
+                }
+                (Location::Register(reg1), Arg::Register(reg2)) if reg1 == *reg2 => (),
+                (Location::Stack(offset1), Arg::Deref(Reg::Rbp, offset2))
+                    if offset1 == *offset2 => {}
+                (loc, _) => panic!("Locations {loc} and {arg} do not match"),
+            }
+        }
+        assignments
     }
 }
 
-pub fn empty_coloring() -> Coloring {
-    Coloring(HashMap::from([
-        (Reg::Rax.into(), -1),
-        (Reg::Rsp.into(), -2),
-        (Reg::Rbp.into(), -3),
-        (Reg::R11.into(), -4),
-        (Reg::R15.into(), -5),
-    ]))
+impl Default for RegisterSet {
+    fn default() -> RegisterSet {
+        RegisterSet::new(ALLOCATABLE_REGISTERS.len())
+    }
 }
 
 pub fn saturation(graph: &LocationGraph, vert: &Location, coloring: &Coloring) -> HashSet<Color> {
     let mut colors = HashSet::new();
-    for v in graph.adjacent(vert) {
-        if let Some(c) = coloring.0.get(&v) {
+    for v in graph.neighbours(vert) {
+        if let Some(c) = coloring.0.get(v) {
             colors.insert(*c);
         }
     }
     colors
 }
 
-pub fn coloring_to_assignment(coloring: Coloring) -> HashMap<String, Arg> {
-    let mut assignments = HashMap::new();
-    for (loc, color) in coloring.0 {
-        let arg = color_to_arg(color);
-        match (loc, &arg) {
-            (Location::Variable(v), _) => {
-                assignments.insert(v, arg);
-            }
-            (Location::Register(reg1), Arg::Register(reg2)) if reg1 == *reg2 => (),
-            (Location::Stack(offset1), Arg::Deref(Reg::Rbp, offset2)) if offset1 == *offset2 => (),
-            (loc, _) => panic!("Locations {loc} and {arg} do not match"),
-        }
-    }
-    assignments
-}
-
 impl fmt::Display for Coloring {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for (loc, color) in self.0.iter() {
@@ -76,3 +121,25 @@ impl fmt::Display for Coloring {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod colors_tests {
+    use super::RegisterSet;
+    use crate::program::Location;
+    use asm::{Arg, Reg};
+
+    #[test]
+    fn colors_three_registers() {
+        let registers = RegisterSet::new(3);
+        let coloring = registers.empty_coloring();
+        assert_eq!(coloring.0.len(), 16);
+        for (loc, color) in coloring.0 {
+            let Location::Register(reg) = loc else {
+                panic!("{loc} is not a register")
+            };
+            assert_eq!(registers.color_to_arg(color), reg.into());
+        }
+        assert_eq!(registers.color_to_arg(2), Reg::Rsi.into());
+        assert_eq!(registers.color_to_arg(3), Arg::Deref(Reg::Rbp, -8));
+    }
+}
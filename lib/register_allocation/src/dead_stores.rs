@@ -0,0 +1,144 @@
+use crate::{
+    errors::Error,
+    uncover_live::{stored_variable, uncover_faint},
+};
+use asm::VarProgram;
+
+/// Removes instructions writing to a variable that is not live afterwards
+/// Liveness skips these stores, so a chain of stores only feeding each other
+/// is found dead in a single analysis and removed in one sweep over each block
+pub fn eliminate_dead_stores(prog: VarProgram) -> Result<VarProgram, Error> {
+    eliminate_counting(prog).map(|(prog, _)| prog)
+}
+
+/// Also returns how many times liveness analyzed a block
+fn eliminate_counting(mut prog: VarProgram) -> Result<(VarProgram, usize), Error> {
+    let (live, visits) = uncover_faint(prog.clone())?;
+    for (block, live_block) in prog.blocks.iter_mut().zip(live.blocks) {
+        block.instrs = live_block
+            .instrs
+            .into_iter()
+            .filter(|instr| {
+                !stored_variable(instr).is_some_and(|var| {
+                    !live
+                        .locations
+                        .get(&var)
+                        .is_some_and(|ind| instr.live_after.contains(ind))
+                })
+            })
+            .map(|instr| instr.instr)
+            .collect();
+    }
+    Ok((prog, visits))
+}
+
+#[cfg(test)]
+mod dead_stores_tests {
+    use super::{eliminate_counting, eliminate_dead_stores};
+    use asm::{Cc, Instruction, Reg, VarProgram};
+    use definitions::READ_INT_CALL;
+
+    #[test]
+    fn eliminate_unused_chain() {
+        let mut prog = VarProgram::new();
+        prog.add_block(
+            "start",
+            vec![
+                Instruction::mov(1, "x"),
+                Instruction::mov("x", "y"),
+                Instruction::add(2, "y"),
+                Instruction::mov(3, "z"),
+                Instruction::mov("z", Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        );
+        let result = eliminate_dead_stores(prog).unwrap();
+        let mut expected = VarProgram::new();
+        expected.add_block(
+            "start",
+            vec![
+                Instruction::mov(3, "z"),
+                Instruction::mov("z", Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        );
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn keep_unused_read() {
+        let mut prog = VarProgram::new();
+        prog.add_block(
+            "start",
+            vec![
+                Instruction::call(READ_INT_CALL, 0),
+                Instruction::mov(Reg::Rax, "x"),
+                Instruction::mov(0, Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        );
+        let result = eliminate_dead_stores(prog).unwrap();
+        let mut expected = VarProgram::new();
+        expected.add_block(
+            "start",
+            vec![
+                Instruction::call(READ_INT_CALL, 0),
+                Instruction::mov(0, Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        );
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn eliminate_loop_chain() {
+        // the chain feeds itself around the loop, so each of its variables is read
+        // by a later store, but only by stores that are dead
+        const LINKS: usize = 50;
+        let var = |ind: usize| format!("x{ind}");
+        let mut prog = VarProgram::new();
+        prog.add_block(
+            "start",
+            vec![
+                Instruction::mov(0, "i"),
+                Instruction::mov(0, var(LINKS - 1).as_str()),
+                Instruction::jmp("loop"),
+            ],
+        );
+        let mut body = vec![Instruction::mov(var(LINKS - 1).as_str(), var(0).as_str())];
+        for ind in 1..LINKS {
+            body.push(Instruction::mov(var(ind - 1).as_str(), var(ind).as_str()));
+        }
+        let tail = vec![
+            Instruction::add(1, "i"),
+            Instruction::CmpQ {
+                left: "i".into(),
+                right: 10.into(),
+            },
+            Instruction::JumpCC {
+                cc: Cc::L,
+                label: "loop".to_owned(),
+            },
+            Instruction::jmp("end"),
+        ];
+        body.extend(tail.clone());
+        prog.add_block("loop", body);
+        let end = vec![
+            Instruction::mov("i", Reg::Rax),
+            Instruction::jmp("conclusion"),
+        ];
+        prog.add_block("end", end.clone());
+
+        let (result, visits) = eliminate_counting(prog).unwrap();
+        let mut expected = VarProgram::new();
+        expected.add_block(
+            "start",
+            vec![Instruction::mov(0, "i"), Instruction::jmp("loop")],
+        );
+        expected.add_block("loop", tail);
+        expected.add_block("end", end);
+        assert_eq!(result, expected);
+        // one liveness run over the three blocks and the conclusion, however long the chain
+        assert!(visits <= 3 * 4, "{visits} visits");
+    }
+}
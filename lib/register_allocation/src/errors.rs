@@ -1,3 +1,4 @@
+use asm::Reg;
 use std::fmt;
 
 #[derive(Debug)]
@@ -7,6 +8,25 @@ pub enum Error {
     NextVertex,
     FlowCycle,
     MissingBlock(String),
+    SharedHome {
+        first: String,
+        second: String,
+        instr: String,
+    },
+    ClobberedHome {
+        var: String,
+        home: String,
+        instr: String,
+    },
+    UnsavedCallee {
+        reg: Reg,
+        instr: String,
+    },
+    SavedFrameRegister(Reg),
+    SlotInCalleeArea {
+        slot: String,
+        instr: String,
+    },
 }
 
 impl fmt::Display for Error {
@@ -19,6 +39,34 @@ impl fmt::Display for Error {
             Error::NextVertex => write!(f, "Could not get next vertex to color"),
             Error::FlowCycle => write!(f, "Flow Graph contains cycle"),
             Error::MissingBlock(label) => write!(f, "Could not find block with label {label}"),
+            Error::SharedHome {
+                first,
+                second,
+                instr,
+            } => write!(
+                f,
+                "Variables {first} and {second} are live at the same time but share a home at {instr}"
+            ),
+            Error::ClobberedHome { var, home, instr } => {
+                write!(
+                    f,
+                    "Home {home} of live variable {var} is overwritten by {instr}"
+                )
+            }
+            Error::UnsavedCallee { reg, instr } => {
+                write!(
+                    f,
+                    "Callee saved register {reg} is used by {instr} but never saved"
+                )
+            }
+            Error::SavedFrameRegister(reg) => write!(
+                f,
+                "Register {reg} is restored by the conclusion and cannot be saved with the callee saved registers"
+            ),
+            Error::SlotInCalleeArea { slot, instr } => write!(
+                f,
+                "Stack slot {slot} used by {instr} overlaps the saved callee saved registers"
+            ),
         }
     }
 }
@@ -1,53 +1,66 @@
-use crate::errors::Error;
+use crate::{errors::Error, program::LiveProg};
 use asm::{Block, Instruction, VarArg, VarProgram};
-use std::{collections::HashSet, fmt};
+use definitions::dataflow::ControlFlow;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+};
 
+/// Labels with the labels they jump to and are jumped to from
 #[derive(Debug)]
 pub struct FlowGraph {
-    verts: HashSet<String>,
-    edges: HashSet<(String, String)>,
+    successors: HashMap<String, HashSet<String>>,
+    predecessors: HashMap<String, HashSet<String>>,
 }
 
 impl FlowGraph {
     pub fn new() -> FlowGraph {
         FlowGraph {
-            verts: HashSet::new(),
-            edges: HashSet::new(),
+            successors: HashMap::new(),
+            predecessors: HashMap::new(),
         }
     }
 
     pub fn add_vert(&mut self, vert: &str) {
-        self.verts.insert(vert.to_owned());
+        if !self.successors.contains_key(vert) {
+            self.successors.insert(vert.to_owned(), HashSet::new());
+            self.predecessors.insert(vert.to_owned(), HashSet::new());
+        }
     }
 
     pub fn add_edge(&mut self, from: &str, to: &str) {
-        if !self.verts.contains(from) {
-            self.add_vert(from);
+        self.add_vert(from);
+        self.add_vert(to);
+        if let Some(succs) = self.successors.get_mut(from) {
+            succs.insert(to.to_owned());
         }
-        if !self.verts.contains(to) {
-            self.add_vert(to);
+        if let Some(preds) = self.predecessors.get_mut(to) {
+            preds.insert(from.to_owned());
         }
-        self.edges.insert((from.to_owned(), to.to_owned()));
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.successors
+            .iter()
+            .flat_map(|(from, succs)| succs.iter().map(move |to| (from, to)))
     }
 
     pub fn incoming(&self, vert: &str) -> Vec<(String, String)> {
-        let mut inc = vec![];
-        for edge in self.edges.iter() {
-            if edge.1 == vert {
-                inc.push(edge.clone());
-            }
-        }
-        inc
+        self.predecessors
+            .get(vert)
+            .into_iter()
+            .flatten()
+            .map(|from| (from.clone(), vert.to_owned()))
+            .collect()
     }
 
     pub fn outgoing(&self, vert: &str) -> Vec<(String, String)> {
-        let mut out = vec![];
-        for edge in self.edges.iter() {
-            if edge.0 == vert {
-                out.push(edge.clone());
-            }
-        }
-        out
+        self.successors
+            .get(vert)
+            .into_iter()
+            .flatten()
+            .map(|to| (vert.to_owned(), to.clone()))
+            .collect()
     }
 
     pub fn build(&mut self, prog: &VarProgram) {
@@ -73,33 +86,162 @@ impl FlowGraph {
         }
     }
 
-    pub fn topo_sort(mut self) -> Result<Vec<String>, Error> {
+    /// Immediate dominator of each label reachable from `entry` except `entry` itself,
+    /// using the iterative algorithm of Cooper, Harvey and Kennedy
+    pub fn immediate_dominators(&self, entry: &str) -> HashMap<String, String> {
+        let order = self.reverse_postorder(entry);
+        let index: HashMap<&String, usize> = order.iter().zip(0..).collect();
+        let mut idom: Vec<Option<usize>> = vec![None; order.len()];
+        if !order.is_empty() {
+            idom[0] = Some(0);
+        }
+        let mut changing = true;
+        while changing {
+            changing = false;
+            for (ind, vert) in order.iter().enumerate().skip(1) {
+                let mut new_idom = None;
+                for pred in self.predecessors[vert].iter() {
+                    let Some(pred) = index.get(pred).copied() else {
+                        continue;
+                    };
+                    if idom[pred].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        None => pred,
+                        Some(other) => intersect(&idom, pred, other),
+                    });
+                }
+                if new_idom.is_some() && idom[ind] != new_idom {
+                    idom[ind] = new_idom;
+                    changing = true;
+                }
+            }
+        }
+        idom.iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(ind, dom)| Some((order[ind].clone(), order[(*dom)?].clone())))
+            .collect()
+    }
+
+    /// Number of natural loops containing each label reachable from `entry`
+    /// Loops sharing a header count once
+    pub fn loop_depths(&self, entry: &str) -> HashMap<String, usize> {
+        let reachable: HashSet<String> = self.reverse_postorder(entry).into_iter().collect();
+        let idoms = self.immediate_dominators(entry);
+        let mut loops: HashMap<&String, HashSet<&String>> = HashMap::new();
+        for (from, to) in self.edges() {
+            if !reachable.contains(from) || !dominates(&idoms, to, from) {
+                continue;
+            }
+            let body = loops.entry(to).or_insert_with(|| HashSet::from([to]));
+            let mut todo = vec![from];
+            while let Some(vert) = todo.pop() {
+                if reachable.contains(vert) && body.insert(vert) {
+                    todo.extend(self.predecessors[vert].iter());
+                }
+            }
+        }
+
+        let mut depths: HashMap<String, usize> =
+            reachable.iter().map(|vert| (vert.clone(), 0)).collect();
+        for vert in loops.into_values().flatten() {
+            *depths.entry(vert.clone()).or_default() += 1;
+        }
+        depths
+    }
+
+    pub fn topo_sort(self) -> Result<Vec<String>, Error> {
         let mut sorted = vec![];
-        let mut start = self
-            .verts
+        let mut in_degrees: HashMap<&String, usize> = self
+            .predecessors
             .iter()
-            .filter(|vert| self.incoming(vert).is_empty())
-            .cloned()
-            .collect::<Vec<_>>();
-        while !start.is_empty() {
-            let next = start.remove(0);
-            let outgoing = self.outgoing(&next);
-            sorted.push(next);
-            for edge in outgoing {
-                self.edges.remove(&edge);
-                if self.incoming(&edge.1).is_empty() {
-                    start.push(edge.1);
+            .map(|(vert, preds)| (vert, preds.len()))
+            .collect();
+        let mut start: VecDeque<&String> = in_degrees
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(vert, _)| *vert)
+            .collect();
+        while let Some(next) = start.pop_front() {
+            sorted.push(next.clone());
+            for succ in self.successors[next].iter() {
+                let Some(degree) = in_degrees.get_mut(succ) else {
+                    continue;
+                };
+                *degree -= 1;
+                if *degree == 0 {
+                    start.push_back(succ);
                 }
             }
         }
 
-        if !self.edges.is_empty() {
+        if sorted.len() != self.successors.len() {
             return Err(Error::FlowCycle);
         }
         Ok(sorted)
     }
 }
 
+/// Whether every path from the entry to `vert` goes through `dom`
+fn dominates<'a>(idoms: &'a HashMap<String, String>, dom: &str, mut vert: &'a str) -> bool {
+    loop {
+        if vert == dom {
+            return true;
+        }
+        match idoms.get(vert) {
+            Some(parent) => vert = parent,
+            None => return false,
+        }
+    }
+}
+
+fn intersect(idom: &[Option<usize>], mut fst: usize, mut snd: usize) -> usize {
+    while fst != snd {
+        while fst > snd {
+            fst = idom[fst].unwrap_or(0);
+        }
+        while snd > fst {
+            snd = idom[snd].unwrap_or(0);
+        }
+    }
+    fst
+}
+
+impl ControlFlow for FlowGraph {
+    fn labels(&self) -> Vec<String> {
+        let mut labels: Vec<String> = self.successors.keys().cloned().collect();
+        labels.sort();
+        labels
+    }
+
+    fn successors(&self, label: &str) -> Vec<String> {
+        let mut succs: Vec<String> = self
+            .successors
+            .get(label)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        succs.sort();
+        succs
+    }
+}
+
+impl From<&LiveProg> for FlowGraph {
+    fn from(prog: &LiveProg) -> FlowGraph {
+        let mut graph = FlowGraph::new();
+        for block in prog.blocks.iter() {
+            graph.add_vert(&block.label);
+            for instr in block.instrs.iter() {
+                graph.build_instr(&instr.instr, &block.label);
+            }
+        }
+        graph
+    }
+}
+
 impl Default for FlowGraph {
     fn default() -> FlowGraph {
         FlowGraph::new()
@@ -109,12 +251,12 @@ impl Default for FlowGraph {
 impl fmt::Display for FlowGraph {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         writeln!(f, "Labels:")?;
-        for vert in self.verts.iter() {
+        for vert in self.successors.keys() {
             writeln!(f, "\t{vert}")?;
         }
         writeln!(f)?;
         writeln!(f, "Flow Edges")?;
-        for (from, to) in self.edges.iter() {
+        for (from, to) in self.edges() {
             writeln!(f, "\t{from} --> {to}")?;
         }
         Ok(())
@@ -139,4 +281,21 @@ mod flow_graph_tests {
         graph.add_edge("8", "9");
         graph.topo_sort().unwrap();
     }
+
+    #[test]
+    fn nested_loop_depths() {
+        let mut graph = FlowGraph::new();
+        graph.add_edge("start", "outer");
+        graph.add_edge("outer", "inner");
+        graph.add_edge("inner", "inner");
+        graph.add_edge("inner", "latch");
+        graph.add_edge("latch", "outer");
+        graph.add_edge("outer", "conclusion");
+        let depths = graph.loop_depths("start");
+        assert_eq!(depths["start"], 0);
+        assert_eq!(depths["outer"], 1);
+        assert_eq!(depths["latch"], 1);
+        assert_eq!(depths["inner"], 2);
+        assert_eq!(depths["conclusion"], 0);
+    }
 }
@@ -0,0 +1,158 @@
+use definitions::dataflow::Lattice;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Set of indices, one bit each
+/// Only words with a bit set are stored, sorted by their position, so a set stays
+/// small however far apart its indices are
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitSet {
+    /// Position of each word with the bits in it
+    words: Vec<(usize, u64)>,
+}
+
+impl BitSet {
+    pub fn new() -> BitSet {
+        BitSet { words: vec![] }
+    }
+
+    fn find(&self, word: usize) -> Result<usize, usize> {
+        self.words.binary_search_by_key(&word, |(pos, _)| *pos)
+    }
+
+    /// Returns whether `ind` was not in the set yet
+    pub fn insert(&mut self, ind: usize) -> bool {
+        let (word, bit) = (ind / WORD_BITS, 1 << (ind % WORD_BITS));
+        match self.find(word) {
+            Ok(pos) => {
+                let new = self.words[pos].1 & bit == 0;
+                self.words[pos].1 |= bit;
+                new
+            }
+            Err(pos) => {
+                self.words.insert(pos, (word, bit));
+                true
+            }
+        }
+    }
+
+    pub fn remove(&mut self, ind: usize) {
+        if let Ok(pos) = self.find(ind / WORD_BITS) {
+            self.words[pos].1 &= !(1 << (ind % WORD_BITS));
+            if self.words[pos].1 == 0 {
+                self.words.remove(pos);
+            }
+        }
+    }
+
+    pub fn contains(&self, ind: usize) -> bool {
+        self.find(ind / WORD_BITS)
+            .is_ok_and(|pos| self.words[pos].1 & (1 << (ind % WORD_BITS)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|(_, word)| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn union_with(&mut self, other: &BitSet) {
+        let mut words = Vec::with_capacity(self.words.len() + other.words.len());
+        let mut others = other.words.iter().copied().peekable();
+        for (pos, mut word) in self.words.iter().copied() {
+            while let Some(other) = others.next_if(|(other, _)| *other < pos) {
+                words.push(other);
+            }
+            if let Some((_, other)) = others.next_if(|(other, _)| *other == pos) {
+                word |= other;
+            }
+            words.push((pos, word));
+        }
+        words.extend(others);
+        self.words = words;
+    }
+
+    pub fn difference_with(&mut self, other: &BitSet) {
+        self.words.retain_mut(|(pos, word)| {
+            if let Ok(ind) = other.find(*pos) {
+                *word &= !other.words[ind].1;
+            }
+            *word != 0
+        });
+    }
+
+    /// Indices in the set, in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().flat_map(|(pos, word)| {
+            let mut word = *word;
+            let base = pos * WORD_BITS;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(base + bit)
+            })
+        })
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> BitSet {
+        let mut set = BitSet::new();
+        for ind in iter {
+            set.insert(ind);
+        }
+        set
+    }
+}
+
+impl Lattice for BitSet {
+    fn bottom() -> BitSet {
+        BitSet::new()
+    }
+
+    fn join(&mut self, other: &BitSet) {
+        self.union_with(other)
+    }
+}
+
+#[cfg(test)]
+mod bitset_tests {
+    use super::BitSet;
+
+    #[test]
+    fn bitset_ops() {
+        let mut set: BitSet = [1, 64, 130].into_iter().collect();
+        assert!(set.contains(64));
+        assert!(!set.contains(65));
+        assert!(!set.insert(130));
+        set.union_with(&[2, 64].into_iter().collect());
+        set.difference_with(&[1, 500].into_iter().collect());
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![2, 64, 130]);
+        assert_eq!(set.len(), 3);
+        set.remove(130);
+        assert_eq!(set, [2, 64].into_iter().collect());
+    }
+
+    #[test]
+    fn bitset_offset() {
+        let mut set: BitSet = [700, 900].into_iter().collect();
+        set.insert(3);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 700, 900]);
+        let mut other: BitSet = [5000].into_iter().collect();
+        other.union_with(&set);
+        assert_eq!(other.len(), 4);
+        other.difference_with(&[3, 900, 5000].into_iter().collect());
+        assert_eq!(other, [700].into_iter().collect());
+        assert!(!other.contains(5000));
+        other.remove(700);
+        assert_eq!(other, BitSet::new());
+    }
+}
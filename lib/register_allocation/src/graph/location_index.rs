@@ -0,0 +1,51 @@
+use super::BitSet;
+use crate::program::Location;
+use std::collections::HashMap;
+
+/// Numbers locations densely in the order they are first seen
+#[derive(Debug, Clone, Default)]
+pub struct LocationIndex {
+    locations: Vec<Location>,
+    indices: HashMap<Location, usize>,
+}
+
+impl LocationIndex {
+    pub fn new() -> LocationIndex {
+        LocationIndex {
+            locations: vec![],
+            indices: HashMap::new(),
+        }
+    }
+
+    /// Index of `loc`, adding it if it has none yet
+    pub fn insert(&mut self, loc: &Location) -> usize {
+        if let Some(ind) = self.indices.get(loc) {
+            return *ind;
+        }
+        let ind = self.locations.len();
+        self.locations.push(loc.clone());
+        self.indices.insert(loc.clone(), ind);
+        ind
+    }
+
+    /// Indices of `locs`, adding the ones that have none yet
+    pub fn insert_all<'a>(&mut self, locs: impl IntoIterator<Item = &'a Location>) -> BitSet {
+        locs.into_iter().map(|loc| self.insert(loc)).collect()
+    }
+
+    pub fn get(&self, loc: &Location) -> Option<usize> {
+        self.indices.get(loc).copied()
+    }
+
+    pub fn location(&self, ind: usize) -> &Location {
+        &self.locations[ind]
+    }
+
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+}
@@ -1,55 +1,174 @@
 use crate::program::Location;
 use std::{collections::HashSet, fmt};
 
+mod bitset;
 mod edge;
+mod location_index;
+pub use bitset::BitSet;
 use edge::Edge;
+pub use location_index::LocationIndex;
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+/// Undirected graph over locations, stored as adjacency lists of dense indices
+#[derive(Debug, Clone)]
 pub struct LocationGraph {
-    pub verts: HashSet<Location>,
-    edges: HashSet<Edge>,
+    index: LocationIndex,
+    /// Whether each indexed location is still a vertex, merged ones are not
+    present: Vec<bool>,
+    adjacency: Vec<HashSet<usize>>,
 }
 
 impl LocationGraph {
     pub fn new() -> LocationGraph {
         LocationGraph {
-            verts: HashSet::new(),
-            edges: HashSet::new(),
+            index: LocationIndex::new(),
+            present: vec![],
+            adjacency: vec![],
         }
     }
 
+    /// Graph without vertices that numbers locations like `index`
+    pub fn with_index(index: LocationIndex) -> LocationGraph {
+        let len = index.len();
+        LocationGraph {
+            index,
+            present: vec![false; len],
+            adjacency: vec![HashSet::new(); len],
+        }
+    }
+
+    /// Index of `v`, without making it a vertex
+    pub(crate) fn index(&mut self, v: &Location) -> usize {
+        let ind = self.index.insert(v);
+        if ind >= self.present.len() {
+            self.present.resize(ind + 1, false);
+            self.adjacency.resize(ind + 1, HashSet::new());
+        }
+        ind
+    }
+
+    fn insert(&mut self, v: &Location) -> usize {
+        let ind = self.index(v);
+        self.present[ind] = true;
+        ind
+    }
+
+    pub(crate) fn is_variable(&self, ind: usize) -> bool {
+        matches!(self.index.location(ind), Location::Variable(_))
+    }
+
+    /// Makes the location with index `ind` a vertex
+    pub(crate) fn add_vert_at(&mut self, ind: usize) {
+        self.present[ind] = true;
+    }
+
+    /// Adds an edge between the locations with indices `left` and `right`
+    pub(crate) fn add_edge_at(&mut self, left: usize, right: usize) {
+        self.present[left] = true;
+        self.present[right] = true;
+        self.adjacency[left].insert(right);
+        self.adjacency[right].insert(left);
+    }
+
+    fn get(&self, v: &Location) -> Option<usize> {
+        self.index.get(v).filter(|ind| self.present[*ind])
+    }
+
     pub fn add_vert(&mut self, v: Location) {
-        self.verts.insert(v);
+        self.insert(&v);
     }
 
     pub fn add_edge(&mut self, left: Location, right: Location) {
-        self.add_vert(left.clone());
-        self.add_vert(right.clone());
-        self.edges.insert(Edge::new(left, right));
+        let left = self.index(&left);
+        let right = self.index(&right);
+        self.add_edge_at(left, right);
+    }
+
+    pub fn contains(&self, vert: &Location) -> bool {
+        self.get(vert).is_some()
+    }
+
+    pub fn verts(&self) -> impl Iterator<Item = &Location> {
+        (0..self.present.len())
+            .filter(|ind| self.present[*ind])
+            .map(|ind| self.index.location(ind))
+    }
+
+    pub fn neighbours(&self, vert: &Location) -> impl Iterator<Item = &Location> {
+        self.get(vert)
+            .into_iter()
+            .flat_map(|ind| self.adjacency[ind].iter())
+            .map(|adj| self.index.location(*adj))
+    }
+
+    pub fn degree(&self, vert: &Location) -> usize {
+        self.get(vert).map_or(0, |ind| self.adjacency[ind].len())
     }
 
     pub fn adjacent(&self, vert: &Location) -> HashSet<Location> {
-        let mut adj = HashSet::new();
-        for edg in self.edges.iter() {
-            if edg.left == *vert {
-                adj.insert(edg.right.clone());
-            }
-            if edg.right == *vert {
-                adj.insert(edg.left.clone());
+        self.neighbours(vert).cloned().collect()
+    }
+
+    pub fn has_edge(&self, left: &Location, right: &Location) -> bool {
+        match (self.get(left), self.get(right)) {
+            (Some(left), Some(right)) => self.adjacency[left].contains(&right),
+            _ => false,
+        }
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (&Location, &Location)> {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(move |(left, adj)| {
+                adj.iter()
+                    .filter(move |right| left <= **right)
+                    .map(move |right| (self.index.location(left), self.index.location(*right)))
+            })
+    }
+
+    /// Replaces `from` by `into`, moving all edges of `from` over
+    pub fn merge(&mut self, from: &Location, into: &Location) {
+        let Some(from) = self.get(from) else {
+            return;
+        };
+        let into = self.insert(into);
+        let adjacent = std::mem::take(&mut self.adjacency[from]);
+        self.present[from] = false;
+        for vert in adjacent {
+            self.adjacency[vert].remove(&from);
+            if vert != into && vert != from {
+                self.adjacency[vert].insert(into);
+                self.adjacency[into].insert(vert);
             }
         }
-        adj
     }
 }
 
+impl PartialEq for LocationGraph {
+    fn eq(&self, other: &LocationGraph) -> bool {
+        let verts: HashSet<&Location> = self.verts().collect();
+        let edges: HashSet<Edge> = self
+            .edges()
+            .map(|(left, right)| Edge::new(left.clone(), right.clone()))
+            .collect();
+        let other_edges: HashSet<Edge> = other
+            .edges()
+            .map(|(left, right)| Edge::new(left.clone(), right.clone()))
+            .collect();
+        verts == other.verts().collect() && edges == other_edges
+    }
+}
+
+impl Eq for LocationGraph {}
+
 impl fmt::Display for LocationGraph {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for vert in self.verts.iter() {
+        for vert in self.verts() {
             writeln!(f, "{vert}")?;
         }
         writeln!(f,)?;
-        for edg in self.edges.iter() {
-            writeln!(f, "{edg}")?;
+        for (left, right) in self.edges() {
+            writeln!(f, "{}", Edge::new(left.clone(), right.clone()))?;
         }
         Ok(())
     }
@@ -60,3 +179,25 @@ impl Default for LocationGraph {
         LocationGraph::new()
     }
 }
+
+#[cfg(test)]
+mod graph_tests {
+    use super::LocationGraph;
+    use std::collections::HashSet;
+
+    #[test]
+    fn merge_moves_edges() {
+        let mut graph = LocationGraph::new();
+        graph.add_edge("a".into(), "b".into());
+        graph.add_edge("b".into(), "c".into());
+        graph.add_edge("a".into(), "d".into());
+        graph.merge(&"b".into(), &"d".into());
+        assert!(!graph.contains(&"b".into()));
+        assert_eq!(
+            graph.adjacent(&"d".into()),
+            HashSet::from(["a".into(), "c".into()])
+        );
+        assert_eq!(graph.adjacent(&"a".into()), HashSet::from(["d".into()]));
+        assert_eq!(graph.edges().count(), 2);
+    }
+}
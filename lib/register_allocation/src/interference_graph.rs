@@ -4,10 +4,11 @@ use crate::{
     uncover_live::written_locations,
 };
 use asm::Instruction;
-use std::collections::HashSet;
 
+/// Graph with an edge between every location written by an instruction
+/// and the other locations live after it, numbering locations like `prog`
 pub fn build_interference_graph(prog: &LiveProg) -> LocationGraph {
-    let mut graph = LocationGraph::new();
+    let mut graph = LocationGraph::with_index(prog.locations.clone());
     for block in prog.blocks.iter() {
         build_block(block, &mut graph);
     }
@@ -16,137 +17,116 @@ pub fn build_interference_graph(prog: &LiveProg) -> LocationGraph {
 
 fn build_block(block: &LiveBlock, graph: &mut LocationGraph) {
     for instr in block.instrs.iter() {
-        if let Instruction::MovQ { ref src, ref dest } = instr.instr {
-            mov_edges(
-                Location::arg_loc(src.clone()),
-                Location::arg_loc(dest.clone()),
-                &instr.live_after,
-                graph,
-            );
-            continue;
-        }
-        let written = written_locations(&instr);
-        for write_loc in written.iter() {
-            if let Location::Variable(_) = write_loc {
-                graph.add_vert(write_loc.clone());
-            }
-            for after_loc in instr.live_after.iter() {
-                if let Location::Variable(_) = after_loc {
-                    graph.add_vert(after_loc.clone());
-                }
-                if write_loc != after_loc {
-                    graph.add_edge(write_loc.clone(), after_loc.clone())
-                }
-            }
-        }
+        build_instr(instr, graph);
     }
 }
 
-fn mov_edges(
-    src: Option<Location>,
-    dest: Option<Location>,
-    live_after: &HashSet<Location>,
-    graph: &mut LocationGraph,
-) {
-    let dest = match dest {
-        Some(dst) => dst,
-        None => return,
+fn build_instr(instr: &LiveInstruction, graph: &mut LocationGraph) {
+    // the destination of a move does not interfere with its source
+    let (written, mov_src) = match &instr.instr {
+        Instruction::MovQ { src, dest } => (
+            Location::arg_loc(dest.clone()).into_iter().collect(),
+            Location::arg_loc(src.clone()).map(|src| graph.index(&src)),
+        ),
+        _ => (written_locations(instr), None),
     };
-    if let Location::Variable(_) = dest {
-        graph.add_vert(dest.clone());
-    }
-
-    for after in live_after {
-        if *after == dest {
-            continue;
+    for written in written {
+        let written = graph.index(&written);
+        if graph.is_variable(written) {
+            graph.add_vert_at(written);
         }
-        if let Some(ref s) = src
-            && after == s
-        {
-            if let Location::Variable(_) = *s {
-                graph.add_vert(s.clone());
+        for after in instr.live_after.iter() {
+            if after == written {
+                continue;
             }
-            continue;
+            if Some(after) == mov_src {
+                if graph.is_variable(after) {
+                    graph.add_vert_at(after);
+                }
+                continue;
+            }
+            graph.add_edge_at(written, after);
         }
-
-        graph.add_edge(dest.clone(), after.clone())
     }
 }
 
 #[cfg(test)]
 mod interference_graph_tests {
-    use super::{LiveBlock, LiveInstruction, LiveProg, LocationGraph, build_interference_graph};
+    use super::{LiveBlock, LiveProg, Location, LocationGraph, build_interference_graph};
+    use crate::{graph::LocationIndex, program::LiveInstruction};
     use asm::{Instruction, Reg};
-    use std::collections::HashSet;
 
     #[test]
     fn build_example() {
+        let mut index = LocationIndex::new();
+        let mut live = |locs: &[Location]| index.insert_all(locs);
         let mut example = LiveProg::new();
         example.blocks.push(LiveBlock {
             label: "main".to_owned(),
             instrs: vec![
                 LiveInstruction::new(
                     Instruction::mov(1, "v"),
-                    HashSet::from([Reg::Rsp.into()]),
-                    HashSet::from(["v".into(), Reg::Rsp.into()]),
+                    live(&[Reg::Rsp.into()]),
+                    live(&["v".into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::mov(42, "w"),
-                    HashSet::from(["v".into(), Reg::Rsp.into()]),
-                    HashSet::from(["v".into(), "w".into(), Reg::Rsp.into()]),
+                    live(&["v".into(), Reg::Rsp.into()]),
+                    live(&["v".into(), "w".into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::mov("v", "x"),
-                    HashSet::from(["v".into(), "w".into(), Reg::Rsp.into()]),
-                    HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
+                    live(&["v".into(), "w".into(), Reg::Rsp.into()]),
+                    live(&["w".into(), "x".into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::add(7, "x"),
-                    HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
-                    HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
+                    live(&["w".into(), "x".into(), Reg::Rsp.into()]),
+                    live(&["w".into(), "x".into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::mov("x", "y"),
-                    HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
-                    HashSet::from(["w".into(), "x".into(), "y".into(), Reg::Rsp.into()]),
+                    live(&["w".into(), "x".into(), Reg::Rsp.into()]),
+                    live(&["w".into(), "x".into(), "y".into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::mov("x", "z"),
-                    HashSet::from(["w".into(), "x".into(), "y".into(), Reg::Rsp.into()]),
-                    HashSet::from(["w".into(), "y".into(), "z".into(), Reg::Rsp.into()]),
+                    live(&["w".into(), "x".into(), "y".into(), Reg::Rsp.into()]),
+                    live(&["w".into(), "y".into(), "z".into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::add("w", "z"),
-                    HashSet::from(["w".into(), "y".into(), "z".into(), Reg::Rsp.into()]),
-                    HashSet::from(["y".into(), "z".into(), Reg::Rsp.into()]),
+                    live(&["w".into(), "y".into(), "z".into(), Reg::Rsp.into()]),
+                    live(&["y".into(), "z".into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::mov("y", "t"),
-                    HashSet::from(["y".into(), "z".into(), Reg::Rsp.into()]),
-                    HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
+                    live(&["y".into(), "z".into(), Reg::Rsp.into()]),
+                    live(&["t".into(), "z".into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::neg("t"),
-                    HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
-                    HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
+                    live(&["t".into(), "z".into(), Reg::Rsp.into()]),
+                    live(&["t".into(), "z".into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::mov("z", Reg::Rax),
-                    HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
-                    HashSet::from([Reg::Rax.into(), "t".into(), Reg::Rsp.into()]),
+                    live(&["t".into(), "z".into(), Reg::Rsp.into()]),
+                    live(&[Reg::Rax.into(), "t".into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::add("t", Reg::Rax),
-                    HashSet::from([Reg::Rax.into(), "t".into(), Reg::Rsp.into()]),
-                    HashSet::from([Reg::Rax.into(), Reg::Rsp.into()]),
+                    live(&[Reg::Rax.into(), "t".into(), Reg::Rsp.into()]),
+                    live(&[Reg::Rax.into(), Reg::Rsp.into()]),
                 ),
                 LiveInstruction::new(
                     Instruction::jmp("conclusion"),
-                    HashSet::from([Reg::Rax.into(), Reg::Rsp.into()]),
-                    HashSet::new(),
+                    live(&[Reg::Rax.into(), Reg::Rsp.into()]),
+                    live(&[]),
                 ),
             ],
         });
+        example.locations = index;
         let result = build_interference_graph(&example);
         let mut expected = LocationGraph::new();
         expected.add_edge("t".into(), Reg::Rax.into());
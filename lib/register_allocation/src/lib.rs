@@ -1,21 +1,32 @@
 mod assign;
+mod coalesce;
 mod color_graph;
 mod colors;
+mod dead_stores;
 mod errors;
 mod flow_graph;
 mod graph;
 mod interference_graph;
+mod linear_scan;
 mod move_graph;
 mod program;
+mod spill_costs;
+mod split_live_ranges;
 mod uncover_live;
+mod verify;
 
 pub use assign::assign_homes;
 pub use color_graph::color_graph;
-pub use colors::Coloring;
+pub use colors::{ALLOCATABLE_REGISTERS, Coloring, RegisterSet};
+pub use dead_stores::eliminate_dead_stores;
 pub use errors::Error;
 pub use flow_graph::FlowGraph;
-pub use graph::LocationGraph;
+pub use graph::{BitSet, LocationGraph, LocationIndex};
 pub use interference_graph::build_interference_graph;
+pub use linear_scan::{Interval, linear_scan, live_intervals};
 pub use move_graph::build_move_graph;
 pub use program::LiveProg;
+pub use spill_costs::{SpillCosts, spill_costs, spill_report};
+pub use split_live_ranges::split_around_calls;
 pub use uncover_live::uncover_live;
+pub use verify::verify_homes;
@@ -0,0 +1,226 @@
+use crate::{
+    colors::{Color, Coloring, RegisterSet},
+    program::{LiveProg, Location},
+    uncover_live::{read_locations, written_locations},
+};
+use asm::Instruction;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+/// Range of points where a variable is live, numbered over all blocks in order
+/// Instruction `i` reads at point `2i` and writes at point `2i + 1`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interval {
+    pub var: Location,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Intervals of all variables, ordered by their start
+pub fn live_intervals(prog: &LiveProg) -> Vec<Interval> {
+    let mut ranges: Vec<Option<(usize, usize)>> = vec![None; prog.locations.len()];
+    let mut extend = |ind: usize, point: usize| {
+        let range = ranges[ind].get_or_insert((point, point));
+        range.0 = range.0.min(point);
+        range.1 = range.1.max(point);
+    };
+    let instrs = prog.blocks.iter().flat_map(|block| block.instrs.iter());
+    for (ind, instr) in instrs.enumerate() {
+        let read = read_locations(instr)
+            .into_iter()
+            .filter_map(|loc| prog.locations.get(&loc));
+        for loc in instr.live_before.iter().chain(read) {
+            extend(loc, 2 * ind);
+        }
+        let written = written_locations(instr)
+            .into_iter()
+            .filter_map(|loc| prog.locations.get(&loc));
+        for loc in instr.live_after.iter().chain(written) {
+            extend(loc, 2 * ind + 1);
+        }
+    }
+
+    let mut intervals: Vec<Interval> = ranges
+        .into_iter()
+        .enumerate()
+        .filter_map(|(ind, range)| {
+            let var = prog.locations.location(ind);
+            match (var, range) {
+                (Location::Variable(_), Some((start, end))) => Some(Interval {
+                    var: var.clone(),
+                    start,
+                    end,
+                }),
+                _ => None,
+            }
+        })
+        .collect();
+    intervals.sort_by(|int1, int2| (int1.start, &int1.var).cmp(&(int2.start, &int2.var)));
+    intervals
+}
+
+/// Assigns registers in order of the intervals,
+/// when none is free the interval ending last goes on the stack
+/// Spilled intervals share stack slots when they do not overlap
+pub fn linear_scan(prog: &LiveProg, registers: &RegisterSet) -> Coloring {
+    let forbidden = forbidden_registers(prog, registers);
+    let no_colors = HashSet::new();
+    let max_color = registers.max_color();
+    let mut coloring = registers.empty_coloring();
+    let mut active: Vec<(Interval, Color)> = vec![];
+    let mut spilled = vec![];
+
+    for interval in live_intervals(prog) {
+        active.retain(|(other, _)| other.end >= interval.start);
+        let blocked = forbidden.get(&interval.var).unwrap_or(&no_colors);
+        let free = (0..=max_color).find(|color| {
+            !blocked.contains(color) && !active.iter().any(|(_, used)| used == color)
+        });
+        if let Some(color) = free {
+            coloring.0.insert(interval.var.clone(), color);
+            active.push((interval, color));
+            continue;
+        }
+
+        let victim = active
+            .iter()
+            .enumerate()
+            .filter(|(_, (other, color))| other.end > interval.end && !blocked.contains(color))
+            .max_by(|(_, (int1, _)), (_, (int2, _))| {
+                (int1.end, &int1.var).cmp(&(int2.end, &int2.var))
+            })
+            .map(|(ind, _)| ind);
+        match victim {
+            Some(ind) => {
+                let (other, color) = active.remove(ind);
+                coloring.0.remove(&other.var);
+                spilled.push(other);
+                coloring.0.insert(interval.var.clone(), color);
+                active.push((interval, color));
+            }
+            None => spilled.push(interval),
+        }
+    }
+
+    spilled.sort_by(|int1, int2| (int1.start, &int1.var).cmp(&(int2.start, &int2.var)));
+    let mut active_slots: Vec<(usize, Color)> = vec![];
+    for interval in spilled {
+        active_slots.retain(|(end, _)| *end >= interval.start);
+        let slot = (max_color + 1..)
+            .find(|color| !active_slots.iter().any(|(_, used)| used == color))
+            .unwrap_or(max_color + 1);
+        coloring.0.insert(interval.var, slot);
+        active_slots.push((interval.end, slot));
+    }
+    coloring
+}
+
+/// Register colors each variable may not use,
+/// following the same rules as the interference graph
+fn forbidden_registers(
+    prog: &LiveProg,
+    registers: &RegisterSet,
+) -> HashMap<Location, HashSet<Color>> {
+    let register_colors = registers.empty_coloring();
+    let mut forbidden: HashMap<Location, HashSet<Color>> = HashMap::new();
+    for instr in prog.blocks.iter().flat_map(|block| block.instrs.iter()) {
+        let mov_src = match &instr.instr {
+            Instruction::MovQ { src, .. } => Location::arg_loc(src.clone()),
+            _ => None,
+        };
+        for written in written_locations(instr) {
+            for after in prog.live_locations(&instr.live_after) {
+                if *after == written || Some(after) == mov_src.as_ref() {
+                    continue;
+                }
+                let (var, reg) = match (&written, after) {
+                    (var @ Location::Variable(_), reg @ Location::Register(_))
+                    | (reg @ Location::Register(_), var @ Location::Variable(_)) => (var, reg),
+                    _ => continue,
+                };
+                if let Some(color) = register_colors.0.get(reg) {
+                    forbidden.entry(var.clone()).or_default().insert(*color);
+                }
+            }
+        }
+    }
+    forbidden
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: [{}, {}]", self.var, self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod linear_scan_tests {
+    use super::{linear_scan, live_intervals};
+    use crate::{colors::RegisterSet, program::Location, uncover_live::uncover_live};
+    use asm::{Instruction, Reg, VarProgram};
+
+    #[test]
+    fn scan_across_call() {
+        let mut prog = VarProgram::new();
+        prog.add_block(
+            "start",
+            vec![
+                Instruction::mov(1, "a"),
+                Instruction::mov(2, "b"),
+                Instruction::mov("a", Reg::Rdi),
+                Instruction::call("print_int", 1),
+                Instruction::mov("b", Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        );
+        let registers = RegisterSet::default();
+        let result = linear_scan(&uncover_live(prog).unwrap(), &registers);
+        assert_eq!(
+            registers.color_to_arg(result.0[&Location::from("a")]),
+            Reg::Rcx.into()
+        );
+        // b is live across the call, so it needs a callee saved register
+        assert_eq!(
+            registers.color_to_arg(result.0[&Location::from("b")]),
+            Reg::Rbx.into()
+        );
+    }
+
+    #[test]
+    fn scan_spill_longest() {
+        let vars: Vec<String> = (0..13).map(|i| format!("x{i}")).collect();
+        let mut instrs: Vec<_> = vars
+            .iter()
+            .zip(1..)
+            .map(|(var, i)| Instruction::mov(i, var.as_str()))
+            .collect();
+        instrs.push(Instruction::mov(0, "sum"));
+        for var in vars.iter() {
+            instrs.push(Instruction::add(var.as_str(), "sum"));
+        }
+        instrs.push(Instruction::mov("sum", Reg::Rax));
+        instrs.push(Instruction::jmp("conclusion"));
+        let mut prog = VarProgram::new();
+        prog.add_block("start", instrs);
+        let live = uncover_live(prog).unwrap();
+        let registers = RegisterSet::new(11);
+        let result = linear_scan(&live, &registers);
+
+        let spilled: Vec<&Location> = result
+            .0
+            .iter()
+            .filter(|(_, color)| **color > registers.max_color())
+            .map(|(loc, _)| loc)
+            .collect();
+        assert_eq!(spilled.len(), 3);
+        let intervals = live_intervals(&live);
+        for (ind, int1) in intervals.iter().enumerate() {
+            for int2 in intervals[ind + 1..].iter() {
+                let overlap = int1.start <= int2.end && int2.start <= int1.end;
+                assert!(!overlap || result.0[&int1.var] != result.0[&int2.var]);
+            }
+        }
+    }
+}
@@ -34,8 +34,8 @@ fn build_instr(instr: &LiveInstruction, graph: &mut LocationGraph) {
 #[cfg(test)]
 mod move_graph_tests {
     use super::{LiveBlock, LiveInstruction, LiveProg, LocationGraph, build_move_graph};
+    use crate::graph::BitSet;
     use asm::{Instruction, Reg};
-    use std::collections::HashSet;
 
     #[test]
     fn build_example() {
@@ -44,64 +44,64 @@ mod move_graph_tests {
             label: "main".to_owned(),
             instrs: vec![
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::mov(1, "v"),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::mov(42, "w"),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::mov("v", "x"),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::add(7, "x"),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::mov("x", "y"),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::mov("x", "z"),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::add("w", "z"),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::mov("y", "t"),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::neg("t"),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::mov("z", Reg::Rax),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::add("t", Reg::Rax),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
                 LiveInstruction {
-                    live_before: HashSet::new(),
+                    live_before: BitSet::new(),
                     instr: Instruction::jmp("conclusion"),
-                    live_after: HashSet::new(),
+                    live_after: BitSet::new(),
                 },
             ],
         });
@@ -1,19 +1,19 @@
-use super::Location;
+use crate::graph::BitSet;
 use asm::{Instruction, VarArg};
-use std::{collections::HashSet, fmt};
 
+/// Instruction with the locations live around it, as indices into `LiveProg::locations`
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct LiveInstruction {
     pub instr: Instruction<VarArg>,
-    pub live_before: HashSet<Location>,
-    pub live_after: HashSet<Location>,
+    pub live_before: BitSet,
+    pub live_after: BitSet,
 }
 
 impl LiveInstruction {
     pub fn new(
         instr: Instruction<VarArg>,
-        live_before: HashSet<Location>,
-        live_after: HashSet<Location>,
+        live_before: BitSet,
+        live_after: BitSet,
     ) -> LiveInstruction {
         LiveInstruction {
             instr,
@@ -23,22 +23,12 @@ impl LiveInstruction {
     }
 }
 
-impl fmt::Display for LiveInstruction {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}; L_before = {:?}; L_after = {:?}",
-            self.instr, self.live_before, self.live_after
-        )
-    }
-}
-
 impl From<Instruction<VarArg>> for LiveInstruction {
     fn from(instr: Instruction<VarArg>) -> LiveInstruction {
         LiveInstruction {
             instr,
-            live_before: HashSet::new(),
-            live_after: HashSet::new(),
+            live_before: BitSet::new(),
+            live_after: BitSet::new(),
         }
     }
 }
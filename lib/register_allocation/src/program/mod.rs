@@ -1,4 +1,6 @@
-use asm::VarProgram;
+use crate::graph::{BitSet, LocationIndex};
+use asm::{StringLiteral, VarProgram};
+use definitions::Global;
 use std::fmt;
 
 mod live_block;
@@ -8,21 +10,54 @@ pub use live_block::LiveBlock;
 pub use live_instruction::LiveInstruction;
 pub use location::Location;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, Clone)]
 pub struct LiveProg {
     pub blocks: Vec<LiveBlock>,
+    pub strings: Vec<StringLiteral>,
+    pub globals: Vec<Global>,
+    /// Locations the live sets of the instructions index into
+    pub locations: LocationIndex,
 }
 
 impl LiveProg {
     pub fn new() -> LiveProg {
-        LiveProg { blocks: vec![] }
+        LiveProg {
+            blocks: vec![],
+            strings: vec![],
+            globals: vec![],
+            locations: LocationIndex::new(),
+        }
+    }
+
+    /// Locations in `live`, in the order they were indexed
+    pub fn live_locations<'a>(&'a self, live: &'a BitSet) -> impl Iterator<Item = &'a Location> {
+        live.iter().map(|ind| self.locations.location(ind))
+    }
+
+    /// Names of the locations in `live`, sorted for printing
+    fn live_names(&self, live: &BitSet) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .live_locations(live)
+            .map(|loc| loc.to_string())
+            .collect();
+        names.sort();
+        names
     }
 }
 
 impl fmt::Display for LiveProg {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for block in self.blocks.iter() {
-            block.fmt(f)?;
+            writeln!(f, "{}: ", block.label)?;
+            for instr in block.instrs.iter() {
+                writeln!(
+                    f,
+                    "\t{}; L_before = {:?}; L_after = {:?}",
+                    instr.instr,
+                    self.live_names(&instr.live_before),
+                    self.live_names(&instr.live_after)
+                )?;
+            }
             writeln!(f)?;
         }
         Ok(())
@@ -38,6 +73,8 @@ impl Default for LiveProg {
 impl From<VarProgram> for LiveProg {
     fn from(prog: VarProgram) -> LiveProg {
         let mut annot_prog = LiveProg::new();
+        annot_prog.strings = prog.strings;
+        annot_prog.globals = prog.globals;
         for block in prog.blocks {
             annot_prog.blocks.push(block.into())
         }
@@ -0,0 +1,157 @@
+use crate::{
+    colors::{Coloring, RegisterSet},
+    flow_graph::FlowGraph,
+    program::{LiveProg, Location},
+    uncover_live::{read_locations, written_locations},
+};
+use std::collections::HashMap;
+
+/// How many times more often a block runs than the code around its innermost loop
+const LOOP_WEIGHT: usize = 10;
+
+/// Estimated number of memory accesses added by keeping a variable on the stack
+pub type SpillCosts = HashMap<Location, usize>;
+
+/// Counts the reads and writes of every variable,
+/// weighted by `LOOP_WEIGHT` for each loop around the instruction
+pub fn spill_costs(prog: &LiveProg) -> SpillCosts {
+    let depths = FlowGraph::from(prog).loop_depths("start");
+    let mut costs = SpillCosts::new();
+    for block in prog.blocks.iter() {
+        let depth = depths.get(&block.label).copied().unwrap_or_default();
+        let weight = LOOP_WEIGHT.saturating_pow(depth as u32);
+        for instr in block.instrs.iter() {
+            let accessed = read_locations(instr)
+                .into_iter()
+                .chain(written_locations(instr))
+                .filter(|loc| matches!(loc, Location::Variable(_)));
+            for loc in accessed {
+                let cost = costs.entry(loc).or_default();
+                *cost = cost.saturating_add(weight);
+            }
+        }
+    }
+    costs
+}
+
+/// Variables that did not get a register, from most to least expensive
+pub fn spill_report(coloring: &Coloring, costs: &SpillCosts, registers: &RegisterSet) -> String {
+    let mut spilled: Vec<(&Location, usize, i64)> = coloring
+        .0
+        .iter()
+        .filter(|(loc, color)| {
+            matches!(loc, Location::Variable(_)) && **color > registers.max_color()
+        })
+        .map(|(loc, color)| (loc, costs.get(loc).copied().unwrap_or_default(), *color))
+        .collect();
+    spilled.sort_by(|(loc1, cost1, _), (loc2, cost2, _)| cost2.cmp(cost1).then(loc1.cmp(loc2)));
+    if spilled.is_empty() {
+        return "No spills".to_owned();
+    }
+    spilled
+        .into_iter()
+        .map(|(loc, cost, color)| {
+            format!(
+                "spilled {loc} (cost {cost}) to {}",
+                registers.color_to_arg(color)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod spill_costs_tests {
+    use super::{LOOP_WEIGHT, spill_costs, spill_report};
+    use crate::{
+        colors::{Coloring, RegisterSet},
+        uncover_live::uncover_live,
+    };
+    use asm::{Cc, Instruction, Reg, VarProgram};
+    use std::collections::HashMap;
+
+    /// `j` counts up in an inner loop, `i` in the loop around it,
+    /// `s` sums `i` in the inner loop and is returned
+    fn nested_loops() -> VarProgram {
+        let mut prog = VarProgram::new();
+        let loop_back = |var: &str, label: &str, exit: &str| {
+            vec![
+                Instruction::CmpQ {
+                    left: var.into(),
+                    right: 10.into(),
+                },
+                Instruction::JumpCC {
+                    cc: Cc::L,
+                    label: label.to_owned(),
+                },
+                Instruction::jmp(exit),
+            ]
+        };
+        prog.add_block(
+            "start",
+            vec![
+                Instruction::mov(0, "i"),
+                Instruction::mov(0, "s"),
+                Instruction::jmp("outer"),
+            ],
+        );
+        prog.add_block(
+            "outer",
+            vec![Instruction::mov(0, "j"), Instruction::jmp("inner")],
+        );
+        let mut inner = vec![Instruction::add("i", "s"), Instruction::add(1, "j")];
+        inner.extend(loop_back("j", "inner", "latch"));
+        prog.add_block("inner", inner);
+        let mut latch = vec![Instruction::add(1, "i")];
+        latch.extend(loop_back("i", "outer", "end"));
+        prog.add_block("latch", latch);
+        prog.add_block(
+            "end",
+            vec![
+                Instruction::mov("s", Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        );
+        prog
+    }
+
+    #[test]
+    fn costs_nested_loops() {
+        let prog = uncover_live(nested_loops()).unwrap();
+        let costs = spill_costs(&prog);
+        let outer = LOOP_WEIGHT;
+        let inner = LOOP_WEIGHT * LOOP_WEIGHT;
+        // an `add` reads and writes its destination, counting twice
+        let expected = HashMap::from([
+            ("i".into(), 1 + inner + 2 * outer + outer),
+            ("s".into(), 1 + 2 * inner + 1),
+            ("j".into(), outer + 2 * inner + inner),
+        ]);
+        assert_eq!(costs, expected);
+    }
+
+    #[test]
+    fn report_by_cost() {
+        let prog = uncover_live(nested_loops()).unwrap();
+        let costs = spill_costs(&prog);
+        let registers = RegisterSet::default();
+        let stack = registers.max_color() + 1;
+        // `k` got a register, so it is not reported
+        let coloring = Coloring(HashMap::from([
+            ("i".into(), stack),
+            ("s".into(), stack + 1),
+            ("j".into(), stack + 2),
+            ("k".into(), 0),
+        ]));
+        let report = spill_report(&coloring, &costs, &registers);
+        let expected = [
+            format!("spilled j (cost {}) to -24(%rbp)", costs[&"j".into()]),
+            format!("spilled s (cost {}) to -16(%rbp)", costs[&"s".into()]),
+            format!("spilled i (cost {}) to -8(%rbp)", costs[&"i".into()]),
+        ];
+        assert_eq!(report.lines().collect::<Vec<_>>(), expected);
+
+        let coloring = Coloring(HashMap::from([("i".into(), 0)]));
+        assert_eq!(spill_report(&coloring, &costs, &registers), "No spills");
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    errors::Error,
+    flow_graph::FlowGraph,
+    program::{LiveProg, Location},
+    uncover_live::{read_locations, uncover_live, written_locations},
+};
+use asm::{Instruction, VarProgram};
+use std::collections::HashMap;
+
+/// Copies a variable live across a call into a fresh variable right before the call
+/// and back right before it is next used or the block is left,
+/// so only the copy has to avoid caller saved registers
+/// Only variables also used in a loop the call is not part of are split,
+/// so they can stay in caller saved registers in that loop
+pub fn split_around_calls(mut prog: VarProgram) -> Result<VarProgram, Error> {
+    let live = uncover_live(prog.clone())?;
+    let depths = FlowGraph::from(&live).loop_depths("start");
+    let access_depths = access_depths(&live, &depths);
+    let mut num_saves = 0;
+    for (block, live_block) in prog.blocks.iter_mut().zip(live.blocks) {
+        let depth = depths.get(&block.label).copied().unwrap_or_default();
+        let mut instrs = vec![];
+        // variables currently held only by their copy, with the name of the copy
+        let mut saved: Vec<(String, String)> = vec![];
+        for instr in live_block.instrs {
+            if !matches!(instr.instr, Instruction::CallQ { .. }) {
+                let read = read_locations(&instr);
+                let written = written_locations(&instr);
+                let leaves = matches!(
+                    instr.instr,
+                    Instruction::Jump { .. } | Instruction::JumpCC { .. } | Instruction::RetQ
+                );
+                saved.retain(|(var, save)| {
+                    let loc = Location::Variable(var.clone());
+                    let live_before = live
+                        .locations
+                        .get(&loc)
+                        .is_some_and(|ind| instr.live_before.contains(ind));
+                    if read.contains(&loc) || (leaves && live_before) {
+                        instrs.push(Instruction::mov(save.as_str(), var.as_str()));
+                        false
+                    } else {
+                        // a copy of an overwritten or dead variable is not needed anymore
+                        !written.contains(&loc) && !leaves
+                    }
+                });
+                instrs.push(instr.instr);
+                continue;
+            }
+            let mut across: Vec<&String> = instr
+                .live_after
+                .iter()
+                .map(|ind| live.locations.location(ind))
+                .filter_map(|loc| match loc {
+                    Location::Variable(var)
+                        if access_depths.get(loc).is_some_and(|max| *max > depth)
+                            && !saved.iter().any(|(saved_var, _)| saved_var == var) =>
+                    {
+                        Some(var)
+                    }
+                    _ => None,
+                })
+                .collect();
+            across.sort();
+            for var in across {
+                num_saves += 1;
+                let save = format!("{var}.save{num_saves}");
+                instrs.push(Instruction::mov(var.as_str(), save.as_str()));
+                saved.push((var.clone(), save));
+            }
+            instrs.push(instr.instr);
+        }
+        for (var, save) in saved {
+            instrs.push(Instruction::mov(save.as_str(), var.as_str()));
+        }
+        block.instrs = instrs;
+    }
+    Ok(prog)
+}
+
+/// Deepest loop each variable is read or written in
+fn access_depths(prog: &LiveProg, depths: &HashMap<String, usize>) -> HashMap<Location, usize> {
+    let mut access_depths = HashMap::new();
+    for block in prog.blocks.iter() {
+        let depth = depths.get(&block.label).copied().unwrap_or_default();
+        for instr in block.instrs.iter() {
+            let accessed = read_locations(instr)
+                .into_iter()
+                .chain(written_locations(instr))
+                .filter(|loc| matches!(loc, Location::Variable(_)));
+            for loc in accessed {
+                let max_depth = access_depths.entry(loc).or_default();
+                *max_depth = depth.max(*max_depth);
+            }
+        }
+    }
+    access_depths
+}
+
+#[cfg(test)]
+mod split_live_ranges_tests {
+    use super::split_around_calls;
+    use asm::{Cc, Instruction, Reg, VarProgram};
+
+    fn sum_loop(before_call: Vec<Instruction<asm::VarArg>>) -> VarProgram {
+        let mut prog = VarProgram::new();
+        let mut start = vec![Instruction::mov(0, "sum")];
+        start.extend(before_call);
+        start.extend([
+            Instruction::call("read_int", 0),
+            Instruction::mov(Reg::Rax, "n"),
+            Instruction::jmp("loop"),
+        ]);
+        prog.add_block("start", start);
+        prog.add_block(
+            "loop",
+            vec![
+                Instruction::add("n", "sum"),
+                Instruction::sub(1, "n"),
+                Instruction::CmpQ {
+                    left: "n".into(),
+                    right: 0.into(),
+                },
+                Instruction::JumpCC {
+                    cc: Cc::G,
+                    label: "loop".to_owned(),
+                },
+                Instruction::jmp("done"),
+            ],
+        );
+        prog.add_block(
+            "done",
+            vec![
+                Instruction::mov("sum", Reg::Rdi),
+                Instruction::call("print_int", 1),
+                Instruction::jmp("conclusion"),
+            ],
+        );
+        prog
+    }
+
+    #[test]
+    fn split_before_loop() {
+        let result = split_around_calls(sum_loop(vec![])).unwrap();
+        let expected = sum_loop(vec![Instruction::mov("sum", "sum.save1")]);
+        let mut expected_start = expected.blocks[0].instrs.clone();
+        expected_start.insert(4, Instruction::mov("sum.save1", "sum"));
+        assert_eq!(result.blocks[0].instrs, expected_start);
+        assert_eq!(result.blocks[1..], expected.blocks[1..]);
+    }
+
+    #[test]
+    fn split_once_for_consecutive_calls() {
+        let result =
+            split_around_calls(sum_loop(vec![Instruction::call("print_newline", 0)])).unwrap();
+        let expected = sum_loop(vec![
+            Instruction::mov("sum", "sum.save1"),
+            Instruction::call("print_newline", 0),
+        ]);
+        let mut expected_start = expected.blocks[0].instrs.clone();
+        expected_start.insert(5, Instruction::mov("sum.save1", "sum"));
+        assert_eq!(result.blocks[0].instrs, expected_start);
+        assert_eq!(result.blocks[1..], expected.blocks[1..]);
+    }
+
+    #[test]
+    fn split_not_outside_loops() {
+        let mut prog = VarProgram::new();
+        prog.add_block(
+            "start",
+            vec![
+                Instruction::mov(1, "a"),
+                Instruction::mov(2, "b"),
+                Instruction::mov("a", Reg::Rdi),
+                Instruction::call("print_int", 1),
+                Instruction::mov("b", Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        );
+        let result = split_around_calls(prog.clone()).unwrap();
+        assert_eq!(result, prog)
+    }
+}
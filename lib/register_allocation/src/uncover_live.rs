@@ -1,74 +1,185 @@
 use crate::{
     errors::Error,
+    flow_graph::FlowGraph,
+    graph::{BitSet, LocationIndex},
     program::{LiveBlock, LiveInstruction, LiveProg, Location, location::arg_locations},
 };
-use asm::{Instruction, Reg, VarProgram};
-use definitions::PRINT_CALL;
-use std::collections::{HashMap, HashSet};
+use asm::{CONCLUSION_LABEL, Instruction, Reg, VarArg, VarProgram};
+use definitions::dataflow::{Analysis, ControlFlow, Direction, solve};
+use std::{
+    cell::Cell,
+    collections::{HashMap, HashSet},
+};
 
-pub fn uncover_live(prog: VarProgram) -> Result<LiveProg, Error> {
-    let mut annot: LiveProg = prog.into();
-    let mut label2live = HashMap::new();
-    label2live.insert(
-        "conclusion".to_owned(),
-        HashSet::from([Reg::Rax.into(), Reg::Rsp.into()]),
-    );
-    for label in annot.blocks.iter().map(|bl| &bl.label) {
-        label2live.insert(label.clone(), HashSet::new());
+/// Indices of the locations an instruction reads and writes
+struct Accesses {
+    read: BitSet,
+    written: BitSet,
+    /// Variable the instruction stores to without any other effect
+    store: Option<usize>,
+}
+
+/// Locations live at the start of each block, as bitsets over `LocationIndex`
+/// Programs end in the conclusion, which reads the return value and the stack pointer
+struct Liveness<'a> {
+    blocks: HashMap<&'a str, (&'a LiveBlock, Vec<Accesses>)>,
+    live_at_exit: BitSet,
+    /// Whether stores to variables that are not live are skipped, reads included
+    faint: bool,
+    /// Number of times a block was analyzed
+    visits: Cell<usize>,
+}
+
+impl Analysis for Liveness<'_> {
+    type Fact = BitSet;
+    const DIRECTION: Direction = Direction::Backward;
+
+    fn initial(&self) -> BitSet {
+        self.live_at_exit.clone()
     }
 
-    let mut changing = true;
-    while changing {
-        changing = false;
-        for block in annot.blocks.iter_mut() {
-            if block.label == "conclusion" || block.label == "main" {
-                continue;
-            }
-            changing = changing || uncover_block(block, &label2live)?;
-            label2live.insert(block.label.clone(), (block.instrs[0]).live_before.clone());
-        }
+    fn transfer(&self, label: &str, live_out: &BitSet) -> BitSet {
+        self.visits.set(self.visits.get() + 1);
+        let (block, accesses) = &self.blocks[label];
+        block.instrs.iter().zip(accesses.iter()).rev().fold(
+            live_out.clone(),
+            |live_after, (instr, accesses)| {
+                live_before(instr, accesses, live_after, self.faint, |_| live_out)
+            },
+        )
     }
+}
 
-    Ok(annot)
+/// Annotates every instruction with the locations live before and after it
+/// The locations live at the start of each block are found first, revisiting blocks
+/// in postorder of the flow graph until nothing changes
+pub fn uncover_live(prog: VarProgram) -> Result<LiveProg, Error> {
+    annotate_live(prog, false).map(|(live, _)| live)
+}
+
+/// Like `uncover_live`, but stores to variables that are not live afterwards are skipped,
+/// so variables only read by such stores are not live either
+/// Also returns how many times a block was analyzed
+pub(crate) fn uncover_faint(prog: VarProgram) -> Result<(LiveProg, usize), Error> {
+    annotate_live(prog, true)
 }
 
-fn uncover_block(
-    block: &mut LiveBlock,
-    label2live: &HashMap<String, HashSet<Location>>,
-) -> Result<bool, Error> {
-    let mut last_after = HashSet::new();
+fn annotate_live(prog: VarProgram, faint: bool) -> Result<(LiveProg, usize), Error> {
+    let mut graph = FlowGraph::new();
+    graph.build(&prog);
+    let mut annot: LiveProg = prog.into();
+
+    let mut index = LocationIndex::new();
+    let live_at_exit = [Reg::Rax.into(), Reg::Rsp.into()]
+        .iter()
+        .map(|loc| index.insert(loc))
+        .collect();
+    let mut accesses: Vec<Vec<Accesses>> = vec![];
+    for block in annot.blocks.iter() {
+        let mut block_accesses = vec![];
+        for instr in block.instrs.iter() {
+            let read = read_locations(instr);
+            let written = written_locations(instr);
+            block_accesses.push(Accesses {
+                read: index.insert_all(&read),
+                written: index.insert_all(&written),
+                store: stored_variable(instr).map(|var| index.insert(&var)),
+            });
+        }
+        accesses.push(block_accesses);
+    }
 
-    block.instrs.reverse();
-    let mut changing = false;
-    for instr in block.instrs.iter_mut() {
-        changing = changing || live_before(instr, &last_after, label2live)?;
-        instr.live_after = last_after;
-        last_after = instr.live_before.clone();
+    // the conclusion is only generated after register allocation,
+    // until then it is an empty block without successors
+    let conclusion = LiveBlock {
+        label: CONCLUSION_LABEL.to_owned(),
+        instrs: vec![],
+    };
+    let mut blocks: HashMap<&str, (&LiveBlock, Vec<Accesses>)> = annot
+        .blocks
+        .iter()
+        .zip(accesses)
+        .map(|(block, accesses)| (block.label.as_str(), (block, accesses)))
+        .collect();
+    blocks.insert(CONCLUSION_LABEL, (&conclusion, vec![]));
+    if let Some(label) = graph
+        .labels()
+        .into_iter()
+        .find(|label| !blocks.contains_key(label.as_str()))
+    {
+        return Err(Error::MissingLiveBefore(label));
+    }
+    let liveness = Liveness {
+        blocks,
+        live_at_exit,
+        faint,
+        visits: Cell::new(0),
+    };
+    let label2live = solve(&liveness, &graph, "start").entry;
+    let visits = liveness.visits.get();
+    let accesses: HashMap<String, Vec<Accesses>> = liveness
+        .blocks
+        .into_iter()
+        .map(|(label, (_, accesses))| (label.to_owned(), accesses))
+        .collect();
+
+    for block in annot.blocks.iter_mut() {
+        let mut live_after = BitSet::new();
+        for (instr, accesses) in block.instrs.iter_mut().zip(&accesses[&block.label]).rev() {
+            let live_before = live_before(instr, accesses, live_after.clone(), faint, |label| {
+                &label2live[label]
+            });
+            instr.live_after = live_after;
+            instr.live_before = live_before.clone();
+            live_after = live_before;
+        }
     }
-    block.instrs.reverse();
-    Ok(changing)
+    annot.locations = index;
+    Ok((annot, visits))
 }
 
-fn live_before(
-    instr: &mut LiveInstruction,
-    live_after: &HashSet<Location>,
-    label2live: &HashMap<String, HashSet<Location>>,
-) -> Result<bool, Error> {
+/// Locations live before `instr`, looking up the locations live at jump targets
+/// With `faint`, a store to a variable that is not live leaves the live locations unchanged
+fn live_before<'a>(
+    instr: &LiveInstruction,
+    accesses: &Accesses,
+    mut live: BitSet,
+    faint: bool,
+    live_in: impl Fn(&str) -> &'a BitSet,
+) -> BitSet {
+    if faint && accesses.store.is_some_and(|var| !live.contains(var)) {
+        return live;
+    }
     if let Instruction::Jump { label } = &instr.instr {
-        let live_before = label2live
-            .get(label)
-            .ok_or(Error::MissingLiveBefore(label.clone()))?
-            .clone();
-        let changed = live_before != instr.live_before;
-        instr.live_before = &instr.live_before | &live_before;
-        return Ok(changed);
+        return live_in(label).clone();
+    }
+    live.difference_with(&accesses.written);
+    live.union_with(&accesses.read);
+    if let Instruction::JumpCC { label, .. } = &instr.instr {
+        live.union_with(live_in(label));
+    }
+    live
+}
+
+/// Variable written by an instruction that has no other effect
+/// Calls are never stores, even if their result is unused
+pub fn stored_variable(instr: &LiveInstruction) -> Option<Location> {
+    let dest = match &instr.instr {
+        Instruction::AddQ { dest, .. }
+        | Instruction::SubQ { dest, .. }
+        | Instruction::MovQ { dest, .. }
+        | Instruction::XorQ { dest, .. }
+        | Instruction::MovZBQ { dest, .. }
+        | Instruction::AndQ { dest, .. }
+        | Instruction::OrQ { dest, .. }
+        | Instruction::LeaQ { dest, .. } => dest,
+        Instruction::NegQ { arg } | Instruction::IncQ { arg } => arg,
+        _ => return None,
+    };
+    match dest {
+        VarArg::Var(var) => Some(Location::Variable(var.to_owned())),
+        VarArg::Arg(_) => None,
     }
-    let written = written_locations(instr);
-    let read = read_locations(instr);
-    let next_live_before = &(live_after - &written) | &read;
-    let changed = next_live_before != instr.live_before;
-    instr.live_before = &instr.live_before | &next_live_before;
-    Ok(changed)
 }
 
 pub fn written_locations(instr: &LiveInstruction) -> HashSet<Location> {
@@ -76,6 +187,7 @@ pub fn written_locations(instr: &LiveInstruction) -> HashSet<Location> {
         Instruction::AddQ { dest, .. } => arg_locations(dest),
         Instruction::SubQ { dest, .. } => arg_locations(dest),
         Instruction::NegQ { arg } => arg_locations(arg),
+        Instruction::IncQ { arg } => arg_locations(arg),
         Instruction::MovQ { dest, .. } => arg_locations(dest),
         Instruction::PushQ { .. } => HashSet::new(),
         Instruction::PopQ { .. } => HashSet::new(),
@@ -92,24 +204,24 @@ pub fn written_locations(instr: &LiveInstruction) -> HashSet<Location> {
         Instruction::JumpCC { .. } => HashSet::new(),
         Instruction::AndQ { dest, .. } => arg_locations(dest),
         Instruction::OrQ { dest, .. } => arg_locations(dest),
+        Instruction::LeaQ { dest, .. } => arg_locations(dest),
     }
 }
 
-fn read_locations(instr: &LiveInstruction) -> HashSet<Location> {
+pub fn read_locations(instr: &LiveInstruction) -> HashSet<Location> {
     match &instr.instr {
         Instruction::AddQ { src, dest } => &arg_locations(src) | &arg_locations(dest),
         Instruction::SubQ { dest, src } => &arg_locations(src) | &arg_locations(dest),
         Instruction::NegQ { arg } => arg_locations(arg),
+        Instruction::IncQ { arg } => arg_locations(arg),
         Instruction::MovQ { src, .. } => arg_locations(src),
         Instruction::PushQ { arg } => arg_locations(arg),
         Instruction::PopQ { arg } => arg_locations(arg),
-        Instruction::CallQ { label } => {
-            if label == PRINT_CALL {
-                HashSet::from([Location::Register(Reg::Rdi)])
-            } else {
-                HashSet::new()
-            }
-        }
+        Instruction::CallQ { arity, .. } => Reg::arg_registers()
+            .into_iter()
+            .take(*arity)
+            .map(Location::Register)
+            .collect(),
         Instruction::RetQ => HashSet::new(),
         Instruction::Jump { .. } => HashSet::new(),
         Instruction::XorQ { src, dest, .. } => &arg_locations(src) | &arg_locations(dest),
@@ -119,14 +231,17 @@ fn read_locations(instr: &LiveInstruction) -> HashSet<Location> {
         Instruction::JumpCC { .. } => HashSet::new(),
         Instruction::AndQ { src, dest } => &arg_locations(src) | &arg_locations(dest),
         Instruction::OrQ { src, dest } => &arg_locations(src) | &arg_locations(dest),
+        Instruction::LeaQ { .. } => HashSet::new(),
     }
 }
 
 #[cfg(test)]
 mod uncover_live_tests {
-    use super::{LiveBlock, LiveInstruction, LiveProg, uncover_live};
-    use asm::{Instruction, Reg, VarProgram};
-    use std::collections::HashSet;
+    use super::{
+        Error, LiveInstruction, LiveProg, Location, read_locations, uncover_live, written_locations,
+    };
+    use asm::{Cc, Instruction, Reg, VarArg, VarProgram};
+    use std::collections::{HashMap, HashSet};
 
     #[test]
     fn uncover_example() {
@@ -142,38 +257,34 @@ mod uncover_live_tests {
             ],
         );
         let result = uncover_live(example).unwrap();
-        let mut expected = LiveProg::new();
-        expected.blocks.push(LiveBlock {
-            label: "start".to_owned(),
-            instrs: vec![
-                LiveInstruction::new(
-                    Instruction::mov(5, "a"),
-                    HashSet::from([]),
-                    HashSet::from(["a".into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::mov(30, "b"),
-                    HashSet::from(["a".into()]),
-                    HashSet::from(["a".into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::mov("a", "c"),
-                    HashSet::from(["a".into()]),
-                    HashSet::from(["c".into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::mov(10, "b"),
-                    HashSet::from(["c".into()]),
-                    HashSet::from(["b".into(), "c".into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::add("b", "c"),
-                    HashSet::from(["b".into(), "c".into()]),
-                    HashSet::new(),
-                ),
-            ],
-        });
-        assert_eq!(result, expected)
+        let expected = vec![
+            (
+                Instruction::mov(5, "a"),
+                HashSet::from([]),
+                HashSet::from(["a".into()]),
+            ),
+            (
+                Instruction::mov(30, "b"),
+                HashSet::from(["a".into()]),
+                HashSet::from(["a".into()]),
+            ),
+            (
+                Instruction::mov("a", "c"),
+                HashSet::from(["a".into()]),
+                HashSet::from(["c".into()]),
+            ),
+            (
+                Instruction::mov(10, "b"),
+                HashSet::from(["c".into()]),
+                HashSet::from(["b".into(), "c".into()]),
+            ),
+            (
+                Instruction::add("b", "c"),
+                HashSet::from(["b".into(), "c".into()]),
+                HashSet::new(),
+            ),
+        ];
+        assert_eq!(live_sets(&result), expected)
     }
 
     #[test]
@@ -197,72 +308,265 @@ mod uncover_live_tests {
             ],
         );
         let result = uncover_live(example).unwrap();
-        let mut expected = LiveProg::new();
-        expected.blocks.push(LiveBlock {
-            label: "start".to_owned(),
-            instrs: vec![
-                LiveInstruction::new(
-                    Instruction::mov(1, "v"),
-                    HashSet::from([Reg::Rsp.into()]),
-                    HashSet::from(["v".into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::mov(42, "w"),
-                    HashSet::from(["v".into(), Reg::Rsp.into()]),
-                    HashSet::from(["v".into(), "w".into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::mov("v", "x"),
-                    HashSet::from(["v".into(), "w".into(), Reg::Rsp.into()]),
-                    HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::add(7, "x"),
-                    HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
-                    HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::mov("x", "y"),
-                    HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
-                    HashSet::from(["w".into(), "x".into(), "y".into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::mov("x", "z"),
-                    HashSet::from(["w".into(), "x".into(), "y".into(), Reg::Rsp.into()]),
-                    HashSet::from(["w".into(), "y".into(), "z".into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::add("w", "z"),
-                    HashSet::from(["w".into(), "y".into(), "z".into(), Reg::Rsp.into()]),
-                    HashSet::from(["y".into(), "z".into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::mov("y", "t"),
-                    HashSet::from(["y".into(), "z".into(), Reg::Rsp.into()]),
-                    HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::neg("t"),
-                    HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
-                    HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::mov("z", Reg::Rax),
-                    HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
-                    HashSet::from([Reg::Rax.into(), "t".into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::add("t", Reg::Rax),
-                    HashSet::from([Reg::Rax.into(), "t".into(), Reg::Rsp.into()]),
-                    HashSet::from([Reg::Rax.into(), Reg::Rsp.into()]),
-                ),
-                LiveInstruction::new(
-                    Instruction::jmp("conclusion"),
-                    HashSet::from([Reg::Rax.into(), Reg::Rsp.into()]),
-                    HashSet::new(),
-                ),
+        let expected = vec![
+            (
+                Instruction::mov(1, "v"),
+                HashSet::from([Reg::Rsp.into()]),
+                HashSet::from(["v".into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::mov(42, "w"),
+                HashSet::from(["v".into(), Reg::Rsp.into()]),
+                HashSet::from(["v".into(), "w".into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::mov("v", "x"),
+                HashSet::from(["v".into(), "w".into(), Reg::Rsp.into()]),
+                HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::add(7, "x"),
+                HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
+                HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::mov("x", "y"),
+                HashSet::from(["w".into(), "x".into(), Reg::Rsp.into()]),
+                HashSet::from(["w".into(), "x".into(), "y".into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::mov("x", "z"),
+                HashSet::from(["w".into(), "x".into(), "y".into(), Reg::Rsp.into()]),
+                HashSet::from(["w".into(), "y".into(), "z".into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::add("w", "z"),
+                HashSet::from(["w".into(), "y".into(), "z".into(), Reg::Rsp.into()]),
+                HashSet::from(["y".into(), "z".into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::mov("y", "t"),
+                HashSet::from(["y".into(), "z".into(), Reg::Rsp.into()]),
+                HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::neg("t"),
+                HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
+                HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::mov("z", Reg::Rax),
+                HashSet::from(["t".into(), "z".into(), Reg::Rsp.into()]),
+                HashSet::from([Reg::Rax.into(), "t".into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::add("t", Reg::Rax),
+                HashSet::from([Reg::Rax.into(), "t".into(), Reg::Rsp.into()]),
+                HashSet::from([Reg::Rax.into(), Reg::Rsp.into()]),
+            ),
+            (
+                Instruction::jmp("conclusion"),
+                HashSet::from([Reg::Rax.into(), Reg::Rsp.into()]),
+                HashSet::new(),
+            ),
+        ];
+        assert_eq!(live_sets(&result), expected)
+    }
+
+    /// Instructions with the locations live before and after them
+    fn live_sets(
+        prog: &LiveProg,
+    ) -> Vec<(Instruction<VarArg>, HashSet<Location>, HashSet<Location>)> {
+        prog.blocks
+            .iter()
+            .flat_map(|block| block.instrs.iter())
+            .map(|instr| {
+                (
+                    instr.instr.clone(),
+                    prog.live_locations(&instr.live_before).cloned().collect(),
+                    prog.live_locations(&instr.live_after).cloned().collect(),
+                )
+            })
+            .collect()
+    }
+
+    /// Linear congruential generator, so the random programs are the same on every run
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, bound: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) as usize) % bound
+        }
+    }
+
+    /// Blocks of random arithmetic and calls, mostly jumping back to earlier blocks
+    fn random_program(rng: &mut Rng) -> VarProgram {
+        let vars = ["a", "b", "c", "d", "e"];
+        let num_blocks = 1 + rng.below(6);
+        let labels: Vec<String> = (0..num_blocks)
+            .map(|ind| match ind {
+                0 => "start".to_owned(),
+                ind => format!("block_{ind}"),
+            })
+            .collect();
+        let mut prog = VarProgram::new();
+        for ind in 0..num_blocks {
+            let mut instrs = vec![];
+            for _ in 0..rng.below(6) {
+                let var = vars[rng.below(vars.len())];
+                let other = vars[rng.below(vars.len())];
+                instrs.push(match rng.below(6) {
+                    0 => Instruction::mov(rng.below(10) as i64, var),
+                    1 => Instruction::mov(other, var),
+                    2 => Instruction::add(other, var),
+                    3 => Instruction::neg(var),
+                    4 => Instruction::mov(var, Reg::Rax),
+                    _ => Instruction::call("read_int", 0),
+                });
+            }
+            // later blocks or the conclusion end the program, earlier ones form loops
+            let target = |rng: &mut Rng| match rng.below(4) {
+                0 => "conclusion".to_owned(),
+                _ => labels[rng.below(num_blocks)].clone(),
+            };
+            if rng.below(2) == 0 {
+                instrs.push(Instruction::CmpQ {
+                    left: vars[rng.below(vars.len())].into(),
+                    right: 0.into(),
+                });
+                instrs.push(Instruction::JumpCC {
+                    cc: Cc::L,
+                    label: target(rng),
+                });
+            }
+            instrs.push(Instruction::jmp(&target(rng)));
+            prog.add_block(&labels[ind], instrs);
+        }
+        prog
+    }
+
+    /// Recomputes the live sets of all instructions until none change
+    fn naive_liveness(prog: &VarProgram) -> Vec<Vec<(HashSet<Location>, HashSet<Location>)>> {
+        let instrs: Vec<Vec<LiveInstruction>> = prog
+            .blocks
+            .iter()
+            .map(|block| {
+                block
+                    .instrs
+                    .iter()
+                    .map(|instr| LiveInstruction::from(instr.clone()))
+                    .collect()
+            })
+            .collect();
+        let blocks: HashMap<&str, usize> = prog
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(ind, block)| (block.label.as_str(), ind))
+            .collect();
+        let mut live: Vec<Vec<(HashSet<Location>, HashSet<Location>)>> = instrs
+            .iter()
+            .map(|block| vec![(HashSet::new(), HashSet::new()); block.len()])
+            .collect();
+        let mut changing = true;
+        while changing {
+            changing = false;
+            for (block_ind, block) in instrs.iter().enumerate() {
+                for (ind, instr) in block.iter().enumerate() {
+                    let live_in = |label: &str| -> HashSet<Location> {
+                        match blocks.get(label) {
+                            Some(target) => live[*target][0].0.clone(),
+                            None => HashSet::from([Reg::Rax.into(), Reg::Rsp.into()]),
+                        }
+                    };
+                    let after = match live[block_ind].get(ind + 1) {
+                        Some((before, _)) => before.clone(),
+                        None => HashSet::new(),
+                    };
+                    let before = match &instr.instr {
+                        Instruction::Jump { label } => live_in(label),
+                        instr_ => {
+                            let mut before =
+                                &(&after - &written_locations(instr)) | &read_locations(instr);
+                            if let Instruction::JumpCC { label, .. } = instr_ {
+                                before.extend(live_in(label));
+                            }
+                            before
+                        }
+                    };
+                    if live[block_ind][ind] != (before.clone(), after.clone()) {
+                        live[block_ind][ind] = (before, after);
+                        changing = true;
+                    }
+                }
+            }
+        }
+        live
+    }
+
+    #[test]
+    fn uncover_random_loops() {
+        let mut rng = Rng(42);
+        for _ in 0..300 {
+            let prog = random_program(&mut rng);
+            let expected = naive_liveness(&prog);
+            let result = live_sets(&uncover_live(prog.clone()).unwrap());
+            for ((instr, before, after), (expected_before, expected_after)) in
+                result.into_iter().zip(expected.into_iter().flatten())
+            {
+                assert_eq!(before, expected_before, "before {instr}\n{prog}");
+                assert_eq!(after, expected_after, "after {instr}\n{prog}");
+            }
+        }
+    }
+
+    #[test]
+    fn uncover_loop_before_exit() {
+        // the loop is listed after the block leaving it, so a single pass in block
+        // order sees the loop before the exit block is known
+        let mut prog = VarProgram::new();
+        prog.add_block(
+            "start",
+            vec![Instruction::mov(1, "a"), Instruction::jmp("loop")],
+        );
+        prog.add_block(
+            "exit",
+            vec![
+                Instruction::mov("a", Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        );
+        prog.add_block(
+            "loop",
+            vec![
+                Instruction::add(1, "b"),
+                Instruction::CmpQ {
+                    left: "b".into(),
+                    right: VarArg::from(10),
+                },
+                Instruction::JumpCC {
+                    cc: Cc::L,
+                    label: "loop".to_owned(),
+                },
+                Instruction::jmp("exit"),
             ],
-        });
-        assert_eq!(result, expected)
+        );
+        let result = live_sets(&uncover_live(prog.clone()).unwrap());
+        let loop_live: HashSet<Location> = HashSet::from(["a".into(), "b".into(), Reg::Rsp.into()]);
+        // the first instruction of the loop and the jump into it
+        assert_eq!(result[4].1, loop_live);
+        assert_eq!(result[1].1, loop_live);
+    }
+
+    #[test]
+    fn uncover_missing_block() {
+        let mut prog = VarProgram::new();
+        prog.add_block("start", vec![Instruction::jmp("nowhere")]);
+        let result = uncover_live(prog);
+        assert!(matches!(result, Err(Error::MissingLiveBefore(label)) if label == "nowhere"));
     }
 }
@@ -0,0 +1,230 @@
+use crate::{
+    colors::{Coloring, RegisterSet},
+    errors::Error,
+    program::{LiveInstruction, LiveProg, Location},
+    uncover_live::written_locations,
+};
+use asm::{Arg, Instruction, Program, Reg};
+use std::collections::HashMap;
+
+/// Checks the homes chosen for variables keep every live value intact
+/// No instruction may overwrite the home of another live variable,
+/// which includes calls overwriting caller saved registers,
+/// and all callee saved registers in the assigned program have to be saved
+/// without stack slots overlapping them
+pub fn verify_homes(
+    prog: &LiveProg,
+    coloring: &Coloring,
+    registers: &RegisterSet,
+    assigned: &Program,
+) -> Result<(), Error> {
+    let homes = registers.coloring_to_assignment(coloring.clone());
+    for instr in prog.blocks.iter().flat_map(|block| block.instrs.iter()) {
+        verify_instr(prog, instr, &homes)?;
+    }
+    verify_callee(assigned)
+}
+
+fn verify_instr(
+    prog: &LiveProg,
+    instr: &LiveInstruction,
+    homes: &HashMap<String, Arg>,
+) -> Result<(), Error> {
+    // the destination of a move may share its home with the source
+    let mov_src = match &instr.instr {
+        Instruction::MovQ { src, .. } => Location::arg_loc(src.clone()),
+        _ => None,
+    };
+    let mut written: Vec<Location> = written_locations(instr).into_iter().collect();
+    written.sort();
+    let mut live: Vec<&Location> = prog.live_locations(&instr.live_after).collect();
+    live.sort();
+    for written in written {
+        let Some(written_home) = home(&written, homes) else {
+            continue;
+        };
+        for live in live.iter() {
+            let Location::Variable(var) = live else {
+                continue;
+            };
+            if **live == written
+                || Some(*live) == mov_src.as_ref()
+                || homes.get(var) != Some(&written_home)
+            {
+                continue;
+            }
+            return Err(match written {
+                Location::Variable(other) => Error::SharedHome {
+                    first: var.clone(),
+                    second: other,
+                    instr: instr.instr.to_string(),
+                },
+                _ => Error::ClobberedHome {
+                    var: var.clone(),
+                    home: written_home.to_string(),
+                    instr: instr.instr.to_string(),
+                },
+            });
+        }
+    }
+    Ok(())
+}
+
+fn home(loc: &Location, homes: &HashMap<String, Arg>) -> Option<Arg> {
+    match loc {
+        Location::Variable(var) => homes.get(var).cloned(),
+        Location::Register(reg) => Some(reg.clone().into()),
+        Location::Stack(offset) => Some(Arg::Deref(Reg::Rbp, *offset)),
+    }
+}
+
+fn verify_callee(prog: &Program) -> Result<(), Error> {
+    if let Some(reg) = prog
+        .used_callee
+        .iter()
+        .find(|reg| matches!(reg, Reg::Rsp | Reg::Rbp))
+    {
+        return Err(Error::SavedFrameRegister(reg.clone()));
+    }
+    // the prelude pushes the saved registers right below the base pointer
+    let saved_space = 8 * prog.used_callee.len() as i64;
+    for instr in prog.blocks.iter().flat_map(|block| block.instrs.iter()) {
+        let mut used = vec![];
+        instr.clone().map_args(|arg| used.push(arg));
+        for arg in used {
+            match arg {
+                Arg::Deref(Reg::Rbp, offset) if offset < 0 && -offset <= saved_space => {
+                    return Err(Error::SlotInCalleeArea {
+                        slot: arg.to_string(),
+                        instr: instr.to_string(),
+                    });
+                }
+                Arg::Register(reg) | Arg::Deref(reg, _)
+                    if Reg::callee_saved().contains(&reg)
+                        && !matches!(reg, Reg::Rsp | Reg::Rbp)
+                        && !prog.used_callee.contains(&reg) =>
+                {
+                    return Err(Error::UnsavedCallee {
+                        reg,
+                        instr: instr.to_string(),
+                    });
+                }
+                _ => (),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod verify_tests {
+    use super::verify_homes;
+    use crate::{
+        assign_homes,
+        colors::{Coloring, RegisterSet},
+        errors::Error,
+        program::LiveProg,
+        uncover_live::uncover_live,
+    };
+    use asm::{Arg, Instruction, Program, Reg, VarProgram};
+
+    fn example() -> VarProgram {
+        let mut prog = VarProgram::new();
+        prog.add_block(
+            "start",
+            vec![
+                Instruction::mov(1, "a"),
+                Instruction::mov(2, "b"),
+                Instruction::mov("a", Reg::Rdi),
+                Instruction::call("print_int", 1),
+                Instruction::add("b", "a"),
+                Instruction::mov("a", Reg::Rax),
+                Instruction::jmp("conclusion"),
+            ],
+        );
+        prog
+    }
+
+    fn assign(homes: [(&str, Reg); 2]) -> (LiveProg, Coloring, Program) {
+        let registers = RegisterSet::default();
+        let prog = uncover_live(example()).unwrap();
+        let mut coloring = registers.empty_coloring();
+        for (var, reg) in homes {
+            let color = coloring.0[&reg.into()];
+            coloring.0.insert(var.into(), color);
+        }
+        let assigned = assign_homes(prog.clone(), coloring.clone(), &registers).unwrap();
+        (prog, coloring, assigned)
+    }
+
+    fn verify(homes: [(&str, Reg); 2]) -> Result<(), Error> {
+        let (prog, coloring, assigned) = assign(homes);
+        verify_homes(&prog, &coloring, &RegisterSet::default(), &assigned)
+    }
+
+    #[test]
+    fn verify_valid() {
+        verify([("a", Reg::Rbx), ("b", Reg::R12)]).unwrap()
+    }
+
+    #[test]
+    fn verify_shared() {
+        let result = verify([("a", Reg::Rbx), ("b", Reg::Rbx)]);
+        assert!(matches!(
+            result,
+            Err(Error::SharedHome { first, second, .. }) if first == "a" && second == "b"
+        ))
+    }
+
+    #[test]
+    fn verify_clobbered() {
+        let result = verify([("a", Reg::Rbx), ("b", Reg::Rcx)]);
+        assert!(matches!(
+            result,
+            Err(Error::ClobberedHome { var, .. }) if var == "b"
+        ))
+    }
+
+    #[test]
+    fn verify_unsaved() {
+        let (prog, coloring, mut assigned) = assign([("a", Reg::Rbx), ("b", Reg::R12)]);
+        assigned.used_callee.remove(&Reg::R12);
+        let result = verify_homes(&prog, &coloring, &RegisterSet::default(), &assigned);
+        assert!(matches!(
+            result,
+            Err(Error::UnsavedCallee { reg: Reg::R12, .. })
+        ))
+    }
+
+    #[test]
+    fn verify_saved_stack_pointer() {
+        let (prog, coloring, mut assigned) = assign([("a", Reg::Rbx), ("b", Reg::R12)]);
+        assigned.used_callee.insert(Reg::Rsp);
+        let result = verify_homes(&prog, &coloring, &RegisterSet::default(), &assigned);
+        assert!(matches!(result, Err(Error::SavedFrameRegister(Reg::Rsp))))
+    }
+
+    #[test]
+    fn verify_slot_in_callee_area() {
+        let registers = RegisterSet::default();
+        let prog = uncover_live(example()).unwrap();
+        let mut coloring = registers.empty_coloring();
+        coloring.0.insert("a".into(), coloring.0[&Reg::Rbx.into()]);
+        coloring.0.insert("b".into(), registers.max_color() + 1);
+        let mut assigned = assign_homes(prog.clone(), coloring.clone(), &registers).unwrap();
+        verify_homes(&prog, &coloring, &registers, &assigned).unwrap();
+        // slots as placed before they were moved below the saved registers
+        for block in assigned.blocks.iter_mut() {
+            for instr in block.instrs.iter_mut() {
+                *instr = instr.clone().map_args(|arg| match arg {
+                    Arg::Deref(Reg::Rbp, offset) => {
+                        Arg::Deref(Reg::Rbp, offset + 8 * assigned.used_callee.len() as i64)
+                    }
+                    arg => arg,
+                });
+            }
+        }
+        let result = verify_homes(&prog, &coloring, &registers, &assigned);
+        assert!(matches!(result, Err(Error::SlotInCalleeArea { .. })))
+    }
+}
@@ -1,7 +1,9 @@
+pub mod partial_eval;
 mod syntax;
 pub mod typecheck;
 mod uniquify;
 
-pub use syntax::{Block, Expression, Program, Statement, Type};
+pub use partial_eval::{ConstEnv, PartialEval};
+pub use syntax::{Block, Expression, ExternDecl, GlobalDecl, Program, Statement, Type};
 pub use typecheck::Typecheck;
-pub use uniquify::Uniquify;
+pub use uniquify::{Renaming, Uniquify};
@@ -0,0 +1,265 @@
+use crate::{Block, Expression, Program, Statement};
+use definitions::{BinaryOperation, UnaryOperation};
+use std::collections::{HashMap, HashSet};
+
+/// Known constant values of variables that are never mutated
+pub struct ConstEnv {
+    constants: HashMap<String, Expression>,
+    mutated: HashSet<String>,
+}
+
+pub trait PartialEval {
+    type Target;
+    fn partial_eval(self, env: &mut ConstEnv) -> Self::Target;
+}
+
+impl ConstEnv {
+    pub fn new(prog: &Program) -> ConstEnv {
+        let mut mutated = HashSet::new();
+        prog.main.collect_mutated(&mut mutated);
+        mutated.extend(prog.globals.iter().map(|global| global.name.clone()));
+        ConstEnv {
+            constants: HashMap::new(),
+            mutated,
+        }
+    }
+}
+
+impl Block {
+    fn collect_mutated(&self, mutated: &mut HashSet<String>) {
+        for stmt in self.stmts.iter() {
+            match stmt {
+                Statement::Set { var, .. } => {
+                    mutated.insert(var.clone());
+                }
+                Statement::If {
+                    then_block,
+                    else_block,
+                    ..
+                } => {
+                    then_block.collect_mutated(mutated);
+                    else_block.collect_mutated(mutated);
+                }
+                Statement::While { while_block, .. } => while_block.collect_mutated(mutated),
+                _ => (),
+            }
+        }
+    }
+}
+
+impl PartialEval for Program {
+    type Target = Program;
+    fn partial_eval(self, env: &mut ConstEnv) -> Program {
+        Program {
+            externs: self.externs,
+            globals: self.globals,
+            main: self.main.partial_eval(env),
+        }
+    }
+}
+
+impl PartialEval for Block {
+    type Target = Block;
+    fn partial_eval(self, env: &mut ConstEnv) -> Block {
+        let mut stmts = vec![];
+        for stmt in self.stmts {
+            stmts.extend(stmt.partial_eval(env));
+        }
+        Block::new(stmts)
+    }
+}
+
+impl PartialEval for Statement {
+    type Target = Vec<Statement>;
+    fn partial_eval(self, env: &mut ConstEnv) -> Vec<Statement> {
+        match self {
+            Statement::Return(exp) => vec![Statement::Return(exp.partial_eval(env))],
+            Statement::Print { kind, arg } => vec![Statement::print(kind, arg.partial_eval(env))],
+            Statement::PrintNewline => vec![Statement::PrintNewline],
+            Statement::Assignment { var, bound } => {
+                let bound = bound.partial_eval(env);
+                if is_constant(&bound) && !env.mutated.contains(&var) {
+                    env.constants.insert(var, bound);
+                    vec![]
+                } else {
+                    vec![Statement::assign(&var, bound)]
+                }
+            }
+            Statement::Set { var, bound } => vec![Statement::set(&var, bound.partial_eval(env))],
+            Statement::If {
+                cond_exp,
+                then_block,
+                else_block,
+            } => match cond_exp.partial_eval(env) {
+                Expression::Bool(true) => then_block.partial_eval(env).stmts,
+                Expression::Bool(false) => else_block.partial_eval(env).stmts,
+                cond => vec![Statement::cond(
+                    cond,
+                    then_block.partial_eval(env),
+                    else_block.partial_eval(env),
+                )],
+            },
+            Statement::While {
+                cond_exp,
+                while_block,
+            } => match cond_exp.partial_eval(env) {
+                Expression::Bool(false) => vec![],
+                cond => vec![Statement::While {
+                    cond_exp: cond,
+                    while_block: while_block.partial_eval(env),
+                }],
+            },
+        }
+    }
+}
+
+impl PartialEval for Expression {
+    type Target = Expression;
+    fn partial_eval(self, env: &mut ConstEnv) -> Expression {
+        match self {
+            Expression::Variable(ref v) => env.constants.get(v).cloned().unwrap_or(self),
+            Expression::BinOp { fst, op, snd } => {
+                let fst = fst.partial_eval(env);
+                let snd = snd.partial_eval(env);
+                match (op, fst, snd) {
+                    (BinaryOperation::Add, Expression::Literal(i), Expression::Literal(j)) => {
+                        Expression::Literal(i.wrapping_add(j))
+                    }
+                    (BinaryOperation::Sub, Expression::Literal(i), Expression::Literal(j)) => {
+                        Expression::Literal(i.wrapping_sub(j))
+                    }
+                    (BinaryOperation::Add, exp, Expression::Literal(0))
+                    | (BinaryOperation::Add, Expression::Literal(0), exp)
+                    | (BinaryOperation::Sub, exp, Expression::Literal(0)) => exp,
+                    (BinaryOperation::And, Expression::Bool(b), exp)
+                    | (BinaryOperation::And, exp, Expression::Bool(b))
+                        if b || is_pure(&exp) =>
+                    {
+                        if b {
+                            exp
+                        } else {
+                            Expression::Bool(false)
+                        }
+                    }
+                    (BinaryOperation::Or, Expression::Bool(b), exp)
+                    | (BinaryOperation::Or, exp, Expression::Bool(b))
+                        if !b || is_pure(&exp) =>
+                    {
+                        if b {
+                            Expression::Bool(true)
+                        } else {
+                            exp
+                        }
+                    }
+                    (BinaryOperation::Concat, Expression::Str(s1), Expression::Str(s2)) => {
+                        Expression::Str(s1 + &s2)
+                    }
+                    (op, fst, snd) => Expression::bin(fst, op, snd),
+                }
+            }
+            Expression::UnOp { arg, op } => match (op, arg.partial_eval(env)) {
+                (UnaryOperation::Neg, Expression::Literal(i)) => {
+                    Expression::Literal(i.wrapping_neg())
+                }
+                (UnaryOperation::Not, Expression::Bool(b)) => Expression::Bool(!b),
+                (UnaryOperation::Length, Expression::Str(s)) => Expression::Literal(s.len() as i64),
+                (op, arg) => Expression::un(arg, op),
+            },
+            Expression::Cmp { left, cmp, right } => {
+                match (left.partial_eval(env), right.partial_eval(env)) {
+                    (Expression::Literal(i), Expression::Literal(j)) => {
+                        Expression::Bool(cmp.eval(i, j))
+                    }
+                    (left, right) => Expression::cmp(left, cmp, right),
+                }
+            }
+            Expression::Call { name, args } => Expression::Call {
+                name,
+                args: args.into_iter().map(|arg| arg.partial_eval(env)).collect(),
+            },
+            Expression::Literal(_)
+            | Expression::Bool(_)
+            | Expression::Str(_)
+            | Expression::ReadInt
+            | Expression::ReadBool
+            | Expression::TryReadInt => self,
+        }
+    }
+}
+
+/// Expressions that can be dropped without losing input or output
+fn is_pure(exp: &Expression) -> bool {
+    match exp {
+        Expression::ReadInt
+        | Expression::ReadBool
+        | Expression::TryReadInt
+        | Expression::Call { .. } => false,
+        Expression::Literal(_)
+        | Expression::Bool(_)
+        | Expression::Str(_)
+        | Expression::Variable(_) => true,
+        Expression::BinOp { fst, snd, .. } => is_pure(fst) && is_pure(snd),
+        Expression::UnOp { arg, .. } => is_pure(arg),
+        Expression::Cmp { left, right, .. } => is_pure(left) && is_pure(right),
+    }
+}
+
+fn is_constant(exp: &Expression) -> bool {
+    matches!(
+        exp,
+        Expression::Literal(_) | Expression::Bool(_) | Expression::Str(_)
+    )
+}
+
+#[cfg(test)]
+mod partial_eval_tests {
+    use super::{ConstEnv, PartialEval};
+    use crate::{Block, Expression, Program, Statement};
+    use definitions::{BinaryOperation, Comparator, PrintKind};
+
+    #[test]
+    fn fold_constant_let() {
+        let prog = Program::new(vec![
+            Statement::assign(
+                "x",
+                Expression::bin(
+                    Expression::lit(40),
+                    BinaryOperation::Add,
+                    Expression::lit(2),
+                ),
+            ),
+            Statement::print(
+                PrintKind::Int,
+                Expression::bin(
+                    Expression::var("x"),
+                    BinaryOperation::Add,
+                    Expression::lit(1),
+                ),
+            ),
+        ]);
+        let result = prog.clone().partial_eval(&mut ConstEnv::new(&prog));
+        let expected = Program::new(vec![Statement::print(PrintKind::Int, Expression::lit(43))]);
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn fold_mutated_if() {
+        let prog = Program::new(vec![
+            Statement::assign("x", Expression::lit(1)),
+            Statement::assign("y", Expression::lit(2)),
+            Statement::cond(
+                Expression::cmp(Expression::var("y"), Comparator::Lt, Expression::lit(3)),
+                Block::new(vec![Statement::set("x", Expression::lit(5))]),
+                Block::new(vec![]),
+            ),
+            Statement::Return(Expression::var("x")),
+        ]);
+        let result = prog.clone().partial_eval(&mut ConstEnv::new(&prog));
+        let expected = Program::new(vec![
+            Statement::assign("x", Expression::lit(1)),
+            Statement::set("x", Expression::lit(5)),
+            Statement::Return(Expression::var("x")),
+        ]);
+        assert_eq!(result, expected)
+    }
+}
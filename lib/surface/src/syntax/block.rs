@@ -35,7 +35,11 @@ impl SubstVar for Block {
 
 impl From<Block> for Program {
     fn from(b: Block) -> Program {
-        Program { main: b }
+        Program {
+            externs: vec![],
+            globals: vec![],
+            main: b,
+        }
     }
 }
 
@@ -1,5 +1,5 @@
 use definitions::{
-    BinaryOperation, Comparator, READ_INT_CALL, UnaryOperation,
+    BinaryOperation, Comparator, READ_BOOL_CALL, READ_INT_CALL, TRY_READ_INT_CALL, UnaryOperation,
     traits::{SubstVar, UsedVars},
 };
 use std::{collections::HashSet, fmt};
@@ -8,8 +8,11 @@ use std::{collections::HashSet, fmt};
 pub enum Expression {
     Literal(i64),
     Bool(bool),
+    Str(String),
     Variable(String),
     ReadInt,
+    ReadBool,
+    TryReadInt,
     BinOp {
         fst: Box<Expression>,
         op: BinaryOperation,
@@ -24,6 +27,10 @@ pub enum Expression {
         cmp: Comparator,
         right: Box<Expression>,
     },
+    Call {
+        name: String,
+        args: Vec<Expression>,
+    },
 }
 
 impl Expression {
@@ -35,6 +42,10 @@ impl Expression {
         Expression::Bool(b)
     }
 
+    pub fn str(s: &str) -> Expression {
+        Expression::Str(s.to_owned())
+    }
+
     pub fn var(v: &str) -> Expression {
         Expression::Variable(v.to_owned())
     }
@@ -54,6 +65,29 @@ impl Expression {
         }
     }
 
+    /// The value of literals, booleans and negated literals
+    pub fn as_constant(&self) -> Option<i64> {
+        match self {
+            Expression::Literal(i) => Some(*i),
+            Expression::Bool(b) => Some(*b as i64),
+            Expression::UnOp {
+                arg,
+                op: UnaryOperation::Neg,
+            } => match **arg {
+                Expression::Literal(i) => Some(-i),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn call(name: &str, args: Vec<Expression>) -> Expression {
+        Expression::Call {
+            name: name.to_owned(),
+            args,
+        }
+    }
+
     pub fn cmp(left: Expression, cmp: Comparator, right: Expression) -> Expression {
         Expression::Cmp {
             left: Box::new(left),
@@ -68,11 +102,13 @@ impl UsedVars for Expression {
         match self {
             Expression::Literal(_) => HashSet::new(),
             Expression::Bool(_) => HashSet::new(),
+            Expression::Str(_) => HashSet::new(),
             Expression::Variable(v) => HashSet::from([v.clone()]),
-            Expression::ReadInt => HashSet::new(),
+            Expression::ReadInt | Expression::ReadBool | Expression::TryReadInt => HashSet::new(),
             Expression::BinOp { fst, snd, .. } => &fst.used_vars() | &snd.used_vars(),
             Expression::UnOp { arg, .. } => arg.used_vars(),
             Expression::Cmp { left, right, .. } => &left.used_vars() | &right.used_vars(),
+            Expression::Call { args, .. } => args.iter().flat_map(|arg| arg.used_vars()).collect(),
         }
     }
 }
@@ -82,6 +118,7 @@ impl SubstVar for Expression {
         match self {
             Expression::Literal(_) => self,
             Expression::Bool(_) => self,
+            Expression::Str(_) => self,
             Expression::Variable(ref v) => {
                 if v == old {
                     Expression::var(new)
@@ -89,7 +126,7 @@ impl SubstVar for Expression {
                     self
                 }
             }
-            Expression::ReadInt => self,
+            Expression::ReadInt | Expression::ReadBool | Expression::TryReadInt => self,
             Expression::BinOp { fst, op, snd } => {
                 let fst_subst = fst.subst_var(old, new);
                 let snd_subst = snd.subst_var(old, new);
@@ -99,6 +136,13 @@ impl SubstVar for Expression {
             Expression::Cmp { left, cmp, right } => {
                 Expression::cmp(left.subst_var(old, new), cmp, right.subst_var(old, new))
             }
+            Expression::Call { name, args } => Expression::Call {
+                name,
+                args: args
+                    .into_iter()
+                    .map(|arg| arg.subst_var(old, new))
+                    .collect(),
+            },
         }
     }
 }
@@ -108,11 +152,42 @@ impl fmt::Display for Expression {
         match self {
             Expression::Literal(lit) => write!(f, "{lit}"),
             Expression::Bool(b) => write!(f, "{b}"),
+            Expression::Str(s) => write!(f, "\"{}\"", escape_str(s)),
             Expression::Variable(v) => f.write_str(v),
             Expression::ReadInt => f.write_str(READ_INT_CALL),
+            Expression::ReadBool => f.write_str(READ_BOOL_CALL),
+            Expression::TryReadInt => f.write_str(TRY_READ_INT_CALL),
             Expression::BinOp { fst, op, snd } => write!(f, "{fst} {op} {snd}"),
+            Expression::UnOp {
+                arg,
+                op: UnaryOperation::Length,
+            } => write!(f, "{}({arg})", UnaryOperation::Length),
             Expression::UnOp { arg, op } => write!(f, "{op}{arg}"),
             Expression::Cmp { left, cmp, right } => write!(f, "{left}{cmp}{right}"),
+            Expression::Call { name, args } => write!(
+                f,
+                "{name}({})",
+                args.iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+fn escape_str(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            c => escaped.push(c),
         }
     }
+    escaped
 }
@@ -0,0 +1,42 @@
+use super::Type;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternDecl {
+    pub name: String,
+    pub args: Vec<Type>,
+    pub ret: Type,
+}
+
+impl ExternDecl {
+    pub fn new(name: &str, args: Vec<Type>, ret: Type) -> ExternDecl {
+        ExternDecl {
+            name: name.to_owned(),
+            args,
+            ret,
+        }
+    }
+
+    pub fn ty(&self) -> Type {
+        Type::Function {
+            args: self.args.clone(),
+            ret: Box::new(self.ret.clone()),
+        }
+    }
+}
+
+impl fmt::Display for ExternDecl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "extern fn {}({}) -> {};",
+            self.name,
+            self.args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.ret
+        )
+    }
+}
@@ -0,0 +1,25 @@
+use super::{Expression, Type};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalDecl {
+    pub name: String,
+    pub ty: Type,
+    pub init: Expression,
+}
+
+impl GlobalDecl {
+    pub fn new(name: &str, ty: Type, init: Expression) -> GlobalDecl {
+        GlobalDecl {
+            name: name.to_owned(),
+            ty,
+            init,
+        }
+    }
+}
+
+impl fmt::Display for GlobalDecl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "global {}: {} = {};", self.name, self.ty, self.init)
+    }
+}
@@ -1,11 +1,15 @@
 mod block;
 mod expression;
+mod extern_decl;
+mod global_decl;
 mod program;
 mod statement;
 mod types;
 
 pub use block::Block;
 pub use expression::Expression;
+pub use extern_decl::ExternDecl;
+pub use global_decl::GlobalDecl;
 pub use program::Program;
 pub use statement::Statement;
 pub use types::Type;
@@ -1,15 +1,19 @@
-use super::{Block, Statement};
+use super::{Block, ExternDecl, GlobalDecl, Statement};
 use definitions::traits::UsedVars;
 use std::{collections::HashSet, fmt};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Program {
+    pub externs: Vec<ExternDecl>,
+    pub globals: Vec<GlobalDecl>,
     pub main: Block,
 }
 
 impl Program {
     pub fn new(stmts: Vec<Statement>) -> Program {
         Program {
+            externs: vec![],
+            globals: vec![],
             main: Block { stmts },
         }
     }
@@ -17,12 +21,20 @@ impl Program {
 
 impl UsedVars for Program {
     fn used_vars(&self) -> HashSet<String> {
-        self.main.used_vars()
+        let mut used = self.main.used_vars();
+        used.extend(self.globals.iter().map(|global| global.name.clone()));
+        used
     }
 }
 
 impl fmt::Display for Program {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for decl in self.externs.iter() {
+            writeln!(f, "{decl}")?;
+        }
+        for global in self.globals.iter() {
+            writeln!(f, "{global}")?;
+        }
         self.main.fmt(f)
     }
 }
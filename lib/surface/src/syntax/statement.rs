@@ -1,6 +1,6 @@
 use super::{Block, expression::Expression};
 use definitions::{
-    PRINT_CALL, RETURN_CALL,
+    PRINT_NEWLINE_CALL, PrintKind, RETURN_CALL,
     traits::{SubstVar, UsedVars},
 };
 use std::{collections::HashSet, fmt};
@@ -8,7 +8,11 @@ use std::{collections::HashSet, fmt};
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Statement {
     Return(Expression),
-    Print(Expression),
+    Print {
+        kind: PrintKind,
+        arg: Expression,
+    },
+    PrintNewline,
     Assignment {
         var: String,
         bound: Expression,
@@ -29,6 +33,10 @@ pub enum Statement {
 }
 
 impl Statement {
+    pub fn print(kind: PrintKind, arg: Expression) -> Statement {
+        Statement::Print { kind, arg }
+    }
+
     pub fn assign(var: &str, bound: Expression) -> Statement {
         Statement::Assignment {
             var: var.to_owned(),
@@ -56,7 +64,8 @@ impl UsedVars for Statement {
     fn used_vars(&self) -> HashSet<String> {
         match self {
             Statement::Return(exp) => exp.used_vars(),
-            Statement::Print(exp) => exp.used_vars(),
+            Statement::Print { arg, .. } => arg.used_vars(),
+            Statement::PrintNewline => HashSet::new(),
             Statement::Assignment { var, bound } => {
                 &HashSet::from([var.clone()]) | &bound.used_vars()
             }
@@ -78,7 +87,8 @@ impl SubstVar for Statement {
     fn subst_var(self, old: &str, new: &str) -> Statement {
         match self {
             Statement::Return(exp) => Statement::Return(exp.subst_var(old, new)),
-            Statement::Print(exp) => Statement::Print(exp.subst_var(old, new)),
+            Statement::Print { kind, arg } => Statement::print(kind, arg.subst_var(old, new)),
+            Statement::PrintNewline => Statement::PrintNewline,
             Statement::Assignment { var, bound } => {
                 let bound_subst = bound.subst_var(old, new);
                 Statement::Assignment {
@@ -117,7 +127,8 @@ impl fmt::Display for Statement {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Statement::Return(exp) => write!(f, "{RETURN_CALL}({exp});"),
-            Statement::Print(exp) => write!(f, "{PRINT_CALL}({exp});"),
+            Statement::Print { kind, arg } => write!(f, "{kind}({arg});"),
+            Statement::PrintNewline => write!(f, "{PRINT_NEWLINE_CALL}();"),
             Statement::Assignment { var, bound } => write!(f, "let {var} = {bound};"),
             Statement::Set { var, bound } => write!(f, "set {var} = {bound};"),
             Statement::If {
@@ -4,7 +4,9 @@ use std::fmt;
 pub enum Type {
     Integer,
     Bool,
+    String,
     Unit,
+    Function { args: Vec<Type>, ret: Box<Type> },
 }
 
 impl fmt::Display for Type {
@@ -12,7 +14,16 @@ impl fmt::Display for Type {
         match self {
             Type::Integer => f.write_str("Int"),
             Type::Bool => f.write_str("Bool"),
+            Type::String => f.write_str("String"),
             Type::Unit => f.write_str("()"),
+            Type::Function { args, ret } => write!(
+                f,
+                "fn({}) -> {ret}",
+                args.iter()
+                    .map(|arg| arg.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
         }
     }
 }
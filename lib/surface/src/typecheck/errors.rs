@@ -4,8 +4,20 @@ use std::fmt;
 #[derive(Debug)]
 pub enum Error {
     FreeVar(String),
-    TypeMismatch { fst: Type, snd: Type },
+    TypeMismatch {
+        fst: Type,
+        snd: Type,
+    },
     EmptyBlock,
+    NotPrintable(Type),
+    FunctionAsValue(String),
+    NonConstantGlobal(String),
+    NotAFunction(String),
+    ArityMismatch {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
 }
 
 impl Error {
@@ -20,6 +32,20 @@ impl fmt::Display for Error {
             Error::FreeVar(v) => write!(f, "Free variable {v}"),
             Error::TypeMismatch { fst, snd } => write!(f, "Type mismatch {fst} != {snd}"),
             Error::EmptyBlock => write!(f, "Cannot have block with no expressions"),
+            Error::NotPrintable(ty) => write!(f, "Cannot print values of type {ty}"),
+            Error::FunctionAsValue(name) => write!(f, "Function {name} can only be called"),
+            Error::NonConstantGlobal(name) => {
+                write!(f, "Global {name} must be initialized with a literal")
+            }
+            Error::NotAFunction(name) => write!(f, "{name} is not a function"),
+            Error::ArityMismatch {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Function {name} expects {expected} arguments, found {found}"
+            ),
         }
     }
 }
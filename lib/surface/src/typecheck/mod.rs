@@ -1,25 +1,38 @@
 use crate::{Block, Expression, Program, Statement, Type};
-use definitions::{BinaryOperation, UnaryOperation};
+use definitions::{BinaryOperation, PrintKind, UnaryOperation};
 use std::collections::HashMap;
 
 mod errors;
 pub use errors::Error;
 
 pub trait Typecheck {
-    fn check(&self, var_types: &mut HashMap<String, Type>) -> Result<Type, Error>;
+    fn check(&mut self, var_types: &mut HashMap<String, Type>) -> Result<Type, Error>;
 }
 
 impl Typecheck for Program {
-    fn check(&self, var_types: &mut HashMap<String, Type>) -> Result<Type, Error> {
+    fn check(&mut self, var_types: &mut HashMap<String, Type>) -> Result<Type, Error> {
+        for decl in self.externs.iter() {
+            var_types.insert(decl.name.clone(), decl.ty());
+        }
+        for global in self.globals.iter_mut() {
+            if global.init.as_constant().is_none() {
+                return Err(Error::NonConstantGlobal(global.name.clone()));
+            }
+            let init_ty = global.init.check(var_types)?;
+            if init_ty != global.ty {
+                return Err(Error::mismatch(init_ty, global.ty.clone()));
+            }
+            var_types.insert(global.name.clone(), global.ty.clone());
+        }
         self.main.check(var_types)?;
         Ok(Type::Unit)
     }
 }
 
 impl Typecheck for Block {
-    fn check(&self, var_types: &mut HashMap<String, Type>) -> Result<Type, Error> {
+    fn check(&mut self, var_types: &mut HashMap<String, Type>) -> Result<Type, Error> {
         let mut ret_ty = Type::Unit;
-        for stmt in self.stmts.iter() {
+        for stmt in self.stmts.iter_mut() {
             ret_ty = stmt.check(var_types)?;
         }
         Ok(ret_ty)
@@ -27,17 +40,27 @@ impl Typecheck for Block {
 }
 
 impl Typecheck for Statement {
-    fn check(&self, var_types: &mut HashMap<String, Type>) -> Result<Type, Error> {
+    fn check(&mut self, var_types: &mut HashMap<String, Type>) -> Result<Type, Error> {
         match self {
             Statement::Return(exp) => exp.check(var_types),
-            Statement::Print(exp) => {
-                let exp_ty = exp.check(var_types)?;
-                if exp_ty == Type::Integer {
+            Statement::Print { kind, arg } => {
+                let arg_ty = arg.check(var_types)?;
+                if *kind == PrintKind::Any {
+                    *kind = print_kind(&arg_ty)?;
+                }
+                let expected = match kind {
+                    PrintKind::Int => Type::Integer,
+                    PrintKind::Bool => Type::Bool,
+                    PrintKind::String | PrintKind::Line => Type::String,
+                    PrintKind::Any => return Err(Error::NotPrintable(arg_ty)),
+                };
+                if arg_ty == expected {
                     Ok(Type::Unit)
                 } else {
-                    Err(Error::mismatch(exp_ty, Type::Integer))
+                    Err(Error::mismatch(arg_ty, expected))
                 }
             }
+            Statement::PrintNewline => Ok(Type::Unit),
             Statement::Assignment { var, bound } => {
                 let bound_ty = bound.check(var_types)?;
                 var_types.insert(var.clone(), bound_ty);
@@ -86,13 +109,29 @@ impl Typecheck for Statement {
     }
 }
 
+/// Selects the print builtin for `print(e)` from the static type of `e`
+fn print_kind(ty: &Type) -> Result<PrintKind, Error> {
+    match ty {
+        Type::Integer => Ok(PrintKind::Int),
+        Type::Bool => Ok(PrintKind::Bool),
+        Type::String => Ok(PrintKind::String),
+        Type::Unit | Type::Function { .. } => Err(Error::NotPrintable(ty.clone())),
+    }
+}
+
 impl Typecheck for Expression {
-    fn check(&self, var_types: &mut HashMap<String, Type>) -> Result<Type, Error> {
+    fn check(&mut self, var_types: &mut HashMap<String, Type>) -> Result<Type, Error> {
         match self {
             Expression::Literal(_) => Ok(Type::Integer),
             Expression::Bool(_) => Ok(Type::Bool),
-            Expression::Variable(v) => var_types.get(v).cloned().ok_or(Error::FreeVar(v.clone())),
+            Expression::Str(_) => Ok(Type::String),
+            Expression::Variable(v) => match var_types.get(v) {
+                None => Err(Error::FreeVar(v.clone())),
+                Some(Type::Function { .. }) => Err(Error::FunctionAsValue(v.clone())),
+                Some(ty) => Ok(ty.clone()),
+            },
             Expression::ReadInt => Ok(Type::Integer),
+            Expression::ReadBool | Expression::TryReadInt => Ok(Type::Bool),
             Expression::BinOp { fst, op, snd } => {
                 let fst_ty = fst.check(var_types)?;
                 let snd_ty = snd.check(var_types)?;
@@ -114,6 +153,13 @@ impl Typecheck for Expression {
                             Err(Error::mismatch(fst_ty, Type::Bool))
                         }
                     }
+                    BinaryOperation::Concat => {
+                        if fst_ty == Type::String {
+                            Ok(Type::String)
+                        } else {
+                            Err(Error::mismatch(fst_ty, Type::String))
+                        }
+                    }
                 }
             }
             Expression::UnOp { arg, op } => {
@@ -133,6 +179,13 @@ impl Typecheck for Expression {
                             Err(Error::mismatch(arg_ty, Type::Bool))
                         }
                     }
+                    UnaryOperation::Length => {
+                        if arg_ty == Type::String {
+                            Ok(Type::Integer)
+                        } else {
+                            Err(Error::mismatch(arg_ty, Type::String))
+                        }
+                    }
                 }
             }
             Expression::Cmp { left, right, .. } => {
@@ -146,6 +199,57 @@ impl Typecheck for Expression {
                 }
                 Ok(Type::Bool)
             }
+            Expression::Call { name, args } => {
+                let Some(Type::Function { args: arg_tys, ret }) = var_types.get(name).cloned()
+                else {
+                    return Err(Error::NotAFunction(name.clone()));
+                };
+                if args.len() != arg_tys.len() {
+                    return Err(Error::ArityMismatch {
+                        name: name.clone(),
+                        expected: arg_tys.len(),
+                        found: args.len(),
+                    });
+                }
+                for (arg, expected) in args.iter_mut().zip(arg_tys) {
+                    let arg_ty = arg.check(var_types)?;
+                    if arg_ty != expected {
+                        return Err(Error::mismatch(arg_ty, expected));
+                    }
+                }
+                Ok(*ret)
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod typecheck_tests {
+    use super::Typecheck;
+    use crate::{Expression, Program, Statement};
+    use definitions::{Comparator, PrintKind};
+
+    #[test]
+    fn check_print_dispatch() {
+        let mut result = Program::new(vec![
+            Statement::assign(
+                "b",
+                Expression::cmp(Expression::lit(1), Comparator::Lt, Expression::lit(2)),
+            ),
+            Statement::print(PrintKind::Any, Expression::var("b")),
+            Statement::print(PrintKind::Any, Expression::lit(3)),
+            Statement::print(PrintKind::Any, Expression::str("s")),
+        ]);
+        result.check(&mut Default::default()).unwrap();
+        let expected = Program::new(vec![
+            Statement::assign(
+                "b",
+                Expression::cmp(Expression::lit(1), Comparator::Lt, Expression::lit(2)),
+            ),
+            Statement::print(PrintKind::Bool, Expression::var("b")),
+            Statement::print(PrintKind::Int, Expression::lit(3)),
+            Statement::print(PrintKind::String, Expression::str("s")),
+        ]);
+        assert_eq!(result, expected)
+    }
+}
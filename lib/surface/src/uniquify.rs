@@ -1,23 +1,40 @@
 use crate::{Block, Expression, Program, Statement};
 use definitions::traits::fresh_var;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// New names of the variables in scope and every name given out so far
+#[derive(Debug, Default)]
+pub struct Renaming {
+    pub substitutions: HashMap<String, String>,
+    pub used_vars: HashSet<String>,
+}
 
 pub trait Uniquify {
-    fn uniquify(self, substitutions: &mut HashMap<String, String>) -> Self;
+    fn uniquify(self, renaming: &mut Renaming) -> Self;
 }
 
 impl Uniquify for Program {
-    fn uniquify(self, substitutions: &mut HashMap<String, String>) -> Program {
-        let new_main = self.main.uniquify(substitutions);
-        new_main.into()
+    fn uniquify(self, renaming: &mut Renaming) -> Program {
+        for global in self.globals.iter() {
+            renaming
+                .substitutions
+                .insert(global.name.clone(), global.name.clone());
+            renaming.used_vars.insert(global.name.clone());
+        }
+        let new_main = self.main.uniquify(renaming);
+        Program {
+            externs: self.externs,
+            globals: self.globals,
+            main: new_main,
+        }
     }
 }
 
 impl Uniquify for Block {
-    fn uniquify(self, substitutions: &mut HashMap<String, String>) -> Block {
+    fn uniquify(self, renaming: &mut Renaming) -> Block {
         let mut new_stmts = vec![];
         for stmt in self.stmts {
-            let new_stmt = stmt.uniquify(substitutions);
+            let new_stmt = stmt.uniquify(renaming);
             new_stmts.push(new_stmt);
         }
         Block::new(new_stmts)
@@ -25,20 +42,23 @@ impl Uniquify for Block {
 }
 
 impl Uniquify for Statement {
-    fn uniquify(self, substitutions: &mut HashMap<String, String>) -> Statement {
+    fn uniquify(self, renaming: &mut Renaming) -> Statement {
         match self {
-            Statement::Return(exp) => Statement::Return(exp.uniquify(substitutions)),
-            Statement::Print(exp) => Statement::Print(exp.uniquify(substitutions)),
+            Statement::Return(exp) => Statement::Return(exp.uniquify(renaming)),
+            Statement::Print { kind, arg } => Statement::print(kind, arg.uniquify(renaming)),
+            Statement::PrintNewline => Statement::PrintNewline,
             Statement::Assignment { var, bound } => {
-                let new_bound = bound.uniquify(substitutions);
-                let new_var = fresh_var(&substitutions.values().cloned().collect());
-                substitutions.insert(var, new_var.clone());
+                let new_bound = bound.uniquify(renaming);
+                let new_var = fresh_var(&renaming.used_vars);
+                renaming.used_vars.insert(new_var.clone());
+                renaming.substitutions.insert(var, new_var.clone());
                 Statement::assign(&new_var, new_bound)
             }
             Statement::Set { var, bound } => {
-                let new_bound = bound.uniquify(substitutions);
+                let new_bound = bound.uniquify(renaming);
+                let new_var = renaming.substitutions.get(&var).cloned().unwrap_or(var);
                 Statement::Set {
-                    var,
+                    var: new_var,
                     bound: new_bound,
                 }
             }
@@ -47,17 +67,17 @@ impl Uniquify for Statement {
                 then_block,
                 else_block,
             } => {
-                let new_cond = cond_exp.uniquify(substitutions);
-                let new_then = then_block.uniquify(substitutions);
-                let new_else = else_block.uniquify(substitutions);
+                let new_cond = cond_exp.uniquify(renaming);
+                let new_then = then_block.uniquify(renaming);
+                let new_else = else_block.uniquify(renaming);
                 Statement::cond(new_cond, new_then, new_else)
             }
             Statement::While {
                 cond_exp,
                 while_block,
             } => {
-                let new_cond = cond_exp.uniquify(substitutions);
-                let new_while = while_block.uniquify(substitutions);
+                let new_cond = cond_exp.uniquify(renaming);
+                let new_while = while_block.uniquify(renaming);
                 Statement::While {
                     cond_exp: new_cond,
                     while_block: new_while,
@@ -68,29 +88,34 @@ impl Uniquify for Statement {
 }
 
 impl Uniquify for Expression {
-    fn uniquify(self, substitutions: &mut HashMap<String, String>) -> Expression {
+    fn uniquify(self, renaming: &mut Renaming) -> Expression {
         match self {
             Expression::Literal(_) => self,
             Expression::Bool(_) => self,
+            Expression::Str(_) => self,
             Expression::Variable(ref v) => {
-                if let Some(v1) = substitutions.get(v) {
+                if let Some(v1) = renaming.substitutions.get(v) {
                     Expression::Variable(v1.clone())
                 } else {
                     self
                 }
             }
-            Expression::ReadInt => self,
-            Expression::UnOp { arg, op } => Expression::un(arg.uniquify(substitutions), op),
+            Expression::ReadInt | Expression::ReadBool | Expression::TryReadInt => self,
+            Expression::UnOp { arg, op } => Expression::un(arg.uniquify(renaming), op),
             Expression::BinOp { fst, op, snd } => {
-                let fst_unique = fst.uniquify(substitutions);
-                let snd_unique = snd.uniquify(substitutions);
+                let fst_unique = fst.uniquify(renaming);
+                let snd_unique = snd.uniquify(renaming);
                 Expression::bin(fst_unique, op, snd_unique)
             }
             Expression::Cmp { left, cmp, right } => {
-                let left_unique = left.uniquify(substitutions);
-                let right_unique = right.uniquify(substitutions);
+                let left_unique = left.uniquify(renaming);
+                let right_unique = right.uniquify(renaming);
                 Expression::cmp(left_unique, cmp, right_unique)
             }
+            Expression::Call { name, args } => Expression::Call {
+                name,
+                args: args.into_iter().map(|arg| arg.uniquify(renaming)).collect(),
+            },
         }
     }
 }
@@ -162,4 +187,50 @@ mod uniquify_tests {
         ]);
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn uniquify_set_after_let() {
+        let result = Program::new(vec![
+            Statement::assign("x", Expression::lit(4)),
+            Statement::set(
+                "x",
+                Expression::bin(
+                    Expression::var("x"),
+                    BinaryOperation::Add,
+                    Expression::lit(1),
+                ),
+            ),
+        ])
+        .uniquify(&mut Default::default());
+        let expected = Program::new(vec![
+            Statement::assign("x0", Expression::lit(4)),
+            Statement::set(
+                "x0",
+                Expression::bin(
+                    Expression::var("x0"),
+                    BinaryOperation::Add,
+                    Expression::lit(1),
+                ),
+            ),
+        ]);
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn uniquify_no_reuse_after_shadow() {
+        let result = Program::new(vec![
+            Statement::assign("x", Expression::lit(1)),
+            Statement::assign("x", Expression::lit(2)),
+            Statement::assign("y", Expression::lit(3)),
+            Statement::Return(Expression::var("y")),
+        ])
+        .uniquify(&mut Default::default());
+        let expected = Program::new(vec![
+            Statement::assign("x0", Expression::lit(1)),
+            Statement::assign("x1", Expression::lit(2)),
+            Statement::assign("x2", Expression::lit(3)),
+            Statement::Return(Expression::var("x2")),
+        ]);
+        assert_eq!(result, expected)
+    }
 }
@@ -0,0 +1,18 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    NonConstantGlobal(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NonConstantGlobal(name) => {
+                write!(f, "Global {name} has non-constant initializer")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
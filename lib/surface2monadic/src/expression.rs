@@ -8,8 +8,11 @@ impl RemoveComplexOperands for surface::Expression {
         match self {
             surface::Expression::Literal(i) => (vec![], monadic::Atom::Integer(i).into()),
             surface::Expression::Bool(b) => (vec![], monadic::Atom::Bool(b).into()),
+            surface::Expression::Str(s) => (vec![], monadic::Expression::Str(s)),
             surface::Expression::Variable(v) => (vec![], monadic::Atom::Variable(v).into()),
             surface::Expression::ReadInt => (vec![], monadic::Expression::ReadInt),
+            surface::Expression::ReadBool => (vec![], monadic::Expression::ReadBool),
+            surface::Expression::TryReadInt => (vec![], monadic::Expression::TryReadInt),
 
             surface::Expression::BinOp { fst, op, snd } => {
                 let (fst_exps, fst_last) = fst.remove_complex_operands(used_vars);
@@ -64,6 +67,29 @@ impl RemoveComplexOperands for surface::Expression {
                 };
                 (exps, monadic::Expression::cmp(left_atm, cmp, right_atm))
             }
+            surface::Expression::Call { name, args } => {
+                let mut exps = vec![];
+                let mut arg_atms = Vec::with_capacity(args.len());
+                for arg in args {
+                    let (arg_exps, arg_last) = arg.remove_complex_operands(used_vars);
+                    exps.extend(arg_exps);
+                    let arg_atm = if let monadic::Expression::Atm(atm) = arg_last {
+                        atm
+                    } else {
+                        let (assignment, atm) = exp_to_atm(arg_last, used_vars, false);
+                        exps.push(assignment);
+                        atm
+                    };
+                    arg_atms.push(arg_atm);
+                }
+                (
+                    exps,
+                    monadic::Expression::Call {
+                        name,
+                        args: arg_atms,
+                    },
+                )
+            }
         }
     }
 }
@@ -2,9 +2,11 @@ use definitions::traits::fresh_var;
 use std::collections::HashSet;
 
 mod block;
+mod errors;
 mod expression;
 mod program;
 mod statement;
+pub use errors::Error;
 
 pub trait RemoveComplexOperands {
     type Target;
@@ -29,7 +31,7 @@ fn exp_to_atm(
 
 #[cfg(test)]
 mod remove_complex_operands_tests {
-    use super::RemoveComplexOperands;
+    use super::{Error, RemoveComplexOperands};
     use definitions::{BinaryOperation, UnaryOperation};
 
     #[test]
@@ -49,10 +51,11 @@ mod remove_complex_operands_tests {
                 surface::Expression::lit(10),
             )),
         ])
-        .remove_complex_operands(&mut Default::default());
+        .remove_complex_operands(&mut Default::default())
+        .unwrap();
         let expected = monadic::Program::new(vec![
             monadic::Statement::assign(
-                "x0",
+                "x1",
                 monadic::Expression::un(monadic::Atom::Integer(10), UnaryOperation::Neg),
             ),
             monadic::Statement::assign(
@@ -60,19 +63,31 @@ mod remove_complex_operands_tests {
                 monadic::Expression::bin(
                     monadic::Atom::Integer(42),
                     BinaryOperation::Add,
-                    monadic::Atom::Variable("x0".to_owned()),
+                    monadic::Atom::Variable("x1".to_owned()),
                 ),
             ),
             monadic::Statement::assign(
-                "x1",
+                "x2",
                 monadic::Expression::bin(
                     monadic::Atom::Variable("x".to_owned()),
                     BinaryOperation::Add,
                     monadic::Atom::Integer(10),
                 ),
             ),
-            monadic::Statement::Return(monadic::Atom::Variable("x1".to_owned())),
+            monadic::Statement::Return(monadic::Atom::Variable("x2".to_owned())),
         ]);
         assert_eq!(result, expected)
     }
+
+    #[test]
+    fn remove_non_constant_global() {
+        let mut prog = surface::Program::new(vec![]);
+        prog.globals.push(surface::GlobalDecl::new(
+            "g",
+            surface::Type::Integer,
+            surface::Expression::var("x"),
+        ));
+        let result = prog.remove_complex_operands(&mut Default::default());
+        assert!(matches!(result, Err(Error::NonConstantGlobal(name)) if name == "g"))
+    }
 }
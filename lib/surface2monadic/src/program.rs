@@ -1,12 +1,21 @@
-use super::RemoveComplexOperands;
-use definitions::traits::UsedVars;
+use super::{Error, RemoveComplexOperands};
+use definitions::{Global, traits::UsedVars};
 use std::collections::HashSet;
 
 impl RemoveComplexOperands for surface::Program {
-    type Target = monadic::Program;
+    type Target = Result<monadic::Program, Error>;
     fn remove_complex_operands(self, used_vars: &mut HashSet<String>) -> Self::Target {
         used_vars.extend(self.used_vars());
         let new_main = self.main.remove_complex_operands(used_vars);
-        new_main.into()
+        let mut monadic_prog: monadic::Program = new_main.into();
+        monadic_prog.globals = self
+            .globals
+            .into_iter()
+            .map(|global| match global.init.as_constant() {
+                Some(value) => Ok(Global::new(&global.name, value)),
+                None => Err(Error::NonConstantGlobal(global.name)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(monadic_prog)
     }
 }
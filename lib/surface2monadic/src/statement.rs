@@ -1,5 +1,6 @@
 use super::{RemoveComplexOperands, exp_to_atm};
-use std::collections::HashSet;
+use definitions::UnaryOperation;
+use std::{collections::HashSet, mem::swap};
 
 impl RemoveComplexOperands for surface::Statement {
     type Target = Vec<monadic::Statement>;
@@ -12,13 +13,14 @@ impl RemoveComplexOperands for surface::Statement {
                 stmts.push(monadic::Statement::Return(atm));
                 stmts
             }
-            surface::Statement::Print(exp) => {
-                let (mut stmts, exp) = exp.remove_complex_operands(used_vars);
+            surface::Statement::Print { kind, arg } => {
+                let (mut stmts, exp) = arg.remove_complex_operands(used_vars);
                 let (assign, atm) = exp_to_atm(exp, used_vars, false);
                 stmts.push(assign);
-                stmts.push(monadic::Statement::Print(atm));
+                stmts.push(monadic::Statement::print(kind, atm));
                 stmts
             }
+            surface::Statement::PrintNewline => vec![monadic::Statement::PrintNewline],
             surface::Statement::Assignment { var, bound } => {
                 let (mut stmts, new_bind) = bound.remove_complex_operands(used_vars);
                 stmts.push(monadic::Statement::assign(&var, new_bind));
@@ -34,25 +36,38 @@ impl RemoveComplexOperands for surface::Statement {
                 then_block,
                 else_block,
             } => {
-                let (mut stmts, new_cond) = cond_exp.remove_complex_operands(used_vars);
-                let (assign, cond_atm) = exp_to_atm(new_cond, used_vars, false);
-                stmts.push(assign);
-                let new_then = then_block.remove_complex_operands(used_vars);
-                let new_else = else_block.remove_complex_operands(used_vars);
-                stmts.push(monadic::Statement::cond(cond_atm, new_then, new_else));
+                let (mut stmts, new_cond, negated) =
+                    remove_complex_cond(cond_exp, used_vars, false);
+                let mut new_then = then_block.remove_complex_operands(used_vars);
+                let mut new_else = else_block.remove_complex_operands(used_vars);
+                if negated {
+                    swap(&mut new_then, &mut new_else);
+                }
+                stmts.push(monadic::Statement::cond(new_cond, new_then, new_else));
                 stmts
             }
             surface::Statement::While {
                 cond_exp,
                 while_block,
             } => {
-                let (mut stmts, new_cond) = cond_exp.remove_complex_operands(used_vars);
-                let (assign, cond_atm) = exp_to_atm(new_cond, used_vars, true);
-                stmts.push(assign.clone());
+                let (mut cond_stmts, mut new_cond, negated) =
+                    remove_complex_cond(cond_exp, used_vars, true);
+                if negated {
+                    let cond_atm = match new_cond {
+                        monadic::Expression::Atm(atm) => atm,
+                        cond => {
+                            let (assign, atm) = exp_to_atm(cond, used_vars, true);
+                            cond_stmts.push(assign);
+                            atm
+                        }
+                    };
+                    new_cond = monadic::Expression::un(cond_atm, UnaryOperation::Not);
+                }
                 let mut new_while = while_block.remove_complex_operands(used_vars);
-                new_while.stmts.push(assign);
+                new_while.stmts.extend(cond_stmts.iter().cloned());
+                let mut stmts = cond_stmts;
                 stmts.push(monadic::Statement::While {
-                    cond: cond_atm,
+                    cond: new_cond,
                     while_block: new_while,
                 });
                 stmts
@@ -60,3 +75,29 @@ impl RemoveComplexOperands for surface::Statement {
         }
     }
 }
+
+/// Reduces a condition to a comparison or an atom
+/// Negations at the top of the condition are removed, returning whether the result is negated
+fn remove_complex_cond(
+    cond_exp: surface::Expression,
+    used_vars: &mut HashSet<String>,
+    mutable: bool,
+) -> (Vec<monadic::Statement>, monadic::Expression, bool) {
+    if let surface::Expression::UnOp {
+        arg,
+        op: UnaryOperation::Not,
+    } = cond_exp
+    {
+        let (stmts, cond, negated) = remove_complex_cond(*arg, used_vars, mutable);
+        return (stmts, cond, !negated);
+    }
+    let (mut stmts, new_cond) = cond_exp.remove_complex_operands(used_vars);
+    match new_cond {
+        monadic::Expression::Atm(_) | monadic::Expression::Cmp { .. } => (stmts, new_cond, false),
+        new_cond => {
+            let (assign, cond_atm) = exp_to_atm(new_cond, used_vars, mutable);
+            stmts.push(assign);
+            (stmts, cond_atm.into(), false)
+        }
+    }
+}
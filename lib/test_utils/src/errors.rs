@@ -10,6 +10,11 @@ pub enum Error {
     SetWorkingDir(PathBuf),
     ReadCommandOut(String),
     RunCommand(String),
+    UnknownFlag(String),
+    AcceptedInput {
+        cmd: String,
+        input: String,
+    },
     UnexpectedOutput {
         cmd: String,
         result: String,
@@ -38,6 +43,10 @@ impl fmt::Display for Error {
             Error::SetWorkingDir(path) => write!(f, "Could not set working directory to {path:?}"),
             Error::ReadCommandOut(cmd) => write!(f, "Could not read command output of {cmd}"),
             Error::RunCommand(cmd) => write!(f, "Command {cmd} exited with non-zero exit status"),
+            Error::UnknownFlag(flag) => write!(f, "Unknown compiler flag {flag}"),
+            Error::AcceptedInput { cmd, input } => {
+                write!(f, "Command {cmd} exited successfully on input {input:?}")
+            }
             Error::UnexpectedOutput {
                 cmd,
                 result,
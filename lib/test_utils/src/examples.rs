@@ -8,6 +8,8 @@ pub struct Example {
     pub source: String,
     pub path: PathBuf,
     pub expected: String,
+    pub input: Option<String>,
+    pub link: Vec<PathBuf>,
 }
 
 pub fn load_examples() -> Result<Vec<Example>, Error> {
@@ -33,6 +35,24 @@ pub fn load_examples() -> Result<Vec<Example>, Error> {
             .trim()
             .to_owned();
 
+        let mut example_input = example_dir.join(example_base);
+        example_input.set_extension("in");
+        let input = if example_input.exists() {
+            Some(read_to_string(&example_input).map_err(|_| Error::FileAccess(example_input))?)
+        } else {
+            None
+        };
+
+        let mut link = vec![];
+        let example_files =
+            read_dir(&example_dir).map_err(|_| Error::ReadDir(format!("{example_dir:?}")))?;
+        for file in example_files {
+            let file = file.map_err(|_| Error::ReadDir(format!("{example_dir:?}")))?;
+            if file.path().extension().is_some_and(|ext| ext == "c") {
+                link.push(file.path());
+            }
+        }
+
         examples.push(Example {
             name: example_base
                 .to_str()
@@ -41,6 +61,8 @@ pub fn load_examples() -> Result<Vec<Example>, Error> {
             source: example_contents,
             path: example_source,
             expected,
+            input,
+            link,
         });
     }
     Ok(examples)
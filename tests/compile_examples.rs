@@ -1,34 +1,151 @@
 use driver::Driver;
-use std::process::Command;
-use test_utils::{Error, load_examples, set_working_dir};
+use std::{
+    io::Write,
+    path::Path,
+    process::{Command, Output, Stdio},
+};
+use test_utils::{Error, Example, load_examples, set_working_dir};
+
+/// Examples compiled again with only the given number of registers, to force spilling
+const RESTRICTED_REGISTERS: [(&str, usize); 1] = [("extern_call", 2)];
+
+/// Every example is compiled and checked once with each of these command line flags
+const CONFIGURATIONS: [&[&str]; 7] = [
+    &[],
+    &["-O0"],
+    &["-O2"],
+    &["--ssa"],
+    &["--cse"],
+    &["--linear-scan"],
+    &["-O2", "--linear-scan", "--regs=2"],
+];
+
+/// Inputs that examples reading from stdin must reject with a non-zero exit status
+const MALFORMED_INPUTS: [(&str, &str); 5] = [
+    ("read_bool", "yes 5"),
+    ("read_bool", "true five"),
+    ("read_bool", "true"),
+    ("read_bool", ""),
+    ("callee_saved", "1 2 3"),
+];
 
 fn main() -> Result<(), Error> {
     set_working_dir()?;
     let examples = load_examples()?;
-    for example in examples {
-        let compiler = Driver::new(false, example.path, None, None, None)?;
-        let exe_path = compiler.paths.exe_out.clone();
-        println!("Compiling {}", example.name);
-        compiler.run()?;
-        println!("\t...Ok");
-        println!("Checking output of {}", example.name);
-        let mut check_cmd = Command::new(&exe_path);
-        let output = check_cmd
-            .output()
-            .map_err(|_| Error::ReadCommandOut(format!("{:?}", exe_path)))?;
-        if !output.status.success() {
-            return Err(Error::RunCommand(format!("{:?}", exe_path)));
+    for flags in CONFIGURATIONS {
+        for example in examples.iter() {
+            check_example(example, flags)?;
         }
-        let result = str::from_utf8(&output.stdout)
-            .map_err(|_| Error::ReadCommandOut(format!("{:?}", exe_path)))?;
-        if result != example.expected {
-            return Err(Error::unexpected(
-                format!("{:?}", exe_path),
-                &result,
-                &example.expected,
-            ));
+    }
+    for (name, registers) in RESTRICTED_REGISTERS {
+        let flag = format!("--regs={registers}");
+        check_example(find_example(&examples, name)?, &[flag.as_str()])?;
+    }
+    for (name, input) in MALFORMED_INPUTS {
+        check_rejects(find_example(&examples, name)?, input)?;
+    }
+    Ok(())
+}
+
+fn find_example<'a>(examples: &'a [Example], name: &str) -> Result<&'a Example, Error> {
+    examples
+        .iter()
+        .find(|example| example.name == name)
+        .ok_or(Error::FileAccess(name.into()))
+}
+
+/// Applies flags as the command line interface would
+fn configure(compiler: &mut Driver, flags: &[&str]) -> Result<(), Error> {
+    for flag in flags {
+        if let Some(level) = flag.strip_prefix("-O") {
+            let level = level
+                .parse()
+                .map_err(|_| Error::UnknownFlag(flag.to_string()))?;
+            compiler.passes.set_level(level);
+        } else if let Some(registers) = flag.strip_prefix("--regs=") {
+            let registers = registers
+                .parse()
+                .map_err(|_| Error::UnknownFlag(flag.to_string()))?;
+            compiler.passes.set_registers(registers)?;
+        } else {
+            match *flag {
+                "--ssa" => compiler.passes.enable("ssa")?,
+                "--cse" => compiler.passes.enable("cse")?,
+                "--linear-scan" => compiler.passes.linear_scan = true,
+                _ => return Err(Error::UnknownFlag(flag.to_string())),
+            }
         }
-        println!("\t...Ok");
     }
     Ok(())
 }
+
+fn check_example(example: &Example, flags: &[&str]) -> Result<(), Error> {
+    let mut compiler = Driver::new(false, example.path.clone(), None, None, None)?;
+    compiler.paths.link_inputs = example.link.clone();
+    configure(&mut compiler, flags)?;
+    let exe_path = compiler.paths.exe_out.clone();
+    if flags.is_empty() {
+        println!("Compiling {}", example.name);
+    } else {
+        println!("Compiling {} with {}", example.name, flags.join(" "));
+    }
+    compiler.run()?;
+    println!("\t...Ok");
+    println!("Checking output of {}", example.name);
+    let output = run(&exe_path, example.input.as_deref().unwrap_or_default())?;
+    if !output.status.success() {
+        return Err(Error::RunCommand(format!("{:?}", exe_path)));
+    }
+    let result = str::from_utf8(&output.stdout)
+        .map_err(|_| Error::ReadCommandOut(format!("{:?}", exe_path)))?;
+    if result.trim() != example.expected {
+        return Err(Error::unexpected(
+            format!("{:?}", exe_path),
+            result,
+            &example.expected,
+        ));
+    }
+    println!("\t...Ok");
+    Ok(())
+}
+
+fn check_rejects(example: &Example, input: &str) -> Result<(), Error> {
+    let mut compiler = Driver::new(false, example.path.clone(), None, None, None)?;
+    compiler.paths.link_inputs = example.link.clone();
+    let exe_path = compiler.paths.exe_out.clone();
+    println!("Compiling {}", example.name);
+    compiler.run()?;
+    println!("\t...Ok");
+    println!("Checking {} rejects {input:?}", example.name);
+    let output = run(&exe_path, input)?;
+    if output.status.success() {
+        return Err(Error::AcceptedInput {
+            cmd: format!("{:?}", exe_path),
+            input: input.to_owned(),
+        });
+    }
+    println!("\t...Ok");
+    Ok(())
+}
+
+fn run(exe_path: &Path, input: &str) -> Result<Output, Error> {
+    let mut check_cmd = Command::new(exe_path);
+    check_cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = check_cmd
+        .spawn()
+        .map_err(|_| Error::RunCommand(format!("{:?}", exe_path)))?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or(Error::RunCommand(format!("{:?}", exe_path)))?;
+    stdin
+        .write_all(input.as_bytes())
+        .map_err(|_| Error::RunCommand(format!("{:?}", exe_path)))?;
+    drop(stdin);
+    child
+        .wait_with_output()
+        .map_err(|_| Error::ReadCommandOut(format!("{:?}", exe_path)))
+}